@@ -17,7 +17,7 @@ fn codegen_benchmark(c: &mut Criterion) {
             };
 
             let mut bindings_helper = fervid_transform::BindingsHelper::default();
-            fervid_transform::template::transform_and_record_template(template_block, &mut bindings_helper);
+            fervid_transform::template::transform_and_record_template(template_block, &mut bindings_helper, Default::default());
 
             b.iter_batched(
                 || template_block.clone(),
@@ -0,0 +1,67 @@
+//! An example of a plugin loaded by `fervid` from a dynamic library.
+//! It strips the things only needed for development:
+//! - `<docs>` blocks;
+//! - `data-testid` attributes in the `<template>`;
+//! - `debugger` statements in the `<script>`.
+//!
+//! Build it using `cargo build --example strip_debug_plugin`,
+//! and pass the path to the library in `CompileOptions::plugins`.
+
+use fervid_core::{
+    check_attribute_name,
+    visit::{VisitMut, VisitMutWith},
+    SfcDescriptor, StartingTag,
+};
+use fervid_plugin_api::{declare_plugin, Plugin, TransformedSfc};
+use swc_core::ecma::{
+    ast::{Module, ModuleItem, Stmt},
+    visit::{VisitMut as SwcVisitMut, VisitMutWith as SwcVisitMutWith},
+};
+
+struct StripDebugPlugin;
+
+impl Plugin for StripDebugPlugin {
+    fn name(&self) -> &str {
+        "strip-debug"
+    }
+
+    fn on_parse(&self, sfc: &mut SfcDescriptor) {
+        sfc.custom_blocks
+            .retain(|block| block.starting_tag.tag_name != "docs");
+    }
+
+    fn on_transform(&self, transformed_sfc: &mut TransformedSfc) {
+        if let Some(ref mut template_block) = transformed_sfc.template_block {
+            template_block.visit_mut_with(&mut TestIdStripper);
+        }
+    }
+
+    fn on_module(&self, module: &mut Module) {
+        module.visit_mut_with(&mut DebuggerStripper);
+    }
+}
+
+struct TestIdStripper;
+
+impl VisitMut for TestIdStripper {
+    fn visit_mut_starting_tag(&mut self, n: &mut StartingTag) {
+        n.attributes
+            .retain(|attr| !check_attribute_name(attr, "data-testid"));
+    }
+}
+
+struct DebuggerStripper;
+
+impl SwcVisitMut for DebuggerStripper {
+    fn visit_mut_module_items(&mut self, n: &mut Vec<ModuleItem>) {
+        n.retain(|item| !matches!(item, ModuleItem::Stmt(Stmt::Debugger(_))));
+        n.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_stmts(&mut self, n: &mut Vec<Stmt>) {
+        n.retain(|stmt| !matches!(stmt, Stmt::Debugger(_)));
+        n.visit_mut_children_with(self);
+    }
+}
+
+declare_plugin!(StripDebugPlugin);
@@ -0,0 +1,45 @@
+#![no_main]
+
+use std::borrow::Cow;
+
+use arbitrary::Arbitrary;
+use fervid::{compile, CompileOptions, ParseMode};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+struct Input<'a> {
+    source: &'a str,
+    is_prod: bool,
+    source_map: bool,
+    parse_mode: u8,
+}
+
+fuzz_target!(|input: Input| {
+    let parse_mode = match input.parse_mode % 4 {
+        0 => ParseMode::HardFailOnError,
+        1 => ParseMode::SoftReportAll,
+        2 => ParseMode::SoftRecoverSafe,
+        _ => ParseMode::SoftRecoverUnsafe,
+    };
+
+    let options = CompileOptions {
+        filename: Cow::Borrowed("anonymous.vue"),
+        id: Cow::Borrowed("data-v-fuzz"),
+        is_prod: Some(input.is_prod),
+        source_map: Some(input.source_map),
+        parse_mode: Some(parse_mode),
+        ..Default::default()
+    };
+
+    // Errors are fine, panics are not
+    match compile(input.source, options) {
+        Ok(result) => {
+            for error in result.errors {
+                let _ = error.to_diagnostic_string(input.source, "anonymous.vue");
+            }
+        }
+        Err(error) => {
+            let _ = error.to_diagnostic_string(input.source, "anonymous.vue");
+        }
+    }
+});
@@ -0,0 +1,126 @@
+//! Compiling many SFCs at once in parallel
+
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex},
+};
+
+use fxhash::FxHashMap as HashMap;
+use lazy_static::lazy_static;
+use rayon::{
+    iter::{IntoParallelRefIterator, ParallelIterator},
+    ThreadPool, ThreadPoolBuilder,
+};
+
+use crate::{compile, errors::CompileError, CompileOptions, CompileResult};
+
+lazy_static! {
+    /// Pools for the explicitly requested thread counts, they are reused between the calls
+    static ref THREAD_POOLS: Mutex<HashMap<usize, Arc<ThreadPool>>> =
+        Mutex::new(HashMap::default());
+}
+
+/// A single SFC for [`compile_many`]
+#[derive(Debug, Clone)]
+pub struct CompileManyInput<'i> {
+    pub source: Cow<'i, str>,
+    /// Options of this SFC, e.g. its own `filename` and `id`.
+    /// For the options shared by all SFCs, clone them using `..shared_options.clone()`
+    pub options: CompileOptions<'i>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CompileManyOptions {
+    /// Number of threads to compile with.
+    /// Defaults to the global `rayon` pool, which has a thread per CPU
+    pub threads: Option<usize>,
+}
+
+/// Compiles the SFCs in parallel using a `rayon` thread pool.
+///
+/// Results are in the same order as `inputs`. An `Err` of one SFC does not affect the others.
+pub fn compile_many(
+    inputs: &[CompileManyInput],
+    options: &CompileManyOptions,
+) -> Vec<Result<CompileResult, CompileError>> {
+    let compile_all = || {
+        inputs
+            .par_iter()
+            .map(|input| compile(&input.source, input.options.to_owned()))
+            .collect()
+    };
+
+    match options.threads.and_then(thread_pool) {
+        Some(pool) => pool.install(compile_all),
+        None => compile_all(),
+    }
+}
+
+/// Gets or creates a pool with `threads` threads.
+/// When the pool cannot be created, the global one is used instead
+fn thread_pool(threads: usize) -> Option<Arc<ThreadPool>> {
+    let mut pools = THREAD_POOLS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(pool) = pools.get(&threads) {
+        return Some(pool.clone());
+    }
+
+    let pool = Arc::new(ThreadPoolBuilder::new().num_threads(threads).build().ok()?);
+    pools.insert(threads, pool.clone());
+
+    Some(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(source: &str, filename: &str) -> CompileManyInput<'static> {
+        CompileManyInput {
+            source: Cow::Owned(source.to_string()),
+            options: CompileOptions {
+                filename: Cow::Owned(filename.to_string()),
+                is_prod: Some(true),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn it_compiles_many_in_order() {
+        let inputs: Vec<_> = (0..32)
+            .map(|i| {
+                input(
+                    &format!("<template><div>component {i}</div></template>"),
+                    &format!("Component{i}.vue"),
+                )
+            })
+            .collect();
+
+        for threads in [None, Some(1), Some(4)] {
+            let results = compile_many(&inputs, &CompileManyOptions { threads });
+            assert_eq!(inputs.len(), results.len());
+
+            for (i, result) in results.into_iter().enumerate() {
+                let result = result.unwrap();
+                assert!(result.code.contains(&format!("\"component {i}\"")));
+            }
+        }
+    }
+
+    #[test]
+    fn it_keeps_errors_per_input() {
+        let mut broken = input("<template><div></template>", "Broken.vue");
+        broken.options.parse_mode = Some(crate::ParseMode::HardFailOnError);
+        let inputs = vec![
+            input("<template><div>ok</div></template>", "Ok.vue"),
+            broken,
+        ];
+
+        let results = compile_many(&inputs, &CompileManyOptions { threads: Some(2) });
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+}
@@ -0,0 +1,653 @@
+//! An opt-in cache of the compilation results, either in memory or persisted on disk
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    mem::discriminant,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use fervid_core::{BindingTypes, FervidAtom};
+use fxhash::{FxHashMap as HashMap, FxHasher64};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compile, errors::CompileError, CompileEmittedAsset, CompileEmittedStyle, CompileOptions,
+    CompileResult, SetupBinding,
+};
+
+/// Bumped whenever [`CacheEntry`] changes, so that the old entries on disk are not read
+const CACHE_FORMAT_VERSION: u32 = 3;
+
+/// Which part of the cache is evicted when it grows over `max_size`
+const EVICT_TO_RATIO: f64 = 0.9;
+
+#[derive(Debug, Clone)]
+pub struct CompileCacheOptions {
+    /// Directory to persist the entries to, one file per entry.
+    /// When `None`, the entries are only kept in memory
+    pub directory: Option<PathBuf>,
+    /// Maximum total size of the entries in bytes.
+    /// The least recently used entries are evicted when it is exceeded
+    pub max_size: u64,
+    /// Becomes a part of every key. It must change whenever the options which cannot be hashed change,
+    /// i.e. `is_custom_element_tag` or the behavior of `template_plugins` (their names are hashed)
+    pub salt: String,
+}
+
+/// Caches the results of [`compile`] by the hash of the source, the options
+/// and the version of `fervid`, so that the unchanged SFCs are not compiled again,
+/// e.g. after the dev server is restarted.
+///
+/// An entry also remembers the content hashes of the files the compilation depended on
+/// (see [`CompileResult::dependencies`] and the plugin libraries),
+/// and is only used while these files are unchanged.
+/// Only the results without errors are cached, so that the diagnostics are always reported.
+pub struct CompileCache {
+    options: CompileCacheOptions,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    slots: HashMap<u64, CacheSlot>,
+    /// Total size of the entries
+    size: u64,
+    /// Incremented on each access, for finding the least recently used entries
+    clock: u64,
+    /// Content hashes of the dependencies, only computed again when a file is modified
+    file_hashes: HashMap<PathBuf, FileHash>,
+}
+
+struct FileHash {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+struct CacheSlot {
+    size: u64,
+    last_used: u64,
+    /// Serialized [`CacheEntry`], only present for the in-memory cache
+    content: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// Hash of the same input as the key, but by another hasher,
+    /// so that a collision of the keys is not mistaken for a hit
+    input_hash: u64,
+    code: String,
+    file_hash: String,
+    source_map: Option<String>,
+    is_ts: bool,
+    styles: Vec<CompileEmittedStyle>,
+    other_assets: Vec<CompileEmittedAsset>,
+    setup_bindings: Vec<(FervidAtom, BindingTypes)>,
+    /// [`CompileResult::dependencies`]
+    dependencies: Vec<String>,
+    /// Paths and content hashes of the files the compilation depended on,
+    /// including the plugin libraries
+    file_hashes: Vec<(String, u64)>,
+}
+
+impl CompileCache {
+    /// Creates the cache. For the on-disk cache, the directory is created
+    /// and the entries already in it are picked up
+    pub fn new(options: CompileCacheOptions) -> io::Result<Self> {
+        let mut state = CacheState::default();
+
+        if let Some(ref directory) = options.directory {
+            fs::create_dir_all(directory)?;
+
+            // Older files are considered less recently used
+            let mut existing = Vec::new();
+            for dir_entry in fs::read_dir(directory)? {
+                let dir_entry = dir_entry?;
+                let file_name = dir_entry.file_name();
+                let Some(key) = file_name
+                    .to_str()
+                    .and_then(|name| name.strip_suffix(".json"))
+                    .and_then(|stem| u64::from_str_radix(stem, 16).ok())
+                else {
+                    continue;
+                };
+
+                let metadata = dir_entry.metadata()?;
+                existing.push((metadata.modified().ok(), key, metadata.len()));
+            }
+            existing.sort();
+
+            for (_, key, size) in existing {
+                state.clock += 1;
+                state.size += size;
+                state.slots.insert(
+                    key,
+                    CacheSlot {
+                        size,
+                        last_used: state.clock,
+                        content: None,
+                    },
+                );
+            }
+        }
+
+        let cache = CompileCache {
+            options,
+            state: Mutex::new(state),
+        };
+        cache.evict(&mut cache.lock());
+
+        Ok(cache)
+    }
+
+    /// Same as [`compile`], but returns the cached result when there is one
+    pub fn compile(
+        &self,
+        source: &str,
+        options: CompileOptions,
+    ) -> Result<CompileResult, CompileError> {
+        let key = self.key(source, &options);
+        let input_hash = self.input_hash(source, &options);
+
+        if let Some(entry) = self.get(key, input_hash) {
+            return Ok(entry.into());
+        }
+
+        let result = compile(source, options.to_owned())?;
+        if result.errors.is_empty() {
+            let plugins = options.plugins.iter().map(|plugin| plugin.as_ref());
+            self.insert(key, input_hash, &result, plugins);
+        }
+
+        Ok(result)
+    }
+
+    /// Removes all the entries, including the ones on disk
+    pub fn clear(&self) -> io::Result<()> {
+        let mut state = self.lock();
+        let keys: Vec<u64> = state.slots.keys().copied().collect();
+        for key in keys {
+            self.remove(&mut state, key)?;
+        }
+
+        Ok(())
+    }
+
+    /// Total size of the cached entries in bytes
+    pub fn size(&self) -> u64 {
+        self.lock().size
+    }
+
+    fn key(&self, source: &str, options: &CompileOptions) -> u64 {
+        let mut hasher = FxHasher64::default();
+        self.hash_input(source, options, &mut hasher);
+        hasher.finish()
+    }
+
+    /// Stored in the entry and compared on [`CompileCache::get`]
+    fn input_hash(&self, source: &str, options: &CompileOptions) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash_input(source, options, &mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_input(&self, source: &str, options: &CompileOptions, hasher: &mut impl Hasher) {
+        CACHE_FORMAT_VERSION.hash(hasher);
+        env!("CARGO_PKG_VERSION").hash(hasher);
+        self.options.salt.hash(hasher);
+        source.hash(hasher);
+
+        // Options
+        options.filename.hash(hasher);
+        options.id.hash(hasher);
+        options.is_prod.hash(hasher);
+        options.is_custom_element.hash(hasher);
+        options.ssr.hash(hasher);
+        options
+            .props_destructure
+            .as_ref()
+            .map(discriminant)
+            .hash(hasher);
+        options.in_source_map.hash(hasher);
+        options.delimiters.hash(hasher);
+        options.whitespace.as_ref().map(discriminant).hash(hasher);
+        options.is_custom_element_tag.is_some().hash(hasher);
+        options.comments.hash(hasher);
+        options.gen_default_as.hash(hasher);
+        options.source_map.hash(hasher);
+        options.parse_mode.as_ref().map(discriminant).hash(hasher);
+        for template_plugin in options.template_plugins.iter() {
+            template_plugin.name().hash(hasher);
+        }
+        options.plugins.hash(hasher);
+    }
+
+    fn get(&self, key: u64, input_hash: u64) -> Option<CacheEntry> {
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let slot = state.slots.get_mut(&key)?;
+        slot.last_used = clock;
+
+        let entry = match slot.content {
+            Some(ref content) => serde_json::from_slice::<CacheEntry>(content).ok(),
+            None => fs::read(self.entry_path(key)?)
+                .ok()
+                .and_then(|content| serde_json::from_slice::<CacheEntry>(&content).ok()),
+        };
+
+        match entry {
+            Some(entry)
+                if entry.input_hash == input_hash
+                    && state.are_files_unchanged(&entry.file_hashes) =>
+            {
+                Some(entry)
+            }
+            _ => {
+                // Outdated, unreadable, or of another input with the same key
+                let _ = self.remove(&mut state, key);
+                None
+            }
+        }
+    }
+
+    /// Inserts the result, which also depends on the `extra_files` besides [`CompileResult::dependencies`]
+    fn insert<'d>(
+        &self,
+        key: u64,
+        input_hash: u64,
+        result: &'d CompileResult,
+        extra_files: impl Iterator<Item = &'d str>,
+    ) {
+        let mut state = self.lock();
+
+        // Changes to a file which cannot be read would go unnoticed, so the result is not cached
+        let Some(file_hashes) = result
+            .dependencies
+            .iter()
+            .map(|path| path.as_str())
+            .chain(extra_files)
+            .map(|path| Some((path.to_string(), state.hash_file(Path::new(path))?)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+
+        let Ok(content) = serde_json::to_vec(&CacheEntry::new(input_hash, result, file_hashes))
+        else {
+            return;
+        };
+        let size = content.len() as u64;
+
+        let _ = self.remove(&mut state, key);
+
+        let content = match self.entry_path(key) {
+            Some(path) => {
+                // Written to a temporary file first, so that a partially written entry is never read
+                let mut temp_path = path.clone().into_os_string();
+                temp_path.push(format!(".{}.tmp", std::process::id()));
+                let is_written = fs::write(&temp_path, &content)
+                    .and_then(|_| fs::rename(&temp_path, &path))
+                    .is_ok();
+                if !is_written {
+                    let _ = fs::remove_file(&temp_path);
+                    return;
+                }
+                None
+            }
+            None => Some(content),
+        };
+
+        state.clock += 1;
+        state.size += size;
+        let last_used = state.clock;
+        state.slots.insert(
+            key,
+            CacheSlot {
+                size,
+                last_used,
+                content,
+            },
+        );
+
+        self.evict(&mut state);
+    }
+
+    /// Removes the least recently used entries when the cache is over `max_size`
+    fn evict(&self, state: &mut CacheState) {
+        if state.size <= self.options.max_size {
+            return;
+        }
+
+        let target_size = (self.options.max_size as f64 * EVICT_TO_RATIO) as u64;
+        let mut by_last_used: Vec<(u64, u64)> = state
+            .slots
+            .iter()
+            .map(|(key, slot)| (slot.last_used, *key))
+            .collect();
+        by_last_used.sort_unstable();
+
+        for (_, key) in by_last_used {
+            if state.size <= target_size {
+                break;
+            }
+            let _ = self.remove(state, key);
+        }
+    }
+
+    fn remove(&self, state: &mut CacheState, key: u64) -> io::Result<()> {
+        let Some(slot) = state.slots.remove(&key) else {
+            return Ok(());
+        };
+        state.size -= slot.size;
+
+        match self.entry_path(key) {
+            Some(path) => match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    fn entry_path(&self, key: u64) -> Option<PathBuf> {
+        self.options
+            .directory
+            .as_ref()
+            .map(|directory| directory.join(format!("{:016x}.json", key)))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl std::fmt::Debug for CompileCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompileCache")
+            .field("options", &self.options)
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+impl CacheState {
+    fn hash_file(&mut self, path: &Path) -> Option<u64> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok();
+        let len = metadata.len();
+
+        if let Some(file_hash) = self.file_hashes.get(path) {
+            if modified.is_some() && file_hash.modified == modified && file_hash.len == len {
+                return Some(file_hash.hash);
+            }
+        }
+
+        let mut hasher = FxHasher64::default();
+        fs::read(path).ok()?.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        self.file_hashes.insert(
+            path.to_path_buf(),
+            FileHash {
+                modified,
+                len,
+                hash,
+            },
+        );
+
+        Some(hash)
+    }
+
+    fn are_files_unchanged(&mut self, file_hashes: &[(String, u64)]) -> bool {
+        file_hashes
+            .iter()
+            .all(|(path, hash)| self.hash_file(Path::new(path)) == Some(*hash))
+    }
+}
+
+impl CacheEntry {
+    fn new(input_hash: u64, result: &CompileResult, file_hashes: Vec<(String, u64)>) -> Self {
+        CacheEntry {
+            input_hash,
+            code: result.code.to_owned(),
+            file_hash: result.file_hash.to_owned(),
+            source_map: result.source_map.to_owned(),
+            is_ts: result.is_ts,
+            styles: result.styles.to_owned(),
+            other_assets: result.other_assets.to_owned(),
+            setup_bindings: result
+                .setup_bindings
+                .iter()
+                .map(|SetupBinding(name, binding_type)| (name.to_owned(), *binding_type))
+                .collect(),
+            dependencies: result.dependencies.to_owned(),
+            file_hashes,
+        }
+    }
+}
+
+impl From<CacheEntry> for CompileResult {
+    fn from(entry: CacheEntry) -> Self {
+        CompileResult {
+            code: entry.code,
+            file_hash: entry.file_hash,
+            errors: vec![],
+            styles: entry.styles,
+            other_assets: entry.other_assets,
+            source_map: entry.source_map,
+            setup_bindings: entry
+                .setup_bindings
+                .into_iter()
+                .map(|(name, binding_type)| SetupBinding(name, binding_type))
+                .collect(),
+            is_ts: entry.is_ts,
+            dependencies: entry.dependencies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    const SOURCE: &str = r#"<template><div>{{ msg }}</div></template>
+<script setup>
+const msg = 'Hello'
+</script>
+<style scoped>div { color: red }</style>"#;
+
+    fn options() -> CompileOptions<'static> {
+        CompileOptions {
+            filename: Cow::Borrowed("Hello.vue"),
+            is_prod: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("fervid-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        path
+    }
+
+    #[test]
+    fn it_caches_in_memory() {
+        let cache = CompileCache::new(CompileCacheOptions {
+            directory: None,
+            max_size: 1 << 20,
+            salt: String::new(),
+        })
+        .unwrap();
+
+        let compiled = cache.compile(SOURCE, options()).unwrap();
+        assert!(cache.size() > 0);
+
+        let cached = cache.compile(SOURCE, options()).unwrap();
+        assert_eq!(compiled.code, cached.code);
+        assert_eq!(compiled.file_hash, cached.file_hash);
+        assert_eq!(compiled.styles[0].code, cached.styles[0].code);
+        assert_eq!(compiled.setup_bindings, cached.setup_bindings);
+
+        // Different options are a different entry
+        let size = cache.size();
+        let prod = cache
+            .compile(
+                SOURCE,
+                CompileOptions {
+                    is_prod: Some(true),
+                    ..options()
+                },
+            )
+            .unwrap();
+        assert_ne!(compiled.code, prod.code);
+        assert!(cache.size() > size);
+    }
+
+    #[test]
+    fn it_persists_on_disk() {
+        let directory = temp_dir("disk");
+        let cache_options = CompileCacheOptions {
+            directory: Some(directory.to_owned()),
+            max_size: 1 << 20,
+            salt: String::new(),
+        };
+
+        let compiled = {
+            let cache = CompileCache::new(cache_options.to_owned()).unwrap();
+            cache.compile(SOURCE, options()).unwrap()
+        };
+
+        // A new cache, e.g. after a restart
+        let cache = CompileCache::new(cache_options).unwrap();
+        assert!(cache.size() > 0);
+        let key = cache.key(SOURCE, &options());
+        let input_hash = cache.input_hash(SOURCE, &options());
+        let cached: CompileResult = cache.get(key, input_hash).unwrap().into();
+        assert_eq!(compiled.code, cached.code);
+
+        cache.clear().unwrap();
+        assert_eq!(0, cache.size());
+        assert_eq!(0, fs::read_dir(&directory).unwrap().count());
+
+        let _ = fs::remove_dir_all(directory);
+    }
+
+    #[test]
+    fn it_checks_the_input_of_an_entry() {
+        let cache = CompileCache::new(CompileCacheOptions {
+            directory: None,
+            max_size: 1 << 20,
+            salt: String::new(),
+        })
+        .unwrap();
+        cache.compile(SOURCE, options()).unwrap();
+
+        // Another input with the same key, e.g. a collision of the keys
+        let key = cache.key(SOURCE, &options());
+        let other_input_hash = cache.input_hash("<template></template>", &options());
+        assert!(cache.get(key, other_input_hash).is_none());
+        assert_eq!(0, cache.size());
+    }
+
+    #[test]
+    fn it_does_not_cache_errors() {
+        let cache = CompileCache::new(CompileCacheOptions {
+            directory: None,
+            max_size: 1 << 20,
+            salt: String::new(),
+        })
+        .unwrap();
+
+        let result = cache
+            .compile("<template><div v-if=\">\"></div></template>", options())
+            .unwrap();
+        assert!(!result.errors.is_empty());
+        assert_eq!(0, cache.size());
+    }
+
+    #[test]
+    fn it_evicts_least_recently_used() {
+        let cache = CompileCache::new(CompileCacheOptions {
+            directory: None,
+            max_size: 1 << 20,
+            salt: String::new(),
+        })
+        .unwrap();
+
+        let sources: Vec<String> = (0..3)
+            .map(|i| format!("<template><div>{}</div></template>", i))
+            .collect();
+        cache.compile(&sources[0], options()).unwrap();
+        let entry_size = cache.size();
+
+        // Only fits two entries
+        let cache = CompileCache {
+            options: CompileCacheOptions {
+                max_size: entry_size * 2 + entry_size / 2,
+                ..cache.options.to_owned()
+            },
+            state: Mutex::new(CacheState::default()),
+        };
+        for source in sources.iter() {
+            cache.compile(source, options()).unwrap();
+        }
+
+        let state = cache.lock();
+        assert_eq!(2, state.slots.len());
+        assert!(!state
+            .slots
+            .contains_key(&cache.key(&sources[0], &options())));
+    }
+
+    #[test]
+    fn it_invalidates_on_dependency_change() {
+        let directory = temp_dir("dependency");
+        fs::create_dir_all(&directory).unwrap();
+        let dependency = directory.join("types.ts");
+        fs::write(&dependency, "export interface Props { foo: string }").unwrap();
+
+        let source = "<script setup lang=\"ts\">\nimport type { Props } from './types'\ndefineProps<Props>()\n</script>";
+        let filename = directory.join("Hello.vue");
+        let options = || CompileOptions {
+            filename: Cow::Owned(filename.to_string_lossy().into_owned()),
+            ..options()
+        };
+
+        let cache = CompileCache::new(CompileCacheOptions {
+            directory: None,
+            max_size: 1 << 20,
+            salt: String::new(),
+        })
+        .unwrap();
+
+        let compiled = cache.compile(source, options()).unwrap();
+        assert_eq!(
+            vec![dependency.to_string_lossy().into_owned()],
+            compiled.dependencies
+        );
+        let key = cache.key(source, &options());
+        let input_hash = cache.input_hash(source, &options());
+        assert!(cache.get(key, input_hash).is_some());
+
+        fs::write(&dependency, "export interface Props { foo: number | null }").unwrap();
+        assert!(cache.get(key, input_hash).is_none());
+
+        let recompiled = cache.compile(source, options()).unwrap();
+        assert_ne!(compiled.code, recompiled.code);
+        assert!(recompiled.code.contains("Number"));
+
+        let _ = fs::remove_dir_all(directory);
+    }
+}
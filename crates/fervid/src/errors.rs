@@ -1,8 +1,10 @@
 //! Error definitions for the glue code of `fervid`
 
+use fervid_core::error::{render_diagnostic, ErrorCode, Severity, SeverityLevel};
 use fervid_parser::ParseError as SfcParseError;
+use fervid_plugin::PluginError;
 use fervid_transform::error::TransformError;
-use swc_core::common::Spanned;
+use swc_core::common::{Spanned, DUMMY_SP};
 
 #[derive(Debug)]
 pub enum CompileError {
@@ -18,12 +20,56 @@ pub enum CompileError {
     SfcParse(SfcParseError),
 
     /// An error during the transformation of an SFC.
-    TransformError(TransformError)
+    TransformError(TransformError),
+
+    /// The input source map passed in options could not be parsed.
+    /// Compilation still succeeds, but the output source map is not composed with it.
+    InvalidInputSourceMap(sourcemap::Error),
+
+    /// A plugin from [`crate::CompileOptions::plugins`] could not be loaded or has panicked.
+    Plugin(PluginError),
+}
+
+impl CompileError {
+    /// Renders the error with its severity, code, location and a code frame.
+    /// `source` must be the same SFC source which was passed to `compile`.
+    pub fn to_diagnostic_string(&self, source: &str, filename: &str) -> String {
+        render_diagnostic(self, self.span(), source, filename)
+    }
 }
 
 impl std::fmt::Display for CompileError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            CompileError::SfcParse(e) => e.fmt(f),
+            CompileError::TransformError(e) => e.fmt(f),
+            CompileError::InvalidInputSourceMap(e) => {
+                write!(f, "Invalid input source map: {}", e)
+            }
+            CompileError::Plugin(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Severity for CompileError {
+    fn get_severity(&self) -> SeverityLevel {
+        match self {
+            CompileError::SfcParse(e) => e.get_severity(),
+            CompileError::TransformError(e) => e.get_severity(),
+            CompileError::InvalidInputSourceMap(_) => SeverityLevel::Warning,
+            CompileError::Plugin(e) => e.get_severity(),
+        }
+    }
+}
+
+impl ErrorCode for CompileError {
+    fn error_code(&self) -> &'static str {
+        match self {
+            CompileError::SfcParse(e) => e.error_code(),
+            CompileError::TransformError(e) => e.error_code(),
+            CompileError::InvalidInputSourceMap(_) => "INVALID_INPUT_SOURCE_MAP",
+            CompileError::Plugin(e) => e.error_code(),
+        }
     }
 }
 
@@ -33,6 +79,12 @@ impl From<SfcParseError> for CompileError {
     }
 }
 
+impl From<PluginError> for CompileError {
+    fn from(value: PluginError) -> Self {
+        Self::Plugin(value)
+    }
+}
+
 impl From<TransformError> for CompileError {
     fn from(value: TransformError) -> Self {
         Self::TransformError(value)
@@ -43,7 +95,8 @@ impl Spanned for CompileError {
     fn span(&self) -> swc_core::common::Span {
         match self {
             CompileError::SfcParse(e) => e.span,
-            CompileError::TransformError(e) => e.span()
+            CompileError::TransformError(e) => e.span(),
+            CompileError::InvalidInputSourceMap(_) | CompileError::Plugin(_) => DUMMY_SP,
         }
     }
 }
@@ -0,0 +1,543 @@
+//! Compilation of the string templates inside JS/TS modules.
+//!
+//! Components which are not SFCs can still declare a template as a string,
+//! e.g. `defineComponent({ template: '<div>{{ msg }}</div>' })`.
+//! Such templates need the full build of Vue which compiles them at runtime.
+//! [`compile_js_module`] compiles them ahead of time into `render` functions,
+//! so that the runtime-only build can be used instead.
+
+use std::hash::{Hash, Hasher};
+
+use fervid_codegen::CodegenContext;
+use fervid_core::{
+    fervid_atom, FervidAtom, SfcTemplateBlock, TemplateGenerationMode, VueImportsSet,
+};
+use fervid_parser::{SfcParser, INTERPOLATION_END_PAT_DEFAULT, INTERPOLATION_START_PAT_DEFAULT};
+use fervid_transform::{
+    error::TransformError, script::analyze_options_object, template::transform_and_record_template,
+    BindingsHelper, OptionsApiBindings, TemplateImport,
+};
+use fxhash::FxHasher32;
+use swc_core::{
+    common::{BytePos, FileName, Span, DUMMY_SP},
+    ecma::{
+        ast::{
+            CallExpr, Callee, ExportDefaultExpr, Expr, IdentName, ImportDecl, Lit, MethodProp,
+            ModuleDecl, ModuleItem, Null, ObjectLit, Prop, PropName, PropOrSpread, Str,
+        },
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+use swc_ecma_parser::{EsSyntax, Syntax, TsSyntax};
+
+use crate::{errors::CompileError, CompileOptions, CompileResult};
+
+/// Compiles the `template` options of the components declared in a JS/TS module.
+///
+/// Component options objects are the ones passed to `defineComponent`,
+/// exported by default, or registered in `components` of another options object.
+/// Their `template` is replaced with a `render` function when it is
+/// a string or a template literal without expressions.
+/// Templates referring to a DOM element (e.g. `template: '#app'`) are left untouched.
+///
+/// The spans of the template errors are relative to the module,
+/// but are only exact when the template string has no escape sequences.
+pub fn compile_js_module(
+    source: &str,
+    options: CompileOptions,
+) -> Result<CompileResult, CompileError> {
+    let generate_source_map = options.source_map.unwrap_or(false);
+
+    // Parse
+    let mut parse_errors = Vec::new();
+    let mut parser =
+        SfcParser::new(source, &mut parse_errors).with_mode(options.parse_mode.unwrap_or_default());
+    let span = Span::new(BytePos(1), BytePos(source.len() as u32 + 1));
+    let syntax = syntax_from_filename(&options.filename);
+    let mut module = parser.parse_module(source, syntax, span)?;
+
+    let mut all_errors: Vec<CompileError> = parse_errors.into_iter().map(From::from).collect();
+
+    // Compile the templates
+    let mut template_compiler = TemplateCompiler {
+        options: &options,
+        errors: Vec::new(),
+        fatal_error: None,
+        vue_imports: VueImportsSet::default(),
+        template_imports: Vec::new(),
+    };
+    module.visit_mut_with(&mut template_compiler);
+
+    if let Some(fatal_error) = template_compiler.fatal_error {
+        return Err(fatal_error);
+    }
+    all_errors.extend(template_compiler.errors);
+
+    // Append the imports from the template plugins and the Vue imports used by the render functions
+    let ctx = CodegenContext::with_bindings_helper(BindingsHelper {
+        vue_imports: template_compiler.vue_imports,
+        template_imports: template_compiler.template_imports,
+        template_plugins: options.template_plugins.to_owned(),
+        ..Default::default()
+    });
+    module.body.extend(
+        ctx.generate_template_imports()
+            .into_iter()
+            .map(|import_decl| ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))),
+    );
+    let used_imports = ctx.generate_imports();
+    if !used_imports.is_empty() {
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                span: DUMMY_SP,
+                specifiers: used_imports,
+                src: Box::new(Str {
+                    span: DUMMY_SP,
+                    value: fervid_atom!("vue"),
+                    raw: None,
+                }),
+                type_only: false,
+                with: None,
+                phase: Default::default(),
+            })));
+    }
+
+    // Parse the input source map to compose with
+    let in_source_map = match options.in_source_map {
+        Some(ref raw) if generate_source_map => {
+            match sourcemap::SourceMap::from_slice(raw.as_bytes()) {
+                Ok(map) => Some(map),
+                Err(e) => {
+                    all_errors.push(CompileError::InvalidInputSourceMap(e));
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Convert AST to string
+    let (code, source_map) = CodegenContext::stringify(
+        source,
+        &module,
+        FileName::Custom(options.filename.to_string()),
+        generate_source_map,
+        false,
+        in_source_map.as_ref(),
+    );
+
+    let file_hash = {
+        let mut hasher = FxHasher32::default();
+        source.hash(&mut hasher);
+        let num = hasher.finish();
+        format!("{:x}", num)
+    };
+
+    Ok(CompileResult {
+        code,
+        file_hash,
+        errors: all_errors,
+        styles: vec![],
+        other_assets: vec![],
+        source_map,
+        setup_bindings: vec![],
+        is_ts: syntax.typescript(),
+        dependencies: vec![],
+    })
+}
+
+fn syntax_from_filename(filename: &str) -> Syntax {
+    let extension = filename.rsplit_once('.').map_or("", |(_, ext)| ext);
+    match extension {
+        "ts" | "mts" | "cts" => Syntax::Typescript(TsSyntax::default()),
+        "tsx" => Syntax::Typescript(TsSyntax {
+            tsx: true,
+            ..Default::default()
+        }),
+        "jsx" => Syntax::Es(EsSyntax {
+            jsx: true,
+            ..Default::default()
+        }),
+        _ => Syntax::Es(EsSyntax::default()),
+    }
+}
+
+struct TemplateCompiler<'a, 'o> {
+    options: &'a CompileOptions<'o>,
+    errors: Vec<CompileError>,
+    /// Set when a template fails to parse in one of the failing [`fervid_parser::ParseMode`]s
+    fatal_error: Option<CompileError>,
+    vue_imports: VueImportsSet,
+    /// Imports added by the template plugins, shared by all the templates of the module
+    template_imports: Vec<TemplateImport>,
+}
+
+impl TemplateCompiler<'_, '_> {
+    fn compile_options_object(&mut self, options_obj: &mut ObjectLit) {
+        // Nested components first, they are independent from the parent
+        for prop in options_obj.props.iter_mut() {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+            let Prop::KeyValue(ref mut key_value) = **prop else {
+                continue;
+            };
+            if !is_prop_name(&key_value.key, "components") {
+                continue;
+            }
+            let Expr::Object(ref mut components) = *key_value.value else {
+                continue;
+            };
+            for component in components.props.iter_mut() {
+                if let PropOrSpread::Prop(component) = component {
+                    if let Prop::KeyValue(ref mut component) = **component {
+                        if let Expr::Object(ref mut component_obj) = *component.value {
+                            self.compile_options_object(component_obj);
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.fatal_error.is_some() {
+            return;
+        }
+
+        let Some((template_idx, template, template_lo)) = find_template(options_obj) else {
+            return;
+        };
+
+        // The templates referring to a DOM element can only be compiled at runtime
+        if template.starts_with('#') {
+            return;
+        }
+
+        let mut options_api_bindings = OptionsApiBindings::default();
+        analyze_options_object(options_obj, &mut options_api_bindings);
+
+        // Component `delimiters` have priority over the ones from `CompileOptions`
+        let (delimiter_start, delimiter_end) = match (
+            options_api_bindings.delimiters.as_ref(),
+            self.options.delimiters.as_ref(),
+        ) {
+            (Some((start, end)), _) => (start.to_string(), end.to_string()),
+            (None, Some((start, end))) => (start.to_string(), end.to_string()),
+            (None, None) => (
+                INTERPOLATION_START_PAT_DEFAULT.to_string(),
+                INTERPOLATION_END_PAT_DEFAULT.to_string(),
+            ),
+        };
+
+        // Parse
+        let mut parse_errors = Vec::new();
+        let mut parser = SfcParser::new(&template, &mut parse_errors)
+            .with_delimiters(&delimiter_start, &delimiter_end)
+            .with_mode(self.options.parse_mode.unwrap_or_default());
+        let template_block = parser.parse_template();
+        let mut template_block: SfcTemplateBlock = match template_block {
+            Ok(v) => v,
+            Err(mut e) => {
+                e.span = shift_span(e.span, template_lo);
+                self.fatal_error = Some(e.into());
+                return;
+            }
+        };
+        self.errors.extend(parse_errors.into_iter().map(|mut e| {
+            e.span = shift_span(e.span, template_lo);
+            CompileError::from(e)
+        }));
+
+        // Transform
+        let mut bindings_helper = BindingsHelper {
+            is_prod: self.options.is_prod.unwrap_or_default(),
+            is_custom_element_tag: self.options.is_custom_element_tag.to_owned(),
+            comments: self.options.comments,
+            options_api_bindings: Some(Box::new(options_api_bindings)),
+            template_generation_mode: TemplateGenerationMode::RenderFn,
+            template_plugins: self.options.template_plugins.to_owned(),
+            ..Default::default()
+        };
+        transform_and_record_template(
+            &mut template_block,
+            &mut bindings_helper,
+            self.options.whitespace.unwrap_or_default(),
+        );
+
+        // Codegen
+        let mut ctx = CodegenContext::with_bindings_helper(bindings_helper);
+        let template_expr = ctx
+            .generate_sfc_template(&template_block)
+            .unwrap_or_else(|| Expr::Lit(Lit::Null(Null { span: DUMMY_SP })));
+        let render_fn = ctx.generate_render_fn(template_expr);
+
+        options_obj.props[template_idx] = PropOrSpread::Prop(Box::new(Prop::Method(MethodProp {
+            key: PropName::Ident(IdentName {
+                span: DUMMY_SP,
+                sym: FervidAtom::from("render"),
+            }),
+            function: Box::new(render_fn),
+        })));
+
+        self.vue_imports |= ctx.bindings_helper.vue_imports;
+        for template_import in ctx.bindings_helper.template_imports.drain(..) {
+            if !self
+                .template_imports
+                .iter()
+                .any(|existing| existing.local == template_import.local)
+            {
+                self.template_imports.push(template_import);
+            }
+        }
+        self.errors
+            .extend(ctx.bindings_helper.errors.drain(..).map(|mut e| {
+                if let TransformError::TemplateError(ref mut e) = e {
+                    e.span = shift_span(e.span, template_lo);
+                }
+                CompileError::from(e)
+            }));
+    }
+}
+
+impl VisitMut for TemplateCompiler<'_, '_> {
+    fn visit_mut_call_expr(&mut self, call_expr: &mut CallExpr) {
+        let is_define_component = match call_expr.callee {
+            Callee::Expr(ref callee) => match callee.as_ref() {
+                Expr::Ident(ident) => ident.sym == "defineComponent",
+                // E.g. `Vue.defineComponent`
+                Expr::Member(member) => member
+                    .prop
+                    .as_ident()
+                    .is_some_and(|prop| prop.sym == "defineComponent"),
+                _ => false,
+            },
+            _ => false,
+        };
+
+        if is_define_component {
+            if let Some(first_arg) = call_expr.args.first_mut() {
+                if let (None, Expr::Object(options_obj)) =
+                    (first_arg.spread, first_arg.expr.as_mut())
+                {
+                    self.compile_options_object(options_obj);
+                }
+            }
+        }
+
+        call_expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_export_default_expr(&mut self, export_default: &mut ExportDefaultExpr) {
+        let mut expr = export_default.expr.as_mut();
+        while let Expr::Paren(paren) = expr {
+            expr = paren.expr.as_mut();
+        }
+
+        if let Expr::Object(options_obj) = expr {
+            self.compile_options_object(options_obj);
+        }
+
+        export_default.visit_mut_children_with(self);
+    }
+}
+
+/// Finds the `template` option with a static value.
+/// Returns its index, value and the position where the value starts.
+fn find_template(options_obj: &ObjectLit) -> Option<(usize, String, u32)> {
+    options_obj
+        .props
+        .iter()
+        .enumerate()
+        .find_map(|(idx, prop)| {
+            let PropOrSpread::Prop(prop) = prop else {
+                return None;
+            };
+            let Prop::KeyValue(ref key_value) = **prop else {
+                return None;
+            };
+            if !is_prop_name(&key_value.key, "template") {
+                return None;
+            }
+
+            match key_value.value.as_ref() {
+                Expr::Lit(Lit::Str(s)) => Some((idx, s.value.to_string(), s.span.lo.0)),
+                Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+                    let quasi = tpl.quasis.first()?;
+                    let value = quasi.cooked.as_ref().unwrap_or(&quasi.raw);
+                    Some((idx, value.to_string(), tpl.span.lo.0))
+                }
+                _ => None,
+            }
+        })
+}
+
+fn is_prop_name(prop_name: &PropName, name: &str) -> bool {
+    match prop_name {
+        PropName::Ident(ident) => ident.sym == name,
+        PropName::Str(s) => s.value == name,
+        _ => false,
+    }
+}
+
+/// Converts a span inside of the template string to the span inside of the module.
+/// `template_lo` is the position of the opening quote.
+fn shift_span(span: Span, template_lo: u32) -> Span {
+    if span.is_dummy() {
+        return span;
+    }
+
+    Span::new(
+        BytePos(span.lo.0 + template_lo),
+        BytePos(span.hi.0 + template_lo),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn options(filename: &str) -> CompileOptions {
+        CompileOptions {
+            filename: Cow::Borrowed(filename),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_compiles_define_component_templates() {
+        let source = r#"
+import { defineComponent } from 'vue'
+
+export const Foo = defineComponent({
+  props: ['msg'],
+  data() { return { count: 0 } },
+  template: '<div @click="count++">{{ msg }} {{ count }}</div>',
+})
+"#;
+        let result = compile_js_module(source, options("foo.js")).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(!result.code.contains("template:"));
+        assert!(result
+            .code
+            .contains("render (_ctx, _cache, $props, $setup, $data, $options)"));
+        assert!(result.code.contains("$props.msg"));
+        assert!(result.code.contains("$data.count++"));
+        assert!(result.code.contains("from \"vue\""));
+    }
+
+    #[test]
+    fn it_compiles_default_export_and_nested_components() {
+        let source = r#"
+export default {
+  components: {
+    Child: {
+      template: `<span>child</span>`,
+    },
+  },
+  template: '<Child></Child>',
+}
+"#;
+        let result = compile_js_module(source, options("foo.ts")).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(!result.code.contains("template:"));
+        assert_eq!(2, result.code.matches("render (").count());
+        assert!(result.code.contains("\"child\""));
+    }
+
+    #[test]
+    fn it_skips_non_static_templates() {
+        let source = r#"
+defineComponent({ template: '#app' })
+defineComponent({ template: `<div>${foo}</div>` })
+defineComponent({ template: getTemplate() })
+"#;
+        let result = compile_js_module(source, options("foo.js")).unwrap();
+        assert!(result.errors.is_empty());
+        assert_eq!(3, result.code.matches("template:").count());
+        assert!(!result.code.contains("render ("));
+    }
+
+    #[test]
+    fn it_uses_component_delimiters() {
+        let source = r#"
+defineComponent({
+  delimiters: ['${', '}'],
+  template: '<div>${ msg }</div>',
+})
+"#;
+        let result = compile_js_module(source, options("foo.js")).unwrap();
+        assert!(result.code.contains("_ctx.msg"));
+    }
+
+    #[test]
+    fn it_reports_errors_relative_to_module() {
+        let source = "defineComponent({ template: '<div>{{ foo </div>' })";
+        let result = compile_js_module(source, options("foo.js")).unwrap();
+        let [error] = result.errors.as_slice() else {
+            panic!("Expected one error")
+        };
+        let CompileError::SfcParse(e) = error else {
+            panic!("Expected a parse error")
+        };
+        // Spans are 1-based
+        assert_eq!(source.find("{{").unwrap() + 1, e.span.lo.0 as usize);
+    }
+
+    #[test]
+    fn it_runs_template_plugins() {
+        use std::sync::Arc;
+
+        use fervid_core::{ElementNode, Node};
+        use fervid_transform::template::{ElementTransform, TemplatePlugin};
+
+        /// `<Logo>` -> `{{ logo }}` and `import logo from '@/logo.txt'`
+        struct InlineLogo;
+
+        impl TemplatePlugin for InlineLogo {
+            fn name(&self) -> &str {
+                "inline-logo"
+            }
+
+            fn transform_element(
+                &self,
+                element: &mut ElementNode,
+                _template_scope: u32,
+                bindings_helper: &mut BindingsHelper,
+            ) -> ElementTransform {
+                if element.starting_tag.tag_name != "Logo" {
+                    return ElementTransform::Keep;
+                }
+
+                bindings_helper.add_template_import(TemplateImport {
+                    local: fervid_atom!("logo"),
+                    imported: None,
+                    source: fervid_atom!("@/logo.txt"),
+                });
+                ElementTransform::Replace(Node::Text(fervid_atom!("Logo"), element.span))
+            }
+        }
+
+        let source = r#"
+export const Foo = defineComponent({ template: '<h1><Logo></Logo></h1>' })
+export const Bar = defineComponent({ template: '<footer><Logo></Logo></footer>' })
+"#;
+        let mut compile_options = options("foo.js");
+        compile_options.template_plugins = vec![Arc::new(InlineLogo)];
+        let result = compile_js_module(source, compile_options).unwrap();
+
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            1,
+            result
+                .code
+                .matches(r#"import logo from "@/logo.txt";"#)
+                .count()
+        );
+        assert!(!result.code.contains("_resolveComponent"));
+        assert!(result
+            .code
+            .contains(r#"_createElementBlock("h1", null, "Logo")"#));
+    }
+}
@@ -25,7 +25,12 @@
 //!   is_ce: false,
 //!   props_destructure: fervid_transform::PropsDestructureConfig::default(),
 //!   scope_id: "filehash",
-//!   filename: "input.vue"
+//!   filename: "input.vue",
+//!   whitespace: fervid_transform::WhitespaceMode::default(),
+//!   is_custom_element_tag: None,
+//!   comments: None,
+//!   style_source_map: None,
+//!   template_plugins: vec![],
 //! };
 //! let transform_result = fervid_transform::transform_sfc(sfc, transform_options, &mut transform_errors);
 //!
@@ -46,33 +51,57 @@
 //! );
 //!
 //! // (Optional) Stringify the code
-//! let compiled_code = fervid_codegen::CodegenContext::stringify(input, &sfc_module, FileName::Custom("input.vue".into()), false, false);
+//! let compiled_code = fervid_codegen::CodegenContext::stringify(input, &sfc_module, FileName::Custom("input.vue".into()), false, false, None);
 //! ```
 
 extern crate lazy_static;
 
+mod batch;
+mod cache;
 pub mod errors;
+mod js_module;
+mod parse;
 #[deprecated]
 pub mod parser_old;
+mod rewrite_default;
+mod style;
+mod template;
 
+pub use batch::{compile_many, CompileManyInput, CompileManyOptions};
+pub use cache::{CompileCache, CompileCacheOptions};
 use errors::CompileError;
 use fervid_codegen::CodegenContext;
 pub use fervid_core::*;
-use fervid_parser::SfcParser;
+use fervid_css::CssSourceMapConfig;
+pub use fervid_parser::ParseMode;
+use fervid_parser::{SfcParser, INTERPOLATION_END_PAT_DEFAULT, INTERPOLATION_START_PAT_DEFAULT};
+use fervid_plugin::load_plugin;
 use fervid_transform::{
-    style::should_transform_style_block, transform_sfc, PropsDestructureConfig, SetupBinding, TransformSfcOptions
+    script::find_default_export_delimiters, style::should_transform_style_block, transform_sfc,
+    PropsDestructureConfig, TransformSfcOptions,
+};
+pub use fervid_transform::{
+    template::{ElementTransform, TemplatePlugin},
+    BindingsHelper, SetupBinding, TagPredicate, TemplateImport, WhitespaceMode,
 };
 use fxhash::FxHasher32;
+pub use js_module::compile_js_module;
+pub use parse::{parse, ParseOptions, ParsedSfc, ParsedSfcBlock, SourceLocation, SourcePosition};
+pub use rewrite_default::rewrite_default;
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     hash::{Hash, Hasher},
+    path::Path,
+    sync::Arc,
 };
+pub use style::{compile_style, CompileStyleOptions, CompileStyleResult};
 use swc_core::{common::FileName, ecma::ast::Expr};
+pub use template::{compile_template, CompileTemplateOptions, CompileTemplateResult};
 
-// TODO Add severity to errors
 // TODO Better structs
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CompileOptions<'o> {
     // ast?: RootNode;
     pub filename: Cow<'o, str>,
@@ -84,9 +113,23 @@ pub struct CompileOptions<'o> {
     pub ssr: Option<bool>,
     pub props_destructure: Option<PropsDestructureConfig>,
     // pub ssrCssVars?: string[],
-    // pub inMap?: RawSourceMap,
+    /// Source map of the preprocessed SFC (e.g. produced by an upstream loader).
+    /// When present, the output source map points to the original file.
+    pub in_source_map: Option<Cow<'o, str>>,
     // pub compiler?: TemplateCompiler,
     // pub compilerOptions?: CompilerOptions,
+    /// Interpolation delimiters, `{{` and `}}` by default.
+    /// A `delimiters` option of the component takes precedence.
+    pub delimiters: Option<(Cow<'o, str>, Cow<'o, str>)>,
+    /// How the whitespace in `<template>` is handled,
+    /// defaults to [`WhitespaceMode::Condense`]
+    pub whitespace: Option<WhitespaceMode>,
+    /// Which unknown tags are native custom elements (e.g. `<ion-button>`) rather than components.
+    /// Matching tags are not resolved and are generated as elements
+    pub is_custom_element_tag: Option<TagPredicate>,
+    /// Whether to keep `<template>` comments.
+    /// Defaults to `true` in development and `false` in production
+    pub comments: Option<bool>,
     // pub preprocessLang?: string,
     // pub preprocessOptions?: any,
     // In some cases, compiler-sfc may not be inside the project root (e.g. when
@@ -102,6 +145,14 @@ pub struct CompileOptions<'o> {
 
     // fervid-specific
     pub source_map: Option<bool>,
+    /// How to handle the errors in HTML and EcmaScript when parsing the SFC,
+    /// defaults to [`ParseMode::SoftRecoverSafe`]
+    pub parse_mode: Option<ParseMode>,
+    /// User-defined transforms of the `<template>` elements, see [`TemplatePlugin`]
+    pub template_plugins: Vec<Arc<dyn TemplatePlugin>>,
+    /// Paths to the plugin libraries (`.so`, `.dll` or `.dylib`) built with `fervid_plugin_api`.
+    /// The plugins are loaded once and their hooks are called in the given order
+    pub plugins: Vec<Cow<'o, str>>,
 }
 
 pub struct CompileResult {
@@ -112,20 +163,34 @@ pub struct CompileResult {
     pub other_assets: Vec<CompileEmittedAsset>,
     pub source_map: Option<String>,
     pub setup_bindings: Vec<SetupBinding>,
+    /// Whether the code is TypeScript, i.e. the SFC has `<script lang="ts">`
+    pub is_ts: bool,
+    /// Files the compilation depended on, e.g. the ones the types of `defineProps` were imported from.
+    /// When one of them changes, the SFC must be compiled again
+    pub dependencies: Vec<String>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CompileEmittedStyle {
     pub code: String,
     pub is_compiled: bool,
     pub lang: String,
     pub is_scoped: bool,
+    /// Source map pointing into the SFC, only present for compiled styles
+    /// when `source_map` is enabled in [`CompileOptions`]
+    pub source_map: Option<String>,
+    /// Attributes of `<style>` as written, e.g. `module`. Attributes without a value have an empty one
+    pub attrs: Vec<(String, String)>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CompileEmittedAsset {
     pub lo: u32,
     pub hi: u32,
     pub tag_name: String,
     pub content: String,
+    /// Attributes of the custom block as written. Attributes without a value have an empty one
+    pub attrs: Vec<(String, String)>,
 }
 
 /// A more general-purpose SFC compilation function.
@@ -136,12 +201,52 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
     // Options
     let is_prod = options.is_prod.unwrap_or_default();
     let is_custom_element = options.is_custom_element.unwrap_or_default();
+    let generate_source_map = options.source_map.unwrap_or(false);
+    let (delimiter_start, delimiter_end) = match options.delimiters {
+        Some((ref start, ref end)) => (start.as_ref(), end.as_ref()),
+        None => (
+            INTERPOLATION_START_PAT_DEFAULT,
+            INTERPOLATION_END_PAT_DEFAULT,
+        ),
+    };
+
+    // Load the plugins
+    let plugins = options
+        .plugins
+        .iter()
+        .map(|path| load_plugin(Path::new(path.as_ref())))
+        .collect::<Result<Vec<_>, _>>()?;
 
     // Parse
+    let parse_mode = options.parse_mode.unwrap_or_default();
     let mut sfc_parsing_errors = Vec::new();
-    let mut parser = SfcParser::new(source, &mut sfc_parsing_errors);
-    let sfc = parser.parse_sfc()?;
+    let mut parser = SfcParser::new(source, &mut sfc_parsing_errors)
+        .with_delimiters(delimiter_start, delimiter_end)
+        .with_mode(parse_mode);
+    let mut sfc = parser.parse_sfc()?;
+
+    // A `delimiters` option of the component changes how the template is parsed,
+    // so the `<template>` is parsed again when it differs from the delimiters used above
+    let component_delimiters = sfc
+        .script_legacy
+        .as_ref()
+        .and_then(|script| find_default_export_delimiters(&script.content));
+    if let (Some((start, end)), Some(template)) = (component_delimiters, sfc.template.as_ref()) {
+        if (start.as_ref(), end.as_ref()) != (delimiter_start, delimiter_end) {
+            let template_span = template.span;
+            sfc_parsing_errors.retain(|e| !template_span.contains(e.span));
+
+            let mut parser = SfcParser::new(source, &mut sfc_parsing_errors)
+                .with_delimiters(&start, &end)
+                .with_mode(parse_mode);
+            sfc.template = parser.parse_sfc_template()?;
+        }
+    }
+
     all_errors.extend(sfc_parsing_errors.into_iter().map(From::from));
+    for plugin in plugins.iter() {
+        plugin.on_parse(&mut sfc)?;
+    }
 
     // For scopes
     // TODO Research if it's better to compute that on the caller site or here
@@ -160,9 +265,21 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
         props_destructure: options.props_destructure.unwrap_or_default(),
         scope_id: &file_hash,
         filename: &options.filename,
+        whitespace: options.whitespace.unwrap_or_default(),
+        is_custom_element_tag: options.is_custom_element_tag.to_owned(),
+        comments: options.comments,
+        style_source_map: generate_source_map.then(|| CssSourceMapConfig {
+            source,
+            filename: &options.filename,
+        }),
+        template_plugins: options.template_plugins.to_owned(),
     };
-    let transform_result = transform_sfc(sfc, transform_options, &mut transform_errors);
+    let mut transform_result = transform_sfc(sfc, transform_options, &mut transform_errors);
+
     all_errors.extend(transform_errors.into_iter().map(From::from));
+    for plugin in plugins.iter() {
+        plugin.on_transform(&mut transform_result)?;
+    }
 
     // Codegen
     let mut ctx = CodegenContext::with_bindings_helper(transform_result.bindings_helper);
@@ -171,21 +288,40 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
         .template_block
         .and_then(|template_block| ctx.generate_sfc_template(&template_block));
 
-    let sfc_module = ctx.generate_module(
+    let mut sfc_module = ctx.generate_module(
         template_expr,
         *transform_result.module,
         transform_result.exported_obj,
         transform_result.setup_fn,
         options.gen_default_as.as_deref(),
     );
+    all_errors.extend(ctx.bindings_helper.errors.drain(..).map(From::from));
+    for plugin in plugins.iter() {
+        plugin.on_module(&mut sfc_module)?;
+    }
+
+    // Parse the input source map to compose with
+    let in_source_map = match options.in_source_map {
+        Some(ref raw) if generate_source_map => {
+            match sourcemap::SourceMap::from_slice(raw.as_bytes()) {
+                Ok(map) => Some(map),
+                Err(e) => {
+                    all_errors.push(CompileError::InvalidInputSourceMap(e));
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
 
     // Convert AST to string
     let (code, source_map) = CodegenContext::stringify(
         &source,
         &sfc_module,
         FileName::Custom(options.filename.to_string()),
-        options.source_map.unwrap_or(false),
+        generate_source_map,
         false,
+        in_source_map.as_ref(),
     );
 
     let styles = transform_result
@@ -196,6 +332,8 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
             is_compiled: should_transform_style_block(&style_block),
             lang: style_block.lang.to_string(),
             is_scoped: style_block.is_scoped,
+            attrs: parse::regular_attributes(&style_block.attrs),
+            source_map: style_block.source_map,
         })
         .collect();
 
@@ -208,10 +346,14 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
                 hi: 0, // todo
                 tag_name: block.starting_tag.tag_name.to_string(),
                 content: block.content.to_string(),
+                attrs: parse::regular_attributes(&block.starting_tag.attributes),
             }
         })
         .collect();
 
+    let mut dependencies: Vec<String> = transform_result.deps.into_iter().collect();
+    dependencies.sort();
+
     Ok(CompileResult {
         code,
         file_hash,
@@ -220,6 +362,8 @@ pub fn compile(source: &str, options: CompileOptions) -> Result<CompileResult, C
         other_assets,
         source_map,
         setup_bindings: ctx.bindings_helper.setup_bindings,
+        is_ts: ctx.bindings_helper.is_ts,
+        dependencies,
     })
 }
 
@@ -257,6 +401,11 @@ pub fn compile_sync_naive(source: &str, is_prod: bool) -> Result<String, String>
         props_destructure: PropsDestructureConfig::default(),
         scope_id: &file_hash,
         filename: "anonymous.vue".into(),
+        whitespace: WhitespaceMode::default(),
+        is_custom_element_tag: None,
+        comments: None,
+        style_source_map: None,
+        template_plugins: vec![],
     };
     let transform_result = transform_sfc(sfc, transform_options, &mut transform_errors);
 
@@ -276,7 +425,511 @@ pub fn compile_sync_naive(source: &str, is_prod: bool) -> Result<String, String>
     );
 
     let (compiled_code, _map) =
-        CodegenContext::stringify(&source, &sfc_module, FileName::Anon, false, false);
+        CodegenContext::stringify(&source, &sfc_module, FileName::Anon, false, false, None);
 
     Ok(compiled_code)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        sync::{Arc, Mutex},
+    };
+
+    use fervid_core::{
+        error::{ErrorCode, Severity, SeverityLevel},
+        fervid_atom, AttributeOrBinding, ElementNode, IntoIdent, StrOrExpr, VBindDirective,
+    };
+    use swc_core::{common::DUMMY_SP, ecma::ast::Expr};
+
+    use crate::{
+        compile, errors::CompileError, BindingsHelper, CompileOptions, ElementTransform,
+        TagPredicate, TemplateImport, TemplatePlugin, WhitespaceMode,
+    };
+
+    fn options(in_source_map: Option<&str>) -> CompileOptions<'_> {
+        CompileOptions {
+            filename: Cow::Borrowed("intermediate.vue"),
+            is_prod: Some(false),
+            in_source_map: in_source_map.map(Cow::Borrowed),
+            source_map: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_composes_input_source_map() {
+        let source = "<script setup>\nconst msg = 'hi'\n</script>\n<template>{{ msg }}</template>";
+
+        // Pretend that a preprocessor shifted every line of `original.vue` by 2
+        let mut builder = sourcemap::SourceMapBuilder::new(None);
+        let src_id = builder.add_source("original.vue");
+        for (line, text) in source.lines().enumerate() {
+            for col in 0..=text.len() as u32 {
+                let line = line as u32;
+                builder.add_raw(line, col, line + 2, col, Some(src_id), None, false);
+            }
+        }
+        let mut in_map = vec![];
+        builder.into_sourcemap().to_writer(&mut in_map).unwrap();
+        let in_map = String::from_utf8(in_map).unwrap();
+
+        let result = compile(source, options(Some(&in_map))).unwrap();
+        assert!(result.errors.is_empty());
+
+        let map = result.source_map.expect("Should have source map");
+        let map = sourcemap::SourceMap::from_slice(map.as_bytes()).unwrap();
+        assert_eq!(Some("original.vue"), map.get_source(0));
+
+        // `const msg` is on line 1 of `source`, therefore on line 3 of the original
+        let (dst_line, dst_col) = result
+            .code
+            .lines()
+            .enumerate()
+            .find_map(|(idx, line)| line.find("const msg").map(|col| (idx, col)))
+            .expect("Should have `const msg`");
+        let msg_token = map
+            .lookup_token(dst_line as u32, dst_col as u32)
+            .expect("Should map `const msg`");
+        assert_eq!(3, msg_token.get_src_line());
+    }
+
+    #[test]
+    fn it_reports_invalid_input_source_map() {
+        let source = "<template><div>hi</div></template>";
+
+        let result = compile(source, options(Some("not a source map"))).unwrap();
+        assert!(result.source_map.is_some());
+        assert!(matches!(
+            result.errors.as_slice(),
+            [CompileError::InvalidInputSourceMap(_)]
+        ));
+    }
+
+    #[test]
+    fn it_renders_diagnostics() {
+        let source = "<script setup>\ndefineProps({})\ndefineProps({})\n</script>";
+
+        let result = compile(source, options(None)).unwrap();
+        let [error] = result.errors.as_slice() else {
+            panic!("Expected exactly one error, got {:?}", result.errors);
+        };
+
+        assert_eq!(
+            error.to_diagnostic_string(source, "intermediate.vue"),
+            [
+                "error[DUPLICATE_DEFINE_PROPS]: Duplicate defineProps() call.",
+                " --> intermediate.vue:3:1",
+                "1  |  <script setup>",
+                "2  |  defineProps({})",
+                "3  |  defineProps({})",
+                "   |  ^^^^^^^^^^^^^^^",
+                "4  |  </script>",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn it_reports_codegen_errors() {
+        let source = "<script setup>\nconst items = []\n</script>\n<template><comp><template v-for=\"i in items\" v-slot:named>hi</template></comp></template>";
+
+        let result = compile(source, options(None)).unwrap();
+        assert!(result.code.contains("named"));
+        let [CompileError::TransformError(error)] = result.errors.as_slice() else {
+            panic!("Expected exactly one error, got {:?}", result.errors);
+        };
+        assert_eq!("X_DYNAMIC_SLOTS_UNSUPPORTED", error.error_code());
+    }
+
+    #[test]
+    fn it_reports_type_dependencies() {
+        let directory = std::env::temp_dir().join(format!("fervid-deps-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("types.ts"),
+            "export interface Props { msg: string; count?: number }",
+        )
+        .unwrap();
+
+        let source = "<script setup lang=\"ts\">\nimport type { Props } from './types'\ndefineProps<Props>()\n</script>";
+        let filename = directory.join("Comp.vue");
+        let result = compile(
+            source,
+            CompileOptions {
+                filename: Cow::Owned(filename.to_string_lossy().into_owned()),
+                ..options(None)
+            },
+        )
+        .unwrap();
+
+        assert!(result.errors.is_empty());
+        assert!(result.code.contains("msg: {"));
+        assert!(result.code.contains("type: String"));
+        assert!(result.code.contains("type: Number"));
+        assert_eq!(
+            vec![directory.join("types.ts").to_string_lossy().into_owned()],
+            result.dependencies
+        );
+
+        let _ = std::fs::remove_dir_all(directory);
+    }
+
+    #[test]
+    fn it_does_not_panic_on_empty_input() {
+        let result = compile("", options(None)).unwrap();
+        assert!(!result.errors.is_empty());
+    }
+
+    #[test]
+    fn it_accepts_self_closing_tags() {
+        let source = "<script setup>\nimport MyComp from './MyComp.vue'\n</script>\n<template><MyComp /><div /></template>";
+
+        let result = compile(source, options(None)).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(result.code.contains("_createVNode(MyComp)"));
+    }
+
+    #[test]
+    fn it_reports_v_model_on_const() {
+        let source =
+            "<script setup>\nconst a = 1\n</script>\n<template><input v-model=\"a\"></template>";
+
+        let result = compile(source, options(None)).unwrap();
+        assert_eq!(1, result.errors.len());
+        assert_eq!("X_V_MODEL_ON_CONST", result.errors[0].error_code());
+        assert!(matches!(
+            result.errors[0].get_severity(),
+            SeverityLevel::RecoverableError
+        ));
+        assert!(!result.code.contains("onUpdate:modelValue"));
+    }
+
+    #[test]
+    fn it_uses_custom_delimiters() {
+        let source = "<script setup>\nconst msg = 'hi'\n</script>\n<template><div>[[ msg ]] {{ raw }}</div></template>";
+
+        let mut compile_options = options(None);
+        compile_options.delimiters = Some((Cow::Borrowed("[["), Cow::Borrowed("]]")));
+        let result = compile(source, compile_options).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(result.code.contains("_toDisplayString(msg)"));
+        assert!(result.code.contains("{{ raw }}"));
+    }
+
+    #[test]
+    fn it_uses_component_delimiters() {
+        /// Counts the visited elements
+        #[derive(Default)]
+        struct CountElements(Mutex<usize>);
+
+        impl TemplatePlugin for CountElements {
+            fn name(&self) -> &str {
+                "count-elements"
+            }
+
+            fn transform_element(
+                &self,
+                _element: &mut ElementNode,
+                _template_scope: u32,
+                _bindings_helper: &mut BindingsHelper,
+            ) -> ElementTransform {
+                *self.0.lock().unwrap() += 1;
+                ElementTransform::Keep
+            }
+        }
+
+        let source = "<script>\nexport default defineComponent({ delimiters: ['${', '}'], data: () => ({ msg: 'hi' }) })\n</script>\n<template><div>${ msg } {{ raw }}</div></template>";
+
+        // Component `delimiters` take precedence over the options
+        let plugin = Arc::new(CountElements::default());
+        let mut compile_options = options(None);
+        compile_options.delimiters = Some((Cow::Borrowed("[["), Cow::Borrowed("]]")));
+        compile_options.template_plugins = vec![plugin.clone()];
+        let result = compile(source, compile_options).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(result.code.contains("_toDisplayString($data.msg)"));
+        assert!(result.code.contains("{{ raw }}"));
+
+        // Only the template is parsed again, the SFC is compiled once
+        assert_eq!(1, *plugin.0.lock().unwrap());
+    }
+
+    #[test]
+    fn it_handles_whitespace() {
+        let source = "<script setup>\nconst a = 1\n</script>\n<template><pre>\nfoo\n  <b> x </b>\n</pre>\n<p>  a   <b>b</b>\n  {{ a }}  </p></template>";
+
+        let result = compile(source, options(None)).unwrap();
+        assert!(result.code.contains(r#"_createTextVNode("foo\n  ")"#));
+        assert!(result.code.contains(r#"_createTextVNode(" a ")"#));
+        assert!(result
+            .code
+            .contains(r#"_createTextVNode(" " + _toDisplayString(a))"#));
+
+        let mut compile_options = options(None);
+        compile_options.whitespace = Some(WhitespaceMode::Preserve);
+        let result = compile(source, compile_options).unwrap();
+        assert!(result.code.contains(r#"_createTextVNode("foo\n  ")"#));
+        assert!(result.code.contains(r#"_createTextVNode("  a   ")"#));
+        // Whitespace-only text is still condensed
+        assert!(result
+            .code
+            .contains(r#"_createTextVNode(" " + _toDisplayString(a))"#));
+    }
+
+    #[test]
+    fn it_generates_custom_elements() {
+        let source = "<script setup>\nconst msg = 'hi'\n</script>\n<template><ion-button :label=\"msg\">hi</ion-button><my-comp></my-comp></template>";
+
+        let mut compile_options = options(None);
+        compile_options.is_custom_element_tag =
+            Some(TagPredicate::new(|tag_name| tag_name.starts_with("ion-")));
+        let result = compile(source, compile_options).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(result
+            .code
+            .contains(r#"_createElementVNode("ion-button", {"#));
+        assert!(!result.code.contains(r#"_resolveComponent("ion-button")"#));
+        assert!(result.code.contains(r#"_resolveComponent("my-comp")"#));
+    }
+
+    #[test]
+    fn it_handles_comments_option() {
+        let source = "<script setup>\nconst msg = 'hi'\n</script>\n<template><div><!-- slot marker -->{{ msg }}</div></template>";
+
+        let compile_with = |is_prod: bool, comments: Option<bool>| {
+            let mut compile_options = options(None);
+            compile_options.is_prod = Some(is_prod);
+            compile_options.comments = comments;
+            compile(source, compile_options).unwrap().code
+        };
+
+        assert!(compile_with(false, None).contains(r#"_createCommentVNode(" slot marker ")"#));
+        assert!(!compile_with(true, None).contains("_createCommentVNode"));
+        assert!(compile_with(true, Some(true)).contains(r#"_createCommentVNode(" slot marker ")"#));
+        assert!(!compile_with(false, Some(false)).contains("_createCommentVNode"));
+    }
+
+    #[test]
+    fn it_folds_constants_in_prod() {
+        let source = r#"<script setup>
+import { ref } from 'vue'
+const title = 'Hello'
+const count = 2
+const isDebug = false
+const msg = ref('hi')
+</script>
+<template>
+  <div :class="{ active: count > 1, hidden: isDebug }" :style="{ fontSize: count * 8 + 'px' }">
+    <h1>{{ title + ' world' }}</h1>
+    <p v-if="isDebug">debug</p>
+    <p v-else-if="msg">{{ msg }}</p>
+    <p v-show="count">{{ count }}</p>
+  </div>
+</template>"#;
+
+        let compile_with = |is_prod: bool| {
+            let mut compile_options = options(None);
+            compile_options.is_prod = Some(is_prod);
+            let result = compile(source, compile_options).unwrap();
+            assert!(result.errors.is_empty());
+            result.code
+        };
+
+        let prod = compile_with(true);
+        assert!(prod.contains(r#"class: "active""#));
+        assert!(prod.contains(r#"style: {"#) && prod.contains(r#""font-size": "16px""#));
+        assert!(prod.contains(r#""Hello world""#));
+        assert!(!prod.contains("debug"));
+        assert!(prod.contains("msg.value ?"));
+        assert!(!prod.contains("_vShow"));
+        assert!(prod.contains(r#""2""#));
+        assert!(!prod.contains("_normalizeClass"));
+
+        // DEV is left as it is
+        let dev = compile_with(false);
+        assert!(dev.contains("_normalizeClass"));
+        assert!(dev.contains("_vShow"));
+        assert!(dev.contains("debug"));
+    }
+
+    #[test]
+    fn it_runs_template_plugins() {
+        /// `<Icon name="x">` -> `<img :src="icon_x">`
+        struct StaticIcons;
+
+        impl TemplatePlugin for StaticIcons {
+            fn name(&self) -> &str {
+                "static-icons"
+            }
+
+            fn transform_element(
+                &self,
+                element: &mut ElementNode,
+                _template_scope: u32,
+                bindings_helper: &mut BindingsHelper,
+            ) -> ElementTransform {
+                if element.starting_tag.tag_name != "Icon" {
+                    return ElementTransform::Keep;
+                }
+
+                bindings_helper.add_template_import(TemplateImport {
+                    local: fervid_atom!("icon_close"),
+                    imported: None,
+                    source: fervid_atom!("@/icons/close.svg"),
+                });
+                element.starting_tag.tag_name = fervid_atom!("img");
+                element.starting_tag.attributes = vec![AttributeOrBinding::VBind(VBindDirective {
+                    argument: Some(StrOrExpr::Str(fervid_atom!("src"))),
+                    value: Box::new(Expr::Ident(fervid_atom!("icon_close").into_ident())),
+                    is_camel: false,
+                    is_prop: false,
+                    is_attr: false,
+                    span: DUMMY_SP,
+                })];
+
+                ElementTransform::Keep
+            }
+        }
+
+        let source = "<script setup>\nconst msg = 'hi'\n</script>\n<template><Icon name=\"close\"></Icon>{{ msg }}</template>";
+
+        let compile_with = |is_prod: bool| {
+            let mut compile_options = options(None);
+            compile_options.is_prod = Some(is_prod);
+            compile_options.template_plugins = vec![Arc::new(StaticIcons)];
+            let result = compile(source, compile_options).unwrap();
+            assert!(result.errors.is_empty());
+            result.code
+        };
+
+        for code in [compile_with(false), compile_with(true)] {
+            assert!(code.contains(r#"import icon_close from "@/icons/close.svg";"#));
+            assert!(code.contains("src: icon_close"));
+            assert!(!code.contains("_resolveComponent"));
+        }
+    }
+
+    #[test]
+    fn it_runs_template_plugins_in_template_scope() {
+        /// Records whether `<Icon :name>` refers to a `v-for` variable
+        #[derive(Default)]
+        struct IconNames(Mutex<Vec<bool>>);
+
+        impl TemplatePlugin for IconNames {
+            fn name(&self) -> &str {
+                "icon-names"
+            }
+
+            fn transform_element(
+                &self,
+                element: &mut ElementNode,
+                template_scope: u32,
+                bindings_helper: &mut BindingsHelper,
+            ) -> ElementTransform {
+                let name = element
+                    .starting_tag
+                    .attributes
+                    .iter()
+                    .find_map(|attr| match attr {
+                        AttributeOrBinding::VBind(VBindDirective {
+                            argument: Some(StrOrExpr::Str(argument)),
+                            value,
+                            ..
+                        }) if argument == "name" => value.as_ident(),
+                        _ => None,
+                    });
+
+                if let Some(name) = name {
+                    self.0
+                        .lock()
+                        .unwrap()
+                        .push(bindings_helper.is_template_local(template_scope, &name.sym));
+                }
+
+                ElementTransform::Keep
+            }
+        }
+
+        let source = r#"<script setup>
+const icon = 'close'
+const icons = ['open', 'save']
+</script>
+<template>
+  <Icon :name="icon" />
+  <ul>
+    <li v-for="icon in icons"><Icon :name="icon" /></li>
+  </ul>
+  <List>
+    <template #item="{ icon }"><Icon :name="icon" /></template>
+  </List>
+</template>"#;
+
+        let plugin = Arc::new(IconNames::default());
+        let mut compile_options = options(None);
+        compile_options.template_plugins = vec![plugin.clone()];
+        let result = compile(source, compile_options).unwrap();
+
+        assert!(result.errors.is_empty());
+        assert_eq!(vec![false, true, true], *plugin.0.lock().unwrap());
+    }
+
+    #[test]
+    fn it_validates_template_after_plugins() {
+        /// `<Card>` -> `<div>` and `<Lorem>` is removed
+        struct Unwrap;
+
+        impl TemplatePlugin for Unwrap {
+            fn name(&self) -> &str {
+                "unwrap"
+            }
+
+            fn transform_element(
+                &self,
+                element: &mut ElementNode,
+                _template_scope: u32,
+                _bindings_helper: &mut BindingsHelper,
+            ) -> ElementTransform {
+                match element.starting_tag.tag_name.as_str() {
+                    "Card" => element.starting_tag.tag_name = fervid_atom!("div"),
+                    "Lorem" => return ElementTransform::Remove,
+                    _ => {}
+                }
+
+                ElementTransform::Keep
+            }
+        }
+
+        let compile_errors = |source: &str| {
+            let mut compile_options = options(None);
+            compile_options.template_plugins = vec![Arc::new(Unwrap)];
+            let result = compile(source, compile_options).unwrap();
+            result
+                .errors
+                .iter()
+                .map(|error| error.error_code())
+                .collect::<Vec<_>>()
+        };
+
+        // `v-slot` is only valid on the `<template>` children of a component
+        assert_eq!(
+            vec!["X_V_SLOT_MISPLACED"],
+            compile_errors("<template><Card><template #header>Hi</template></Card></template>")
+        );
+
+        // The `v-if` of the removed element does not count
+        assert_eq!(
+            vec!["X_V_ELSE_NO_ADJACENT_IF"],
+            compile_errors(r#"<template><Lorem v-if="ok" /><p v-else>Hi</p></template>"#)
+        );
+
+        // The only child of `<template v-if>` is transformed before it replaces the `<template>`
+        assert_eq!(
+            vec!["X_V_SLOT_MISPLACED"],
+            compile_errors(
+                r#"<template><template v-if="ok"><Card><template #header>Hi</template></Card></template></template>"#
+            )
+        );
+    }
+}
@@ -1,38 +1,506 @@
-extern crate swc_core;
-extern crate swc_ecma_codegen;
-extern crate swc_ecma_parser;
-use std::time::Instant;
+//! The `fervid` command-line compiler, see `fervid --help`
 
-use fervid::compile_sync_naive;
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-fn main() {
-    let n = Instant::now();
-    test_real_compilation();
-    println!("Time took: {:?}", n.elapsed());
+use fervid::{
+    compile_many, errors::CompileError, CompileManyInput, CompileManyOptions, CompileOptions,
+    CompileResult, ParseMode, TagPredicate, WhitespaceMode,
+};
+use fervid_core::error::{Severity, SeverityLevel};
+use fervid_transform::PropsDestructureConfig;
+
+const HELP: &str = "\
+Compiles Vue Single-File Components to JavaScript
+
+Usage: fervid [OPTIONS] <INPUT>...
+
+Arguments:
+  <INPUT>...  `.vue` files or directories to search for them recursively
+              (`node_modules` and hidden directories are skipped)
+
+Output:
+  For `Foo.vue`, writes `Foo.vue.js` (or `Foo.vue.ts` for `<script lang=\"ts\">`),
+  `Foo.vue.<index>.css` per `<style>` block (or `.scss`, `.less`, etc. when not compiled)
+  and `.map` files next to them when source maps are enabled.
+
+Options:
+  -o, --out-dir <DIR>              Write the output to DIR, keeping the directory structure
+                                   of the inputs [default: next to the input files]
+      --check                      Only report the errors, do not write anything
+      --prod                       Compile for production
+      --dev                        Compile for development [default]
+      --ssr                        Compile for server-side rendering
+      --custom-element             Compile as a custom element (`defineCustomElement`)
+      --source-map                 Generate source maps
+      --in-source-map              Compose the source maps with `<INPUT>.map` when it exists
+      --gen-default-as <NAME>      Assign the component to a `const NAME` instead of `export default`
+      --props-destructure <MODE>   Reactive props destructure: true, false or error [default: false]
+      --delimiters <START,END>     Interpolation delimiters [default: {{,}}]
+      --whitespace <MODE>          Template whitespace: condense or preserve [default: condense]
+      --custom-element-prefix <P>  Treat the tags starting with P as custom elements (repeatable)
+      --custom-element-pattern <R> Treat the tags matching the regex R as custom elements (repeatable)
+      --comments                   Keep the template comments [default: in development]
+      --no-comments                Remove the template comments [default: in production]
+      --parse-mode <MODE>          How to handle the parsing errors: hard-fail, report-all,
+                                   recover-safe or recover-unsafe [default: recover-safe]
+      --plugin <PATH>              Load a plugin library built with `fervid_plugin_api` (repeatable)
+  -j, --threads <N>                Compile with N threads [default: a thread per CPU]
+  -q, --quiet                      Only print the diagnostics
+  -h, --help                       Print this help
+  -V, --version                    Print the version
+";
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(ParsedArgs::Run(args)) => *args,
+        Ok(ParsedArgs::Help) => {
+            print!("{HELP}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(ParsedArgs::Version) => {
+            println!("fervid {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\nFor more information, try `fervid --help`");
+            return ExitCode::from(2);
+        }
+    };
+
+    let inputs = match collect_inputs(&args.inputs) {
+        Ok(inputs) => inputs,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let is_custom_element_tag =
+        if args.custom_element_prefixes.is_empty() && args.custom_element_patterns.is_empty() {
+            None
+        } else {
+            match TagPredicate::from_prefixes_and_patterns(
+                args.custom_element_prefixes.to_owned(),
+                &args.custom_element_patterns,
+            ) {
+                Ok(predicate) => Some(predicate),
+                Err(e) => {
+                    eprintln!("error: invalid `--custom-element-pattern`: {e}");
+                    return ExitCode::from(2);
+                }
+            }
+        };
+
+    // Read all the files first, so that they are compiled in parallel
+    let mut summary = Summary {
+        files: inputs.len(),
+        ..Default::default()
+    };
+    let mut sources = Vec::with_capacity(inputs.len());
+    for input in inputs.iter() {
+        match fs::read_to_string(&input.path) {
+            Ok(source) => sources.push((input, source)),
+            Err(e) => {
+                eprintln!("error: {}: {e}", input.path.display());
+                summary.errors += 1;
+            }
+        }
+    }
+
+    let compile_inputs: Vec<CompileManyInput> = sources
+        .iter()
+        .map(|(input, source)| CompileManyInput {
+            source: Cow::Borrowed(source),
+            options: args.compile_options(input, &is_custom_element_tag),
+        })
+        .collect();
+    let results = compile_many(
+        &compile_inputs,
+        &CompileManyOptions {
+            threads: args.threads,
+        },
+    );
+
+    for ((input, source), result) in sources.iter().zip(results) {
+        if let Err(message) = emit_result(input, source, result, &args, &mut summary) {
+            eprintln!("error: {}: {message}", input.path.display());
+            summary.errors += 1;
+        }
+    }
+
+    if !args.quiet {
+        eprintln!(
+            "{} {} file(s): {} error(s), {} warning(s)",
+            if args.check { "Checked" } else { "Compiled" },
+            summary.files,
+            summary.errors,
+            summary.warnings
+        );
+    }
+
+    if summary.errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[derive(Default)]
+struct Summary {
+    files: usize,
+    errors: usize,
+    warnings: usize,
+}
+
+/// Command-line arguments, see [`HELP`]
+#[derive(Default)]
+struct Args {
+    inputs: Vec<PathBuf>,
+    out_dir: Option<PathBuf>,
+    check: bool,
+    quiet: bool,
+    threads: Option<usize>,
+    is_prod: bool,
+    ssr: bool,
+    is_custom_element: bool,
+    source_map: bool,
+    in_source_map: bool,
+    gen_default_as: Option<String>,
+    props_destructure: Option<PropsDestructureConfig>,
+    delimiters: Option<(String, String)>,
+    whitespace: Option<WhitespaceMode>,
+    custom_element_prefixes: Vec<String>,
+    custom_element_patterns: Vec<String>,
+    comments: Option<bool>,
+    parse_mode: Option<ParseMode>,
+    plugins: Vec<String>,
+}
+
+enum ParsedArgs {
+    Run(Box<Args>),
+    Help,
+    Version,
+}
+
+impl Args {
+    fn parse(mut raw_args: impl Iterator<Item = String>) -> Result<ParsedArgs, String> {
+        let mut args = Args::default();
+
+        while let Some(arg) = raw_args.next() {
+            // Positional arguments
+            if !arg.starts_with('-') || arg == "-" {
+                args.inputs.push(PathBuf::from(arg));
+                continue;
+            }
+            if arg == "--" {
+                args.inputs.extend(raw_args.by_ref().map(PathBuf::from));
+                break;
+            }
+
+            // Support both `--flag value` and `--flag=value`
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = || -> Result<String, String> {
+                inline_value
+                    .clone()
+                    .or_else(|| raw_args.next())
+                    .ok_or_else(|| format!("`{flag}` requires a value"))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => return Ok(ParsedArgs::Help),
+                "-V" | "--version" => return Ok(ParsedArgs::Version),
+                "-o" | "--out-dir" => args.out_dir = Some(PathBuf::from(value()?)),
+                "--check" => args.check = true,
+                "-q" | "--quiet" => args.quiet = true,
+                "-j" | "--threads" => {
+                    let threads = value()?;
+                    match threads.parse::<usize>() {
+                        Ok(threads) if threads > 0 => args.threads = Some(threads),
+                        _ => return Err(invalid_value(&flag, &threads, "a positive number")),
+                    }
+                }
+                "--prod" => args.is_prod = true,
+                "--dev" => args.is_prod = false,
+                "--ssr" => args.ssr = true,
+                "--custom-element" => args.is_custom_element = true,
+                "--source-map" => args.source_map = true,
+                "--in-source-map" => args.in_source_map = true,
+                "--gen-default-as" => args.gen_default_as = Some(value()?),
+                "--props-destructure" => {
+                    args.props_destructure = Some(match value()?.as_str() {
+                        "true" => PropsDestructureConfig::True,
+                        "false" => PropsDestructureConfig::False,
+                        "error" => PropsDestructureConfig::Error,
+                        other => return Err(invalid_value(&flag, other, "true, false or error")),
+                    })
+                }
+                "--delimiters" => {
+                    let delimiters = value()?;
+                    match delimiters.split_once(',') {
+                        Some((start, end)) if !start.is_empty() && !end.is_empty() => {
+                            args.delimiters = Some((start.to_string(), end.to_string()))
+                        }
+                        _ => return Err(invalid_value(&flag, &delimiters, "START,END")),
+                    }
+                }
+                "--whitespace" => {
+                    args.whitespace = Some(match value()?.as_str() {
+                        "condense" => WhitespaceMode::Condense,
+                        "preserve" => WhitespaceMode::Preserve,
+                        other => return Err(invalid_value(&flag, other, "condense or preserve")),
+                    })
+                }
+                "--custom-element-prefix" => args.custom_element_prefixes.push(value()?),
+                "--custom-element-pattern" => args.custom_element_patterns.push(value()?),
+                "--comments" => args.comments = Some(true),
+                "--no-comments" => args.comments = Some(false),
+                "--parse-mode" => {
+                    args.parse_mode = Some(match value()?.as_str() {
+                        "hard-fail" => ParseMode::HardFailOnError,
+                        "report-all" => ParseMode::SoftReportAll,
+                        "recover-safe" => ParseMode::SoftRecoverSafe,
+                        "recover-unsafe" => ParseMode::SoftRecoverUnsafe,
+                        other => {
+                            return Err(invalid_value(
+                                &flag,
+                                other,
+                                "hard-fail, report-all, recover-safe or recover-unsafe",
+                            ))
+                        }
+                    })
+                }
+                "--plugin" => args.plugins.push(value()?),
+                _ => return Err(format!("unknown option `{flag}`")),
+            }
+        }
+
+        if args.inputs.is_empty() {
+            return Err("no input files".to_string());
+        }
+
+        Ok(ParsedArgs::Run(Box::new(args)))
+    }
+
+    /// Options for compiling a single file
+    fn compile_options<'a>(
+        &'a self,
+        input: &'a Input,
+        is_custom_element_tag: &Option<TagPredicate>,
+    ) -> CompileOptions<'a> {
+        let in_source_map = if self.in_source_map {
+            let mut map_path = input.path.clone().into_os_string();
+            map_path.push(".map");
+            fs::read_to_string(map_path).ok()
+        } else {
+            None
+        };
+
+        CompileOptions {
+            filename: input.path.to_string_lossy(),
+            id: input.relative_path.to_string_lossy(),
+            is_prod: Some(self.is_prod),
+            is_custom_element: Some(self.is_custom_element),
+            ssr: Some(self.ssr),
+            props_destructure: self.props_destructure,
+            in_source_map: in_source_map.map(Cow::Owned),
+            delimiters: self
+                .delimiters
+                .as_ref()
+                .map(|(start, end)| (Cow::Borrowed(start.as_str()), Cow::Borrowed(end.as_str()))),
+            whitespace: self.whitespace,
+            is_custom_element_tag: is_custom_element_tag.to_owned(),
+            comments: self.comments,
+            gen_default_as: self.gen_default_as.as_deref().map(Cow::Borrowed),
+            source_map: Some(self.source_map),
+            parse_mode: self.parse_mode,
+            plugins: self
+                .plugins
+                .iter()
+                .map(|plugin| Cow::Borrowed(plugin.as_str()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+fn invalid_value(flag: &str, value: &str, expected: &str) -> String {
+    format!("invalid value `{value}` for `{flag}`, expected {expected}")
+}
+
+/// A `.vue` file to compile
+struct Input {
+    path: PathBuf,
+    /// Path relative to the input directory, or the file name for the file inputs.
+    /// The output is written to `out_dir` using this path
+    relative_path: PathBuf,
+}
+
+/// Expands the directories to the `.vue` files they contain
+fn collect_inputs(paths: &[PathBuf]) -> Result<Vec<Input>, String> {
+    let mut inputs = Vec::new();
+
+    for path in paths {
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+
+        if metadata.is_dir() {
+            let mut files = Vec::new();
+            collect_vue_files(path, &mut files)
+                .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+            files.sort();
+
+            inputs.extend(files.into_iter().map(|file| Input {
+                relative_path: file.strip_prefix(path).unwrap_or(&file).to_path_buf(),
+                path: file,
+            }));
+        } else {
+            inputs.push(Input {
+                relative_path: PathBuf::from(path.file_name().unwrap_or(path.as_os_str())),
+                path: path.to_owned(),
+            });
+        }
+    }
+
+    Ok(inputs)
+}
+
+fn collect_vue_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        if entry.file_type()?.is_dir() {
+            if file_name != "node_modules" && !file_name.starts_with('.') {
+                collect_vue_files(&path, out)?;
+            }
+        } else if file_name.ends_with(".vue") {
+            out.push(path);
+        }
+    }
+
+    Ok(())
 }
 
-fn test_real_compilation() {
-    let test = include_str!("../benches/fixtures/input.vue");
+/// Reports the diagnostics of a single file and writes its output.
+/// `Err` is only returned for the IO errors
+fn emit_result(
+    input: &Input,
+    source: &str,
+    result: Result<CompileResult, CompileError>,
+    args: &Args,
+    summary: &mut Summary,
+) -> Result<(), String> {
+    let filename = input.path.to_string_lossy();
 
-    let compiled_code = match compile_sync_naive(test, true) {
+    let mut report = |error: &CompileError| {
+        match error.get_severity() {
+            SeverityLevel::Warning => summary.warnings += 1,
+            SeverityLevel::RecoverableError | SeverityLevel::UnrecoverableError => {
+                summary.errors += 1
+            }
+        }
+        eprintln!("{}\n", error.to_diagnostic_string(source, &filename));
+    };
+
+    let result = match result {
         Ok(result) => result,
-        Err(e) => std::panic::panic_any(e)
+        Err(error) => {
+            report(&error);
+            return Ok(());
+        }
     };
+    result.errors.iter().for_each(&mut report);
 
-    #[cfg(feature = "dbg_print")]
-    {
-        println!("Result: {:#?}", ast);
-        println!("Remaining: {:?}", res.0);
+    if args.check {
+        return Ok(());
+    }
 
-        println!();
-        println!("SFC blocks length: {}", ast.len());
+    let out_base = match args.out_dir {
+        Some(ref out_dir) => out_dir.join(&input.relative_path),
+        None => input.path.to_owned(),
+    };
+    if let Some(parent) = out_base.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
 
-        println!();
-        println!("Scopes: {:#?}", scope_helper);
+    let module_path = write_output(&out_base, &result).map_err(|e| e.to_string())?;
+    if !args.quiet {
+        println!("{} -> {}", input.path.display(), module_path.display());
     }
 
-    // Real codegen
-    println!("\n[Real File Compile Result]");
-    println!("{compiled_code}");
-    // println!("{}", compile_sfc(sfc_blocks, scope_helper).unwrap());
+    Ok(())
+}
+
+/// Writes the module, the styles and their source maps next to `out_base`
+/// (which is the output path of the `.vue` file itself).
+/// Returns the path of the module
+fn write_output(out_base: &Path, result: &CompileResult) -> std::io::Result<PathBuf> {
+    let with_extension = |extension: &str| {
+        let mut path = out_base.to_owned().into_os_string();
+        path.push(".");
+        path.push(extension);
+        PathBuf::from(path)
+    };
+
+    let module_path = with_extension(if result.is_ts { "ts" } else { "js" });
+    write_with_source_map(
+        &module_path,
+        &result.code,
+        result.source_map.as_deref(),
+        |map_file_name| format!("\n//# sourceMappingURL={map_file_name}\n"),
+    )?;
+
+    for (index, style) in result.styles.iter().enumerate() {
+        let extension = if style.is_compiled || style.lang.is_empty() {
+            "css"
+        } else {
+            style.lang.as_str()
+        };
+        let style_path = with_extension(&format!("{index}.{extension}"));
+        write_with_source_map(
+            &style_path,
+            &style.code,
+            style.source_map.as_deref(),
+            |map_file_name| format!("\n/*# sourceMappingURL={map_file_name} */\n"),
+        )?;
+    }
+
+    Ok(module_path)
+}
+
+fn write_with_source_map(
+    path: &Path,
+    code: &str,
+    source_map: Option<&str>,
+    source_mapping_url: impl Fn(&str) -> String,
+) -> std::io::Result<()> {
+    let Some(source_map) = source_map else {
+        return fs::write(path, code);
+    };
+
+    let mut map_path = path.to_owned().into_os_string();
+    map_path.push(".map");
+    let map_path = PathBuf::from(map_path);
+    let map_file_name = map_path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+
+    fs::write(
+        path,
+        format!("{code}{}", source_mapping_url(&map_file_name)),
+    )?;
+    fs::write(&map_path, source_map)
 }
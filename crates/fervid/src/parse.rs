@@ -0,0 +1,373 @@
+//! Parsing an SFC into its blocks without compiling it, similar to `parse` of `@vue/compiler-sfc`.
+//! This is meant for tooling which needs the raw blocks, e.g. IDE helpers or documentation generators.
+
+use std::{borrow::Cow, ops::Range};
+
+use fervid_core::{error::get_line_column, AttributeOrBinding, SfcDescriptor};
+use fervid_parser::{
+    ParseMode, SfcParser, INTERPOLATION_END_PAT_DEFAULT, INTERPOLATION_START_PAT_DEFAULT,
+};
+use swc_core::common::Span;
+
+use crate::errors::CompileError;
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions<'o> {
+    /// Interpolation delimiters. Default: `{{` and `}}`
+    pub delimiters: Option<(Cow<'o, str>, Cow<'o, str>)>,
+    pub parse_mode: Option<ParseMode>,
+    /// Whether to skip the blocks with empty or whitespace-only content. Default: `true`
+    pub ignore_empty: Option<bool>,
+}
+
+/// Blocks of an SFC, see [`parse`]
+#[derive(Debug)]
+pub struct ParsedSfc {
+    pub template: Option<ParsedSfcBlock>,
+    pub script: Option<ParsedSfcBlock>,
+    pub script_setup: Option<ParsedSfcBlock>,
+    pub styles: Vec<ParsedSfcBlock>,
+    pub custom_blocks: Vec<ParsedSfcBlock>,
+    /// Non-fatal errors, e.g. a duplicate `<template>`
+    pub errors: Vec<CompileError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedSfcBlock {
+    /// Tag name of the block, e.g. `template`, `script`, `style` or `i18n`
+    pub block_type: String,
+    /// Raw content between the starting and the ending tags
+    pub content: String,
+    /// Attributes as written, in order. Attributes without a value (e.g. `scoped`) have an empty one
+    pub attrs: Vec<(String, String)>,
+    /// Location of [`ParsedSfcBlock::content`]
+    pub loc: SourceLocation,
+    pub lang: Option<String>,
+    pub src: Option<String>,
+    pub setup: bool,
+    pub scoped: bool,
+    /// Name of the CSS module, empty for a plain `module`
+    pub module: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition {
+    /// 0-based byte offset
+    pub offset: usize,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+}
+
+/// Parses an SFC into its blocks with their content, attributes and locations.
+///
+/// Unlike [`crate::compile`], scripts are not analyzed and templates are not transformed,
+/// but the errors are the same as the ones reported by the parser during compilation.
+pub fn parse(source: &str, options: ParseOptions) -> Result<ParsedSfc, CompileError> {
+    let (delimiter_start, delimiter_end) = match options.delimiters {
+        Some((ref start, ref end)) => (start.as_ref(), end.as_ref()),
+        None => (
+            INTERPOLATION_START_PAT_DEFAULT,
+            INTERPOLATION_END_PAT_DEFAULT,
+        ),
+    };
+
+    let mut parse_errors = Vec::new();
+    let mut parser = SfcParser::new(source, &mut parse_errors)
+        .with_delimiters(delimiter_start, delimiter_end)
+        .with_mode(options.parse_mode.unwrap_or_default());
+    parser.ignore_empty = options.ignore_empty.unwrap_or(true);
+
+    let SfcDescriptor {
+        template,
+        script_legacy,
+        script_setup,
+        styles,
+        custom_blocks,
+    } = parser.parse_sfc()?;
+
+    let block = |block_type: &str, attrs: &[AttributeOrBinding], content: Range<usize>| {
+        ParsedSfcBlock::new(source, block_type, attrs, content)
+    };
+
+    Ok(ParsedSfc {
+        template: template.map(|template| {
+            let content = content_range(source, "template", template.span);
+            block("template", &template.attrs, content)
+        }),
+        script: script_legacy.map(|script| {
+            let content = content_range(source, "script", script.span);
+            block("script", &script.attrs, content)
+        }),
+        script_setup: script_setup.map(|script| {
+            let content = content_range(source, "script", script.span);
+            block("script", &script.attrs, content)
+        }),
+        styles: styles
+            .into_iter()
+            .map(|style| {
+                // Span of a non-empty `<style>` is already the span of its content
+                let content = if style.content.is_empty() {
+                    content_range(source, "style", style.span)
+                } else {
+                    span_range(style.span)
+                };
+                block("style", &style.attrs, content)
+            })
+            .collect(),
+        custom_blocks: custom_blocks
+            .into_iter()
+            .map(|custom_block| {
+                let tag_name = custom_block.starting_tag.tag_name.as_str();
+                let content = content_range(source, tag_name, custom_block.span);
+                block(tag_name, &custom_block.starting_tag.attributes, content)
+            })
+            .collect(),
+        errors: parse_errors.into_iter().map(From::from).collect(),
+    })
+}
+
+impl ParsedSfcBlock {
+    fn new(
+        source: &str,
+        block_type: &str,
+        attributes: &[AttributeOrBinding],
+        content: Range<usize>,
+    ) -> Self {
+        let attrs = regular_attributes(attributes);
+
+        let attr = |name: &str| {
+            attrs
+                .iter()
+                .find(|(attr_name, _)| attr_name == name)
+                .map(|(_, value)| value.to_owned())
+        };
+        let non_empty_attr = |name: &str| attr(name).filter(|value| !value.is_empty());
+
+        ParsedSfcBlock {
+            block_type: block_type.to_owned(),
+            content: source[content.to_owned()].to_owned(),
+            loc: SourceLocation {
+                start: SourcePosition::new(source, content.start),
+                end: SourcePosition::new(source, content.end),
+            },
+            lang: non_empty_attr("lang"),
+            src: non_empty_attr("src"),
+            setup: attr("setup").is_some(),
+            scoped: attr("scoped").is_some(),
+            module: attr("module"),
+            attrs,
+        }
+    }
+}
+
+/// Regular attributes of a block as name-value pairs, attributes without a value have an empty one
+pub(crate) fn regular_attributes(attributes: &[AttributeOrBinding]) -> Vec<(String, String)> {
+    attributes
+        .iter()
+        .filter_map(|attr| match attr {
+            AttributeOrBinding::RegularAttribute { name, value, .. } => {
+                Some((name.to_string(), value.to_string()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+impl SourcePosition {
+    fn new(source: &str, offset: usize) -> Self {
+        let (line, column) = get_line_column(source, offset);
+        SourcePosition {
+            offset,
+            line,
+            column,
+        }
+    }
+}
+
+/// Converts a parser span (first byte at position 1) to a range of `source`
+fn span_range(span: Span) -> Range<usize> {
+    (span.lo.0 as usize).saturating_sub(1)..(span.hi.0 as usize).saturating_sub(1)
+}
+
+/// Finds the content of an element spanning `span`,
+/// i.e. the part between the end of its starting tag and the beginning of its ending tag
+fn content_range(source: &str, tag_name: &str, span: Span) -> Range<usize> {
+    let element_range = span_range(span);
+    let Some(element) = source.get(element_range.to_owned()) else {
+        return 0..0;
+    };
+
+    // Attribute values may contain `>`
+    let mut quote = None;
+    let starting_tag_end = element
+        .char_indices()
+        .find(|&(_, c)| match quote {
+            Some(q) if c == q => {
+                quote = None;
+                false
+            }
+            Some(_) => false,
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                false
+            }
+            None => c == '>',
+        })
+        .map_or(element.len(), |(idx, _)| idx + 1);
+
+    // Self-closing, e.g. `<template/>`
+    if element[..starting_tag_end].ends_with("/>") {
+        let offset = element_range.start + starting_tag_end;
+        return offset..offset;
+    }
+
+    let ending_tag = format!("</{}", tag_name.to_ascii_lowercase());
+    let content_end = element
+        .to_ascii_lowercase()
+        .rfind(&ending_tag)
+        .filter(|&idx| idx >= starting_tag_end)
+        .unwrap_or(element.len());
+
+    element_range.start + starting_tag_end..element_range.start + content_end
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::error::ErrorCode;
+
+    use super::*;
+
+    const SOURCE: &str = r#"<template lang="html">
+  <div :title="a > b">{{ msg }}</div>
+</template>
+
+<script setup lang="ts" generic="T extends string">
+const msg = 'Hello'
+</script>
+
+<style scoped module="classes">
+.a { color: red }
+</style>
+
+<i18n locale="en">{ "hello": "Hello" }</i18n>
+"#;
+
+    #[test]
+    fn it_parses_blocks() {
+        let parsed = parse(SOURCE, ParseOptions::default()).unwrap();
+        assert!(parsed.errors.is_empty());
+        assert!(parsed.script.is_none());
+
+        let template = parsed.template.expect("Should exist");
+        assert_eq!(
+            "\n  <div :title=\"a > b\">{{ msg }}</div>\n",
+            template.content
+        );
+        assert_eq!(Some("html".to_owned()), template.lang);
+        assert_eq!(
+            SourcePosition {
+                offset: 22,
+                line: 1,
+                column: 23
+            },
+            template.loc.start
+        );
+        assert_eq!(
+            SourcePosition {
+                offset: 61,
+                line: 3,
+                column: 1
+            },
+            template.loc.end
+        );
+
+        let script_setup = parsed.script_setup.expect("Should exist");
+        assert_eq!("script", script_setup.block_type);
+        assert_eq!("\nconst msg = 'Hello'\n", script_setup.content);
+        assert!(script_setup.setup);
+        assert_eq!(Some("ts".to_owned()), script_setup.lang);
+        assert_eq!(
+            vec![
+                ("setup".to_owned(), String::new()),
+                ("lang".to_owned(), "ts".to_owned()),
+                ("generic".to_owned(), "T extends string".to_owned()),
+            ],
+            script_setup.attrs
+        );
+        assert_eq!(5, script_setup.loc.start.line);
+
+        let [style] = parsed.styles.as_slice() else {
+            panic!("Expected one style")
+        };
+        assert_eq!("\n.a { color: red }\n", style.content);
+        assert_eq!(
+            &SOURCE[style.loc.start.offset..style.loc.end.offset],
+            style.content
+        );
+        assert!(style.scoped);
+        assert_eq!(Some("classes".to_owned()), style.module);
+        assert_eq!(None, style.lang);
+
+        let [i18n] = parsed.custom_blocks.as_slice() else {
+            panic!("Expected one custom block")
+        };
+        assert_eq!("i18n", i18n.block_type);
+        assert_eq!("{ \"hello\": \"Hello\" }", i18n.content);
+        assert_eq!(vec![("locale".to_owned(), "en".to_owned())], i18n.attrs);
+    }
+
+    #[test]
+    fn it_keeps_empty_blocks_when_asked() {
+        let source = "<template/>\n<script src=\"./foo.js\"></script>";
+
+        let parsed = parse(source, ParseOptions::default()).unwrap();
+        assert!(parsed.script.is_none());
+
+        let parsed = parse(
+            source,
+            ParseOptions {
+                ignore_empty: Some(false),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let template = parsed.template.expect("Should exist");
+        assert_eq!("", template.content);
+        assert_eq!(11, template.loc.start.offset);
+
+        let script = parsed.script.expect("Should exist");
+        assert_eq!("", script.content);
+        assert_eq!(Some("./foo.js".to_owned()), script.src);
+        assert_eq!(35, script.loc.start.offset);
+        assert_eq!(35, script.loc.end.offset);
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        let parsed = parse(
+            "<template><div></div></template><template><span></span></template>",
+            ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(1, parsed.errors.len());
+        assert_eq!("SFC_DUPLICATE_TEMPLATE", parsed.errors[0].error_code());
+
+        let result = parse(
+            "<template><div>{{ a + }}</div></template>",
+            ParseOptions {
+                parse_mode: Some(ParseMode::HardFailOnError),
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+    }
+}
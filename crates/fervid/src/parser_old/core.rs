@@ -124,6 +124,7 @@ fn parse_root_block<'a>(
             out.template = Some(SfcTemplateBlock {
                 lang,
                 roots: Vec::new(),
+                attrs: vec![],
                 span: DUMMY_SP, // TODO
             });
 
@@ -140,6 +141,7 @@ fn parse_root_block<'a>(
         out.template = Some(SfcTemplateBlock {
             lang,
             roots: children,
+            attrs: vec![],
             span: DUMMY_SP, // TODO
         });
 
@@ -168,6 +170,7 @@ fn parse_root_block<'a>(
                         content: $content,
                         lang,
                         is_setup,
+                        attrs: vec![],
                         span: DUMMY_SP,
                     });
                 } else {
@@ -175,6 +178,7 @@ fn parse_root_block<'a>(
                         content: $content,
                         lang,
                         is_setup,
+                        attrs: vec![],
                         span: DUMMY_SP,
                     })
                 }
@@ -242,7 +246,9 @@ fn parse_root_block<'a>(
         content: content.into(),
         is_scoped,
         is_module,
-        span: DUMMY_SP
+        attrs: vec![],
+        span: DUMMY_SP,
+        source_map: None,
     });
 
     Ok(input)
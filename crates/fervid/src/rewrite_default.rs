@@ -0,0 +1,282 @@
+//! Rewriting of `export default` in the source code,
+//! same as `rewriteDefault` of `@vue/compiler-sfc`.
+
+use std::ops::Range;
+
+use fervid_parser::SfcParser;
+use swc_core::{
+    common::{BytePos, Span, Spanned},
+    ecma::ast::{
+        DefaultDecl, ExportSpecifier, ModuleDecl, ModuleExportName, ModuleItem, NamedExport,
+    },
+};
+use swc_ecma_parser::{EsSyntax, Syntax, TsSyntax};
+
+use crate::errors::CompileError;
+
+/// Local name of a default export re-exported from another module
+const DEFAULT_IMPORT_NAME: &str = "__VUE_DEFAULT__";
+
+/// Rewrites the default export of a module to a `const` declaration named `as_name`,
+/// e.g. `export default { name: 'Foo' }` becomes `const _sfc_main = { name: 'Foo' }`.
+/// When there is no default export, `const as_name = {}` is appended.
+///
+/// Unlike the official implementation, the source is not parsed with Babel plugins,
+/// TypeScript syntax is enabled with `is_ts` instead.
+pub fn rewrite_default(source: &str, as_name: &str, is_ts: bool) -> Result<String, CompileError> {
+    let syntax = if is_ts {
+        Syntax::Typescript(TsSyntax::default())
+    } else {
+        Syntax::Es(EsSyntax::default())
+    };
+    let span = Span::new(BytePos(1), BytePos(source.len() as u32 + 1));
+
+    let mut errors = Vec::new();
+    let mut parser = SfcParser::new(source, &mut errors);
+    let module = parser.parse_module(source, syntax, span)?;
+    if let Some(error) = errors.into_iter().next() {
+        return Err(error.into());
+    }
+
+    let mut prepended = String::new();
+    let mut replacements: Vec<(Range<usize>, String)> = Vec::new();
+    let mut default_name: Option<String> = None;
+
+    for module_item in module.body.iter() {
+        let ModuleItem::ModuleDecl(module_decl) = module_item else {
+            continue;
+        };
+
+        match module_decl {
+            // `export default foo` -> `const as_name = foo`
+            ModuleDecl::ExportDefaultExpr(export_default) => {
+                let prefix = range(export_default.span.lo, export_default.expr.span_lo());
+                replacements.push((prefix, format!("const {} = ", as_name)));
+                default_name = None;
+            }
+
+            // `export default class Foo {}` -> `class Foo {}` and `const as_name = Foo`
+            // `export default class {}` -> `const as_name = class {}`
+            ModuleDecl::ExportDefaultDecl(export_default) => {
+                let ident = match export_default.decl {
+                    DefaultDecl::Class(ref class_expr) => class_expr.ident.as_ref(),
+                    DefaultDecl::Fn(ref fn_expr) => fn_expr.ident.as_ref(),
+                    // Not a value
+                    DefaultDecl::TsInterfaceDecl(_) => continue,
+                };
+
+                let prefix = range(export_default.span.lo, export_default.decl.span_lo());
+                match ident {
+                    Some(ident) => {
+                        replacements.push((prefix, String::new()));
+                        default_name = Some(ident.sym.to_string());
+                    }
+                    None => {
+                        replacements.push((prefix, format!("const {} = ", as_name)));
+                        default_name = None;
+                    }
+                }
+            }
+
+            // `export { foo as default }` -> `const as_name = foo`
+            ModuleDecl::ExportNamed(named_export) if !named_export.type_only => {
+                let Some(rewritten) = rewrite_named_export(source, named_export, &mut prepended)
+                else {
+                    continue;
+                };
+
+                replacements.push((
+                    range(named_export.span.lo, named_export.span.hi),
+                    rewritten.0,
+                ));
+                default_name = Some(rewritten.1);
+            }
+
+            _ => {}
+        }
+    }
+
+    let has_default = !replacements.is_empty();
+
+    let mut result = String::with_capacity(source.len() + prepended.len() + as_name.len() + 16);
+    result.push_str(&prepended);
+
+    let mut position = 0;
+    replacements.sort_by_key(|(range, _)| range.start);
+    for (range, replacement) in replacements {
+        result.push_str(&source[position..range.start]);
+        result.push_str(&replacement);
+        position = range.end;
+    }
+    result.push_str(&source[position..]);
+
+    match default_name {
+        Some(name) => result.push_str(&format!("\nconst {} = {}", as_name, name)),
+        None if !has_default => result.push_str(&format!("\nconst {} = {{}}", as_name)),
+        None => {}
+    }
+
+    Ok(result)
+}
+
+/// When `named_export` exports a default, returns the export without it and the name of the default.
+/// Defaults re-exported from other modules are imported in `prepended`
+fn rewrite_named_export(
+    source: &str,
+    named_export: &NamedExport,
+    prepended: &mut String,
+) -> Option<(String, String)> {
+    let mut default_name = None;
+    let mut remaining = Vec::with_capacity(named_export.specifiers.len());
+
+    for specifier in named_export.specifiers.iter() {
+        let ExportSpecifier::Named(named) = specifier else {
+            remaining.push(&source[range(specifier.span_lo(), specifier.span_hi())]);
+            continue;
+        };
+
+        let exported = named.exported.as_ref().unwrap_or(&named.orig);
+        if default_name.is_some() || named.is_type_only || !is_default(exported) {
+            remaining.push(&source[range(named.span.lo, named.span.hi)]);
+            continue;
+        }
+
+        let orig = &source[range(named.orig.span_lo(), named.orig.span_hi())];
+        default_name = Some(match named_export.src {
+            // `export { foo as default } from './foo'`
+            Some(ref src) => {
+                let src = &source[range(src.span.lo, src.span.hi)];
+                prepended.push_str(&format!(
+                    "import {{ {} as {} }} from {}\n",
+                    orig, DEFAULT_IMPORT_NAME, src
+                ));
+                DEFAULT_IMPORT_NAME.to_owned()
+            }
+            None => orig.to_owned(),
+        });
+    }
+
+    let default_name = default_name?;
+    if remaining.is_empty() {
+        return Some((String::new(), default_name));
+    }
+
+    let from = match named_export.src {
+        Some(ref src) => format!(" from {}", &source[range(src.span.lo, src.span.hi)]),
+        None => String::new(),
+    };
+    Some((
+        format!("export {{ {} }}{}", remaining.join(", "), from),
+        default_name,
+    ))
+}
+
+fn is_default(name: &ModuleExportName) -> bool {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym == "default",
+        ModuleExportName::Str(s) => s.value == "default",
+    }
+}
+
+/// Converts the parser positions (first byte at position 1) to a range of the source
+fn range(lo: BytePos, hi: BytePos) -> Range<usize> {
+    (lo.0 as usize - 1)..(hi.0 as usize - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rewrite_default;
+
+    // Adapted from
+    // https://github.com/vuejs/core/blob/a41c5f1f4367a9f41bcdb8c4e02f54b2378e577d/packages/compiler-sfc/__tests__/rewriteDefault.spec.ts
+
+    fn rewrite(source: &str) -> String {
+        rewrite_default(source, "script", false).unwrap()
+    }
+
+    #[test]
+    fn without_export_default() {
+        assert_eq!(
+            "export const a = {}\nconst script = {}",
+            rewrite("export const a = {}")
+        );
+    }
+
+    #[test]
+    fn rewrite_export_default() {
+        assert_eq!(
+            "const script = {}",
+            rewrite("export  default {}").trim_start()
+        );
+        assert_eq!(
+            "const a = 1\nconst script = a",
+            rewrite("const a = 1\nexport default a")
+        );
+    }
+
+    #[test]
+    fn rewrite_export_named_default() {
+        assert_eq!(
+            "const a = 1\n\nconst script = a",
+            rewrite("const a = 1\nexport { a as default }")
+        );
+        assert_eq!(
+            "const a = 1, b = 2\nexport { b }\nconst script = a",
+            rewrite("const a = 1, b = 2\nexport { a as default, b }")
+        );
+        assert_eq!(
+            "const a = 1, b = 2\nexport { b }\nconst script = a",
+            rewrite("const a = 1, b = 2\nexport { b, a as default }")
+        );
+    }
+
+    #[test]
+    fn rewrite_export_named_default_from() {
+        assert_eq!(
+            "import { default as __VUE_DEFAULT__ } from './index.js'\n\nconst script = __VUE_DEFAULT__",
+            rewrite("export { default } from './index.js'")
+        );
+        assert_eq!(
+            "import { foo as __VUE_DEFAULT__ } from \"./index.js\"\nexport { bar } from \"./index.js\"\nconst script = __VUE_DEFAULT__",
+            rewrite("export { foo as default, bar } from \"./index.js\"")
+        );
+    }
+
+    #[test]
+    fn rewrite_export_default_declarations() {
+        assert_eq!(
+            "function foo() {}\nconst script = foo",
+            rewrite("export default function foo() {}")
+        );
+        assert_eq!(
+            "const script = async function () {}",
+            rewrite("export default async function () {}")
+        );
+        assert_eq!(
+            "class Foo {}\nconst script = Foo",
+            rewrite("export default class Foo {}")
+        );
+        assert_eq!(
+            "const script = class {}",
+            rewrite("export default class {}")
+        );
+    }
+
+    #[test]
+    fn it_handles_typescript() {
+        assert_eq!(
+            "const script = { name: 'foo' } satisfies Options",
+            rewrite_default(
+                "export default { name: 'foo' } satisfies Options",
+                "script",
+                true
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "export default interface Foo {}\nconst script = {}",
+            rewrite_default("export default interface Foo {}", "script", true).unwrap()
+        );
+        assert!(rewrite_default("export default {", "script", false).is_err());
+    }
+}
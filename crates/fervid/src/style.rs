@@ -0,0 +1,114 @@
+//! Compilation of a standalone stylesheet, i.e. not a part of an SFC.
+
+use std::borrow::Cow;
+
+use fervid_css::{transform_css, CssSourceMapConfig, TransformCssConfig};
+use fervid_transform::{error::TransformError, style::create_style_scope};
+use swc_core::common::{BytePos, Span};
+
+use crate::errors::CompileError;
+
+#[derive(Debug, Clone)]
+pub struct CompileStyleOptions<'o> {
+    /// Filename used in the source map
+    pub filename: Cow<'o, str>,
+    /// Scope ID, with or without the `data-v-` prefix
+    pub id: Cow<'o, str>,
+    /// Whether the selectors are scoped with `id`, i.e. the style is `<style scoped>`
+    pub scoped: bool,
+    pub source_map: Option<bool>,
+}
+
+pub struct CompileStyleResult {
+    /// Transformed CSS, empty when it could not be parsed
+    pub code: String,
+    pub source_map: Option<String>,
+    pub errors: Vec<CompileError>,
+}
+
+/// Compiles the content of a `<style>` block, e.g. when the bundler handles the styles separately.
+/// Only plain CSS is supported, preprocessors must be run beforehand.
+pub fn compile_style(source: &str, options: CompileStyleOptions) -> CompileStyleResult {
+    let span = Span::new(BytePos(1), BytePos(source.len() as u32 + 1));
+    let scope = options
+        .scoped
+        .then(|| create_style_scope(options.id.trim_start_matches("data-v-")));
+
+    let mut css_errors = Vec::new();
+    let result = transform_css(
+        source,
+        span,
+        scope.as_deref(),
+        &mut css_errors,
+        TransformCssConfig {
+            source_map: options
+                .source_map
+                .unwrap_or(false)
+                .then_some(CssSourceMapConfig {
+                    source,
+                    filename: &options.filename,
+                }),
+            ..Default::default()
+        },
+    );
+
+    let errors = css_errors
+        .into_iter()
+        .map(|e| CompileError::TransformError(TransformError::from(e)))
+        .collect();
+
+    match result {
+        Some(transformed) => CompileStyleResult {
+            code: transformed.code,
+            source_map: transformed.source_map,
+            errors,
+        },
+        None => CompileStyleResult {
+            code: String::new(),
+            source_map: None,
+            errors,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(scoped: bool) -> CompileStyleOptions<'static> {
+        CompileStyleOptions {
+            filename: Cow::Borrowed("Component.vue"),
+            id: Cow::Borrowed("data-v-abcd1234"),
+            scoped,
+            source_map: None,
+        }
+    }
+
+    #[test]
+    fn it_compiles_scoped_style() {
+        let result = compile_style(".a { color: red }", options(true));
+        assert!(result.errors.is_empty());
+        assert_eq!(".a[data-v-abcd1234]{color:red}", result.code);
+
+        // The same scope without the prefix
+        let result = compile_style(
+            ".a { color: red }",
+            CompileStyleOptions {
+                id: Cow::Borrowed("abcd1234"),
+                source_map: Some(true),
+                ..options(true)
+            },
+        );
+        assert_eq!(".a[data-v-abcd1234]{color:red}", result.code);
+        assert!(result.source_map.is_some());
+
+        let result = compile_style(".a { color: red }", options(false));
+        assert_eq!(".a{color:red}", result.code);
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        let result = compile_style(".a { color: red", options(true));
+        assert!(!result.errors.is_empty());
+    }
+}
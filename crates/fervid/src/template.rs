@@ -0,0 +1,233 @@
+//! Compilation of a standalone template, i.e. not a part of an SFC.
+
+use std::borrow::Cow;
+
+use fervid_codegen::CodegenContext;
+use fervid_core::{fervid_atom, FervidAtom, TemplateGenerationMode};
+use fervid_parser::{
+    ParseMode, SfcParser, INTERPOLATION_END_PAT_DEFAULT, INTERPOLATION_START_PAT_DEFAULT,
+};
+use fervid_transform::{
+    template::transform_and_record_template, BindingsHelper, SetupBinding, TagPredicate,
+    WhitespaceMode,
+};
+use swc_core::{
+    common::{FileName, DUMMY_SP},
+    ecma::ast::{
+        Decl, ExportDecl, Expr, FnDecl, Ident, ImportDecl, Lit, Module, ModuleDecl, ModuleItem,
+        Null, Str,
+    },
+};
+
+use crate::errors::CompileError;
+
+#[derive(Debug, Clone)]
+pub struct CompileTemplateOptions<'o> {
+    pub filename: Cow<'o, str>,
+    pub is_prod: Option<bool>,
+    /// Bindings which the template refers to, e.g. [`crate::CompileResult::setup_bindings`].
+    /// Same as `bindingMetadata` in the official compiler
+    pub binding_metadata: Vec<SetupBinding>,
+    /// How the template is generated, see [`TemplateGenerationMode`]. Default: `RenderFn`
+    pub generation_mode: Option<TemplateGenerationMode>,
+    /// Interpolation delimiters. Default: `{{` and `}}`
+    pub delimiters: Option<(Cow<'o, str>, Cow<'o, str>)>,
+    pub whitespace: Option<WhitespaceMode>,
+    pub is_custom_element_tag: Option<TagPredicate>,
+    pub comments: Option<bool>,
+    pub source_map: Option<bool>,
+    pub parse_mode: Option<ParseMode>,
+}
+
+pub struct CompileTemplateResult {
+    /// For [`TemplateGenerationMode::RenderFn`], a module exporting the `render` function.\
+    /// For [`TemplateGenerationMode::Inline`], the render arrow function to be returned from `setup`,
+    /// the caller is responsible for importing the [`CompileTemplateResult::imports`].
+    pub code: String,
+    /// Helpers imported from `vue`, e.g. `createElementBlock`.
+    /// In the code they are used with an underscore, e.g. `_createElementBlock`
+    pub imports: Vec<String>,
+    pub errors: Vec<CompileError>,
+    pub source_map: Option<String>,
+}
+
+/// Compiles a template without the SFC around it, e.g. for template-only HMR updates.
+/// Template variables are resolved using [`CompileTemplateOptions::binding_metadata`].
+pub fn compile_template(
+    source: &str,
+    options: CompileTemplateOptions,
+) -> Result<CompileTemplateResult, CompileError> {
+    let generate_source_map = options.source_map.unwrap_or(false);
+    let (delimiter_start, delimiter_end) = match options.delimiters {
+        Some((ref start, ref end)) => (start.as_ref(), end.as_ref()),
+        None => (
+            INTERPOLATION_START_PAT_DEFAULT,
+            INTERPOLATION_END_PAT_DEFAULT,
+        ),
+    };
+
+    // Parse
+    let mut parse_errors = Vec::new();
+    let mut parser = SfcParser::new(source, &mut parse_errors)
+        .with_delimiters(delimiter_start, delimiter_end)
+        .with_mode(options.parse_mode.unwrap_or_default());
+    let mut template_block = parser.parse_template()?;
+    let mut all_errors: Vec<CompileError> = parse_errors.into_iter().map(From::from).collect();
+
+    // Transform
+    let generation_mode = options.generation_mode.unwrap_or_default();
+    let mut bindings_helper = BindingsHelper {
+        is_prod: options.is_prod.unwrap_or_default(),
+        is_custom_element_tag: options.is_custom_element_tag,
+        comments: options.comments,
+        setup_bindings: options.binding_metadata,
+        template_generation_mode: generation_mode,
+        ..Default::default()
+    };
+    transform_and_record_template(
+        &mut template_block,
+        &mut bindings_helper,
+        options.whitespace.unwrap_or_default(),
+    );
+
+    // Codegen
+    let mut ctx = CodegenContext::with_bindings_helper(bindings_helper);
+    let template_expr = ctx
+        .generate_sfc_template(&template_block)
+        .unwrap_or_else(|| Expr::Lit(Lit::Null(Null { span: DUMMY_SP })));
+
+    let filename = FileName::Custom(options.filename.to_string());
+    let (code, source_map) = match generation_mode {
+        TemplateGenerationMode::Inline => {
+            let render_arrow = Expr::Arrow(ctx.generate_render_arrow(template_expr));
+            CodegenContext::stringify(
+                source,
+                &render_arrow,
+                filename,
+                generate_source_map,
+                false,
+                None,
+            )
+        }
+
+        TemplateGenerationMode::RenderFn => {
+            let render_fn = ctx.generate_render_fn(template_expr);
+
+            let mut module = Module {
+                span: DUMMY_SP,
+                body: Vec::with_capacity(2),
+                shebang: None,
+            };
+
+            let used_imports = ctx.generate_imports();
+            if !used_imports.is_empty() {
+                module
+                    .body
+                    .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                        span: DUMMY_SP,
+                        specifiers: used_imports,
+                        src: Box::new(Str {
+                            span: DUMMY_SP,
+                            value: fervid_atom!("vue"),
+                            raw: None,
+                        }),
+                        type_only: false,
+                        with: None,
+                        phase: Default::default(),
+                    })));
+            }
+
+            // `export function render(_ctx, _cache, $props, $setup, $data, $options) { /*...*/ }`
+            module
+                .body
+                .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    span: DUMMY_SP,
+                    decl: Decl::Fn(FnDecl {
+                        ident: Ident {
+                            span: DUMMY_SP,
+                            ctxt: Default::default(),
+                            sym: FervidAtom::from("render"),
+                            optional: false,
+                        },
+                        declare: false,
+                        function: Box::new(render_fn),
+                    }),
+                })));
+
+            CodegenContext::stringify(source, &module, filename, generate_source_map, false, None)
+        }
+    };
+
+    let imports = ctx
+        .bindings_helper
+        .vue_imports
+        .into_iter()
+        .map(|import| import.as_str()[1..].to_string())
+        .collect();
+    all_errors.extend(ctx.bindings_helper.errors.drain(..).map(From::from));
+
+    Ok(CompileTemplateResult {
+        code,
+        imports,
+        errors: all_errors,
+        source_map,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid_core::BindingTypes;
+
+    use super::*;
+
+    fn options(generation_mode: TemplateGenerationMode) -> CompileTemplateOptions<'static> {
+        CompileTemplateOptions {
+            filename: Cow::Borrowed("anonymous.vue"),
+            is_prod: None,
+            binding_metadata: vec![
+                SetupBinding(fervid_atom!("count"), BindingTypes::SetupRef),
+                SetupBinding(fervid_atom!("msg"), BindingTypes::Props),
+            ],
+            generation_mode: Some(generation_mode),
+            delimiters: None,
+            whitespace: None,
+            is_custom_element_tag: None,
+            comments: None,
+            source_map: None,
+            parse_mode: None,
+        }
+    }
+
+    #[test]
+    fn it_compiles_render_fn() {
+        let source = r#"<div @click="count++">{{ msg }} {{ count }} {{ other }}</div>"#;
+        let result = compile_template(source, options(TemplateGenerationMode::RenderFn)).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(result
+            .code
+            .contains("export function render(_ctx, _cache, $props, $setup, $data, $options)"));
+        assert!(result.code.contains("$setup.count++"));
+        assert!(result.code.contains("$props.msg"));
+        assert!(result.code.contains("_ctx.other"));
+        assert!(result.code.contains("from \"vue\""));
+        assert!(result.imports.iter().any(|i| i == "toDisplayString"));
+    }
+
+    #[test]
+    fn it_compiles_inline() {
+        let source = r#"<div @click="count++">{{ msg }} {{ count }}</div>"#;
+        let result = compile_template(source, options(TemplateGenerationMode::Inline)).unwrap();
+        assert!(result.errors.is_empty());
+        assert!(result.code.starts_with("(_ctx, _cache)=>"));
+        assert!(result.code.contains("count.value++"));
+        assert!(!result.code.contains("import"));
+        assert!(result.imports.iter().any(|i| i == "createElementBlock"));
+    }
+
+    #[test]
+    fn it_compiles_empty_template() {
+        let result = compile_template("", options(TemplateGenerationMode::RenderFn)).unwrap();
+        assert!(result.code.contains("return null"));
+        assert!(result.imports.is_empty());
+    }
+}
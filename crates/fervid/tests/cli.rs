@@ -0,0 +1,125 @@
+//! Runs the `fervid` binary (`src/main.rs`) on the files in a temporary directory
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const COMPONENT: &str = r#"<template>
+  <div class="greeting">{{ msg }}</div>
+</template>
+
+<script setup lang="ts">
+const msg: string = 'Hello'
+</script>
+
+<style scoped>
+.greeting { color: red }
+</style>
+
+<style lang="scss">
+.a { .b { color: blue } }
+</style>"#;
+
+const BROKEN: &str = r#"<template>
+  <div v-if=">">Broken</div>
+</template>"#;
+
+/// A fresh directory in the system temp dir, removed on drop
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("fervid-cli-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn write(&self, relative_path: &str, content: &str) {
+        let path = self.0.join(relative_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn fervid(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fervid"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn it_compiles_directory() {
+    let dir = TempDir::new("directory");
+    dir.write("src/components/Greeting.vue", COMPONENT);
+    dir.write("src/node_modules/Broken.vue", BROKEN);
+
+    let output = fervid(
+        &["src", "--out-dir", "dist", "--prod", "--source-map"],
+        &dir.0,
+    );
+    assert!(output.status.success(), "{:?}", output);
+
+    let out = dir.0.join("dist/components");
+    let code = fs::read_to_string(out.join("Greeting.vue.ts")).unwrap();
+    assert!(code.contains("const msg: string = 'Hello'"));
+    assert!(code.contains(r#"__name: "Greeting""#));
+    assert!(code.ends_with("//# sourceMappingURL=Greeting.vue.ts.map\n"));
+    assert!(out.join("Greeting.vue.ts.map").exists());
+
+    let css = fs::read_to_string(out.join("Greeting.vue.0.css")).unwrap();
+    assert!(css.contains(".greeting[data-v-"));
+    assert!(out.join("Greeting.vue.0.css.map").exists());
+
+    // Not compiled, written as is
+    let scss = fs::read_to_string(out.join("Greeting.vue.1.scss")).unwrap();
+    assert!(scss.contains(".a { .b { color: blue } }"));
+
+    // `node_modules` are skipped
+    assert!(!dir.0.join("dist/node_modules").exists());
+}
+
+#[test]
+fn it_reports_errors_in_check_mode() {
+    let dir = TempDir::new("check");
+    dir.write("Broken.vue", BROKEN);
+    dir.write("Greeting.vue", COMPONENT);
+
+    let output = fervid(&["--check", "Broken.vue", "Greeting.vue"], &dir.0);
+    assert_eq!(Some(1), output.status.code());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("error[X_INVALID_EXPRESSION]"));
+    assert!(stderr.contains("--> Broken.vue:2:"));
+    assert!(stderr.contains("Checked 2 file(s)"));
+
+    // Nothing is written
+    assert!(!dir.0.join("Greeting.vue.ts").exists());
+}
+
+#[test]
+fn it_rejects_invalid_arguments() {
+    let dir = TempDir::new("arguments");
+
+    let output = fervid(&["--whitespace", "collapse", "App.vue"], &dir.0);
+    assert_eq!(Some(2), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("invalid value `collapse` for `--whitespace`"));
+
+    let output = fervid(&["--gen-default-as"], &dir.0);
+    assert_eq!(Some(2), output.status.code());
+
+    let output = fervid(&["--help"], &dir.0);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Usage: fervid"));
+}
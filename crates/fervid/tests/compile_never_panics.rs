@@ -0,0 +1,139 @@
+//! Property tests which feed arbitrary input into `fervid::compile`.
+//! The compiler must report malformed input as errors instead of panicking.
+
+use std::borrow::Cow;
+
+use fervid::{compile, CompileOptions, ParseMode};
+use proptest::prelude::*;
+
+const PARSE_MODES: [ParseMode; 4] = [
+    ParseMode::HardFailOnError,
+    ParseMode::SoftReportAll,
+    ParseMode::SoftRecoverSafe,
+    ParseMode::SoftRecoverUnsafe,
+];
+
+/// Building blocks of a Vue SFC, including malformed and unusual ones
+const FRAGMENTS: &[&str] = &[
+    "<template>",
+    "</template>",
+    "<script>",
+    "<script setup>",
+    "<script setup lang=\"ts\">",
+    "</script>",
+    "<style>",
+    "<style scoped>",
+    "</style>",
+    "<div",
+    "<div>",
+    "</div>",
+    "<span/>",
+    "<!DOCTYPE html>",
+    "<!-- comment -->",
+    "<template v-slot:named>",
+    "<template #default=\"{ item }\">",
+    "<template v-for=\"i in 3\" v-slot:[i]>",
+    "<MyComponent>",
+    "</MyComponent>",
+    "<component :is=\"foo\">",
+    "<input v-model=\"foo\">",
+    "<input v-model=\"{ a, b: [c] } = d\">",
+    "<input v-model=\"foo()\">",
+    "<input v-model=\"[a, ...b]\">",
+    "<div v-if=\"ok\">",
+    "<div v-else-if=\"other\">",
+    "<div v-else>",
+    "<li v-for=\"(item, index) in items\" :key=\"item.id\">",
+    "<button @click=\"count++\">",
+    "<button @click.stop.prevent=\"\">",
+    ":[dyn",
+    "v-bind:=",
+    "{{",
+    "}}",
+    "{{ foo }}",
+    "{{ props.foo }}",
+    "{{ if ( }}",
+    "\"",
+    "'",
+    "=",
+    ">",
+    "<",
+    "/",
+    " ",
+    "\n",
+    "const { foo } = defineProps(['foo'])\n",
+    "const { foo: bar } = defineProps<{ foo: string }>()\n",
+    "const model = defineModel()\n",
+    "defineEmits(['change'])\n",
+    "import Foo from './Foo.vue'\n",
+    "export default {}\n",
+    "export default { data() { return { a: 1 } } }\n",
+    "if (",
+    ".foo { color: red }",
+    ":deep(",
+    "v-bind(color)",
+];
+
+fn options(is_prod: bool, parse_mode: ParseMode) -> CompileOptions<'static> {
+    CompileOptions {
+        filename: Cow::Borrowed("anonymous.vue"),
+        id: Cow::Borrowed("data-v-fuzz"),
+        is_prod: Some(is_prod),
+        source_map: Some(true),
+        parse_mode: Some(parse_mode),
+        ..Default::default()
+    }
+}
+
+fn vue_like_source() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(FRAGMENTS), 0..40)
+        .prop_map(|fragments| fragments.concat())
+}
+
+fn check(source: &str, is_prod: bool, parse_mode_idx: usize) {
+    let result = compile(source, options(is_prod, PARSE_MODES[parse_mode_idx]));
+
+    // Diagnostics must be renderable for any input
+    match result {
+        Ok(result) => {
+            for error in result.errors {
+                let _ = error.to_diagnostic_string(source, "anonymous.vue");
+            }
+        }
+        Err(error) => {
+            let _ = error.to_diagnostic_string(source, "anonymous.vue");
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn it_does_not_panic_on_arbitrary_strings(
+        source in any::<String>(),
+        is_prod in any::<bool>(),
+        parse_mode_idx in 0..PARSE_MODES.len(),
+    ) {
+        check(&source, is_prod, parse_mode_idx);
+    }
+
+    #[test]
+    fn it_does_not_panic_on_vue_like_input(
+        source in vue_like_source(),
+        is_prod in any::<bool>(),
+        parse_mode_idx in 0..PARSE_MODES.len(),
+    ) {
+        check(&source, is_prod, parse_mode_idx);
+    }
+
+    #[test]
+    fn it_does_not_panic_on_arbitrary_templates(
+        template in vue_like_source(),
+        is_prod in any::<bool>(),
+        parse_mode_idx in 0..PARSE_MODES.len(),
+    ) {
+        let source = format!("<template>{template}</template>\n<script setup>\nconst foo = ref(1)\n</script>");
+        check(&source, is_prod, parse_mode_idx);
+    }
+}
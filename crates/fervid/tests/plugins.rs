@@ -0,0 +1,131 @@
+//! Loads the example plugin (`examples/strip_debug_plugin.rs`) from its dynamic library.
+//! `cargo test` builds the examples before running the tests,
+//! otherwise (e.g. `cargo test --test plugins`) the example is built by the tests.
+
+use std::{
+    borrow::Cow,
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+use fervid::{compile, errors::CompileError, CompileOptions};
+use fervid_core::error::ErrorCode;
+
+const SOURCE: &str = r#"<template>
+  <button data-testid="submit" @click="submit">Submit</button>
+</template>
+
+<script setup>
+function submit() {
+  debugger
+  console.log('submitted')
+}
+</script>
+
+<docs>
+The button which submits the form.
+</docs>"#;
+
+/// The test binary is in `target/<profile>/deps`, the examples are in `target/<profile>/examples`
+fn example_plugin_path() -> PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+
+    PATH.get_or_init(|| {
+        let mut profile_dir = std::env::current_exe().unwrap();
+        profile_dir.pop();
+        if profile_dir.ends_with("deps") {
+            profile_dir.pop();
+        }
+
+        let path = profile_dir
+            .join("examples")
+            .join(format!("{}strip_debug_plugin{}", DLL_PREFIX, DLL_SUFFIX));
+        if !path.exists() {
+            build_example_plugin(&profile_dir);
+        }
+
+        assert!(
+            path.exists(),
+            "{} does not exist, build it with `cargo build --example strip_debug_plugin`",
+            path.display()
+        );
+        path
+    })
+    .to_owned()
+}
+
+/// Builds the example with the same profile and into the same target directory as the tests
+fn build_example_plugin(profile_dir: &Path) {
+    let profile = match profile_dir.file_name().and_then(|name| name.to_str()) {
+        Some("debug") | None => "dev",
+        Some(profile) => profile,
+    };
+    let target_dir = profile_dir.parent().unwrap();
+
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "build",
+            "--example",
+            "strip_debug_plugin",
+            "--profile",
+            profile,
+        ])
+        .arg("--target-dir")
+        .arg(target_dir)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("Failed to run cargo");
+    assert!(status.success(), "Failed to build the example plugin");
+}
+
+fn options(plugins: Vec<Cow<'static, str>>) -> CompileOptions<'static> {
+    CompileOptions {
+        filename: Cow::Borrowed("Button.vue"),
+        is_prod: Some(false),
+        plugins,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn it_runs_dynamic_plugin() {
+    // Without the plugin
+    let result = compile(SOURCE, options(vec![])).unwrap();
+    assert!(result.code.contains("data-testid"));
+    assert!(result.code.contains("debugger"));
+    assert_eq!(1, result.other_assets.len());
+
+    // With the plugin
+    let plugin_path = example_plugin_path().to_string_lossy().into_owned();
+    let result = compile(SOURCE, options(vec![Cow::Owned(plugin_path)])).unwrap();
+    assert!(result.errors.is_empty());
+    assert!(!result.code.contains("data-testid"));
+    assert!(!result.code.contains("debugger"));
+    assert!(result.code.contains("console.log('submitted')"));
+    assert!(result.other_assets.is_empty());
+}
+
+#[test]
+fn it_loads_dynamic_plugin_once() {
+    let plugin_path = example_plugin_path();
+    let first = fervid_plugin::load_plugin(&plugin_path).unwrap();
+    let second = fervid_plugin::load_plugin(&plugin_path).unwrap();
+
+    assert_eq!("strip-debug", first.name());
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+}
+
+#[test]
+fn it_reports_missing_plugin() {
+    let result = compile(
+        SOURCE,
+        options(vec![Cow::Borrowed("./does-not-exist/plugin.so")]),
+    );
+
+    let Err(CompileError::Plugin(error)) = result else {
+        panic!("expected a plugin error")
+    };
+    assert_eq!("PLUGIN_LOAD", error.error_code());
+}
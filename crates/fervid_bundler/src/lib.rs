@@ -0,0 +1,269 @@
+//! Virtual modules of the Vue SFC blocks shared by the bundler integrations of `fervid`.
+//!
+//! The compiled SFC imports its `<style>`s and custom blocks using the ids in the format
+//! of `@vitejs/plugin-vue`, e.g. `/src/App.vue?vue&type=style&index=0&scoped=7ba5bd90&lang.css`.
+//! When the bundler loads such an id, the block is taken from the cached [`CompileResult`].
+//!
+//! ## Example
+//! ```
+//! use fervid_bundler::{VueBlockType, VueRequest};
+//!
+//! let request = VueRequest::parse("/src/App.vue?vue&type=style&index=0&scoped=7ba5bd90&lang.css");
+//! assert_eq!("/src/App.vue", request.filename);
+//!
+//! let query = request.query.unwrap();
+//! assert_eq!(VueBlockType::Style, query.block_type);
+//! assert_eq!(Some("7ba5bd90"), query.scoped.as_deref());
+//! assert_eq!(Some("css"), query.lang.as_deref());
+//! ```
+
+mod query;
+
+use fervid::{CompileEmittedAsset, CompileEmittedStyle, CompileResult};
+
+pub use query::{VueBlockType, VueQuery, VueRequest, DEFAULT_CSS_MODULE_NAME};
+
+/// A block of the SFC which is a separate module
+pub enum VueBlock<'r> {
+    Style(&'r CompileEmittedStyle),
+    Custom(&'r CompileEmittedAsset),
+}
+
+/// Finds the block requested by `query` in the compilation result of the SFC.
+/// Returns `None` for `<script>` and `<template>`, because they are a part of [`CompileResult::code`]
+pub fn extract_block<'r>(result: &'r CompileResult, query: &VueQuery) -> Option<VueBlock<'r>> {
+    match query.block_type {
+        VueBlockType::Style => result.styles.get(query.index).map(VueBlock::Style),
+        VueBlockType::Custom(ref tag_name) => result
+            .other_assets
+            .get(query.index)
+            .filter(|block| &block.tag_name == tag_name)
+            .map(VueBlock::Custom),
+        VueBlockType::Script | VueBlockType::Template => None,
+    }
+}
+
+/// Queries of all the blocks of the SFC which are separate modules, in the order of the prelude
+pub fn block_queries(result: &CompileResult) -> Vec<VueQuery> {
+    let styles = result
+        .styles
+        .iter()
+        .enumerate()
+        .map(|(index, style)| VueQuery::style(index, style, &result.file_hash));
+
+    let custom_blocks = result
+        .other_assets
+        .iter()
+        .enumerate()
+        .map(|(index, block)| VueQuery::custom_block(index, block));
+
+    styles.chain(custom_blocks).collect()
+}
+
+/// Imports of the blocks, one per line.
+/// CSS modules are imported as `style{index}`, see [`generate_css_modules_code`] for injecting them.
+/// Custom blocks are imported as `_sfc_block{index}`, see [`generate_custom_blocks_code`] for applying them.
+///
+/// ES imports are hoisted, so the prelude may be appended to the code instead
+/// when the source map of [`CompileResult::code`] must stay intact.
+pub fn generate_prelude(filename: &str, result: &CompileResult) -> String {
+    let mut prelude = String::new();
+
+    for query in block_queries(result) {
+        let id = js_string(&format!("{}{}", filename, query));
+        match query.block_type {
+            VueBlockType::Custom(_) => {
+                prelude.push_str(&format!("import _sfc_block{} from {}\n", query.index, id))
+            }
+            VueBlockType::Style if query.module.is_some() => {
+                prelude.push_str(&format!("import style{} from {}\n", query.index, id))
+            }
+            _ => prelude.push_str(&format!("import {}\n", id)),
+        }
+    }
+
+    prelude
+}
+
+/// Injection of the CSS modules imported in the prelude into the component, same as in `@vitejs/plugin-vue`.
+/// They are available by their names, e.g. `$style` in the template or `useCssModule('classes')`.
+/// `component_name` is the name of the component in the code, see `CompileOptions::gen_default_as`
+pub fn generate_css_modules_code(component_name: &str, result: &CompileResult) -> String {
+    let css_modules: Vec<String> = block_queries(result)
+        .into_iter()
+        .filter_map(|query| {
+            let module = query.module?;
+            Some(format!("{}: style{}", js_string(&module), query.index))
+        })
+        .collect();
+
+    if css_modules.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "{component_name}.__cssModules = {{ {} }}\n",
+        css_modules.join(", ")
+    )
+}
+
+/// Calls of the custom blocks imported in the prelude with the component, same as in `@vitejs/plugin-vue`.
+/// `component_name` is the name of the component in the code, see `CompileOptions::gen_default_as`
+pub fn generate_custom_blocks_code(component_name: &str, result: &CompileResult) -> String {
+    let mut code = String::new();
+
+    for index in 0..result.other_assets.len() {
+        code.push_str(&format!(
+            "if (typeof _sfc_block{index} === \"function\") _sfc_block{index}({component_name})\n"
+        ));
+    }
+
+    code
+}
+
+/// JS string literal, JSON strings are a subset of them
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use fervid::CompileOptions;
+
+    use super::*;
+
+    const SFC: &str = r#"<template><div class="a">{{ $t('hello') }}</div></template>
+<script setup>
+const a = 1
+</script>
+<style scoped>
+.a { color: red }
+</style>
+<style lang="scss" module>
+.b { color: blue }
+</style>
+<i18n lang="json" locale="en">
+{ "hello": "Hello" }
+</i18n>
+<docs>
+Hello "docs"
+</docs>
+"#;
+
+    fn compile(source: &str) -> CompileResult {
+        fervid::compile(
+            source,
+            CompileOptions {
+                filename: "/src/App.vue".into(),
+                id: "app".into(),
+                is_prod: Some(false),
+                gen_default_as: Some("_sfc_main".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_generates_prelude() {
+        let result = compile(SFC);
+        let hash = &result.file_hash;
+
+        assert_eq!(
+            format!(
+                r#"import "/src/App.vue?vue&type=style&index=0&scoped={hash}&lang.css"
+import style1 from "/src/App.vue?vue&type=style&index=1&module&lang.module.scss"
+import _sfc_block0 from "/src/App.vue?vue&type=i18n&index=0&locale=en&lang.json"
+import _sfc_block1 from "/src/App.vue?vue&type=docs&index=1"
+"#
+            ),
+            generate_prelude("/src/App.vue", &result)
+        );
+
+        assert_eq!(
+            "_sfc_main.__cssModules = { \"$style\": style1 }\n",
+            generate_css_modules_code("_sfc_main", &result)
+        );
+
+        assert_eq!(
+            "if (typeof _sfc_block0 === \"function\") _sfc_block0(_sfc_main)\nif (typeof _sfc_block1 === \"function\") _sfc_block1(_sfc_main)\n",
+            generate_custom_blocks_code("_sfc_main", &result)
+        );
+    }
+
+    #[test]
+    fn it_injects_named_css_modules() {
+        let result = compile(
+            "<style module>.a { color: red }</style>\n<style>.b { color: blue }</style>\n<style module=\"classes\">.c { color: green }</style>",
+        );
+
+        assert_eq!(
+            r#"import style0 from "/src/App.vue?vue&type=style&index=0&module&lang.module.css"
+import "/src/App.vue?vue&type=style&index=1&lang.css"
+import style2 from "/src/App.vue?vue&type=style&index=2&module=classes&lang.module.css"
+"#,
+            generate_prelude("/src/App.vue", &result)
+        );
+        assert_eq!(
+            "_sfc_main.__cssModules = { \"$style\": style0, \"classes\": style2 }\n",
+            generate_css_modules_code("_sfc_main", &result)
+        );
+        assert_eq!(
+            "",
+            generate_css_modules_code("_sfc_main", &compile("<style>.a { color: red }</style>"))
+        );
+    }
+
+    #[test]
+    fn it_quotes_filenames() {
+        let result = compile("<style>.a { color: red }</style>");
+        assert_eq!(
+            "import \"C:\\\\src\\\\\\\"App\\\".vue?vue&type=style&index=0&lang.css\"\n",
+            generate_prelude("C:\\src\\\"App\".vue", &result)
+        );
+    }
+
+    #[test]
+    fn it_extracts_blocks() {
+        let result = compile(SFC);
+
+        for query in block_queries(&result) {
+            let id = format!("/src/App.vue{}", query);
+            let request = VueRequest::parse(&id);
+            let block = extract_block(&result, request.query.as_ref().unwrap());
+
+            match (query.index, block) {
+                (0, Some(VueBlock::Style(style))) if query.block_type == VueBlockType::Style => {
+                    assert!(style.is_scoped);
+                    assert!(style.code.contains(".a[data-v-"));
+                }
+                (1, Some(VueBlock::Style(style))) => {
+                    assert_eq!("scss", style.lang);
+                    assert!(!style.is_compiled);
+                }
+                (0, Some(VueBlock::Custom(block))) => {
+                    assert_eq!("i18n", block.tag_name);
+                    assert!(block.content.contains("\"hello\""));
+                }
+                (1, Some(VueBlock::Custom(block))) => assert_eq!("docs", block.tag_name),
+                _ => panic!("Unexpected block for {}", id),
+            }
+        }
+    }
+
+    #[test]
+    fn it_ignores_other_blocks() {
+        let result = compile(SFC);
+
+        for id in [
+            "/src/App.vue?vue&type=script&index=0&lang.js",
+            "/src/App.vue?vue&type=template&index=0",
+            "/src/App.vue?vue&type=style&index=2&lang.css",
+            // Index of a block with another tag name
+            "/src/App.vue?vue&type=i18n&index=1",
+        ] {
+            let request = VueRequest::parse(id);
+            assert!(extract_block(&result, request.query.as_ref().unwrap()).is_none());
+        }
+    }
+}
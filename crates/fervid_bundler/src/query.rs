@@ -0,0 +1,396 @@
+//! Parsing and formatting of the `?vue&type=...` queries, same as in `@vitejs/plugin-vue`.
+
+use std::fmt::{self, Write};
+
+use fervid::{CompileEmittedAsset, CompileEmittedStyle};
+
+/// Name of the CSS module of `<style module>` without a name
+pub const DEFAULT_CSS_MODULE_NAME: &str = "$style";
+
+/// Attributes which are a part of the query already, or must not end up in it
+const IGNORED_ATTRS: [&str; 8] = [
+    "id", "index", "src", "type", "lang", "module", "scoped", "generic",
+];
+
+/// Module id of a Vue SFC, e.g. `/src/App.vue?vue&type=style&index=0&lang.css`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VueRequest<'i> {
+    /// Path without the query
+    pub filename: &'i str,
+    /// `None` for the SFC itself
+    pub query: Option<VueQuery>,
+}
+
+/// Query of a single block of an SFC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VueQuery {
+    pub block_type: VueBlockType,
+    /// Index among the `<style>`s for a style, e.g. the second `<style>` has the index 1.
+    /// Custom blocks are indexed among all of them, whatever their tag names are
+    pub index: usize,
+    pub lang: Option<String>,
+    /// Scope ID of a `<style scoped>`, i.e. [`fervid::CompileResult::file_hash`]
+    pub scoped: Option<String>,
+    /// Name of the CSS module of a `<style module>`, [`DEFAULT_CSS_MODULE_NAME`] when not named
+    pub module: Option<String>,
+    /// Other attributes of a custom block, e.g. `locale` of `<i18n locale="en">`
+    pub attrs: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VueBlockType {
+    Script,
+    Template,
+    Style,
+    /// Custom block with its tag name, e.g. `i18n`
+    Custom(String),
+}
+
+impl<'i> VueRequest<'i> {
+    /// Parses a module id. Ids without the `vue` query parameter are the SFCs themselves
+    pub fn parse(id: &'i str) -> VueRequest<'i> {
+        let (filename, query) = match id.split_once('?') {
+            Some((filename, query)) => (filename, Some(query)),
+            None => (id, None),
+        };
+
+        VueRequest {
+            filename,
+            query: query.and_then(VueQuery::parse),
+        }
+    }
+}
+
+impl fmt::Display for VueRequest<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.filename)?;
+        match self.query {
+            Some(ref query) => query.fmt(f),
+            None => Ok(()),
+        }
+    }
+}
+
+impl VueQuery {
+    /// Parses a query string without the leading `?`.
+    /// Returns `None` when it is not a query of a Vue block
+    pub fn parse(query: &str) -> Option<VueQuery> {
+        let mut is_vue = false;
+        let mut block_type = None;
+        let mut index = 0;
+        let mut lang = None;
+        let mut scoped = None;
+        let mut module = None;
+        let mut attrs = Vec::new();
+
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = match param.split_once('=') {
+                Some((key, value)) => (decode(key), decode(value)),
+                None => (decode(param), String::new()),
+            };
+
+            match key.as_str() {
+                "vue" => is_vue = true,
+                "type" => {
+                    block_type = Some(match value.as_str() {
+                        "script" => VueBlockType::Script,
+                        "template" => VueBlockType::Template,
+                        "style" => VueBlockType::Style,
+                        _ => VueBlockType::Custom(value),
+                    })
+                }
+                "index" => index = value.parse().ok()?,
+                "lang" => lang = Some(value),
+                "scoped" => scoped = Some(value),
+                "module" if value.is_empty() => module = Some(DEFAULT_CSS_MODULE_NAME.to_owned()),
+                "module" => module = Some(value),
+                // `lang.css`, so that the bundlers can match the id by the extension.
+                // CSS modules are `lang.module.css`, see the `Display` implementation
+                _ if key.starts_with("lang.") => {
+                    let extension = &key["lang.".len()..];
+                    let extension = match module {
+                        Some(_) => extension.strip_prefix("module.").unwrap_or(extension),
+                        None => extension,
+                    };
+                    lang = Some(extension.to_owned())
+                }
+                _ => attrs.push((key, value)),
+            }
+        }
+
+        if !is_vue {
+            return None;
+        }
+
+        Some(VueQuery {
+            block_type: block_type?,
+            index,
+            lang,
+            scoped,
+            module,
+            attrs,
+        })
+    }
+
+    /// Query of the `index`-th style of an SFC with the scope ID `scope_id`
+    pub fn style(index: usize, style: &CompileEmittedStyle, scope_id: &str) -> VueQuery {
+        let module = style
+            .attrs
+            .iter()
+            .find(|(name, _)| name == "module")
+            .map(|(_, value)| match value.as_str() {
+                "" => DEFAULT_CSS_MODULE_NAME.to_owned(),
+                name => name.to_owned(),
+            });
+
+        VueQuery {
+            block_type: VueBlockType::Style,
+            index,
+            lang: Some(style.lang.to_owned()),
+            scoped: style.is_scoped.then(|| scope_id.to_owned()),
+            module,
+            attrs: vec![],
+        }
+    }
+
+    /// Query of the `index`-th custom block of an SFC
+    pub fn custom_block(index: usize, block: &CompileEmittedAsset) -> VueQuery {
+        let lang = block
+            .attrs
+            .iter()
+            .find(|(name, value)| name == "lang" && !value.is_empty())
+            .map(|(_, value)| value.to_owned());
+
+        let attrs = block
+            .attrs
+            .iter()
+            .filter(|(name, _)| !IGNORED_ATTRS.contains(&name.as_str()))
+            .cloned()
+            .collect();
+
+        VueQuery {
+            block_type: VueBlockType::Custom(block.tag_name.to_owned()),
+            index,
+            lang,
+            scoped: None,
+            module: None,
+            attrs,
+        }
+    }
+}
+
+/// Formats the query with the leading `?`
+impl fmt::Display for VueQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("?vue&type=")?;
+        match self.block_type {
+            VueBlockType::Script => f.write_str("script")?,
+            VueBlockType::Template => f.write_str("template")?,
+            VueBlockType::Style => f.write_str("style")?,
+            VueBlockType::Custom(ref tag_name) => encode(tag_name, f)?,
+        }
+        write!(f, "&index={}", self.index)?;
+
+        if let Some(ref scoped) = self.scoped {
+            f.write_str("&scoped=")?;
+            encode(scoped, f)?;
+        }
+
+        match self.module.as_deref() {
+            Some(DEFAULT_CSS_MODULE_NAME) => f.write_str("&module")?,
+            Some(module) => {
+                f.write_str("&module=")?;
+                encode(module, f)?;
+            }
+            None => {}
+        }
+
+        for (name, value) in self.attrs.iter() {
+            f.write_char('&')?;
+            encode(name, f)?;
+            if !value.is_empty() {
+                f.write_char('=')?;
+                encode(value, f)?;
+            }
+        }
+
+        // Always last, the extension of the id.
+        // `.module` is put before it, so that the bundlers handle the style as a CSS module
+        if let Some(ref lang) = self.lang {
+            f.write_str("&lang.")?;
+            if self.module.is_some() {
+                f.write_str("module.")?;
+            }
+            encode(lang, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Same as `encodeURIComponent`
+fn encode(value: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => f.write_char(byte as char)?,
+            _ => write!(f, "%{:02X}", byte)?,
+        }
+    }
+    Ok(())
+}
+
+/// Same as `decodeURIComponent`, but keeps the invalid escapes as is
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = (bytes[idx] == b'%')
+            .then(|| value.get(idx + 1..idx + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            None => {
+                decoded.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(lang: &str, is_scoped: bool, attrs: &[(&str, &str)]) -> CompileEmittedStyle {
+        CompileEmittedStyle {
+            code: String::new(),
+            is_compiled: lang == "css",
+            lang: lang.to_owned(),
+            is_scoped,
+            source_map: None,
+            attrs: to_attrs(attrs),
+        }
+    }
+
+    fn to_attrs(attrs: &[(&str, &str)]) -> Vec<(String, String)> {
+        attrs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn it_formats_style_queries() {
+        let query = VueQuery::style(0, &style("css", true, &[("scoped", "")]), "7ba5bd90");
+        assert_eq!(
+            "?vue&type=style&index=0&scoped=7ba5bd90&lang.css",
+            query.to_string()
+        );
+
+        let query = VueQuery::style(1, &style("scss", false, &[("module", "")]), "7ba5bd90");
+        assert_eq!(
+            "?vue&type=style&index=1&module&lang.module.scss",
+            query.to_string()
+        );
+
+        let query = VueQuery::style(2, &style("css", false, &[("module", "classes")]), "");
+        assert_eq!(
+            "?vue&type=style&index=2&module=classes&lang.module.css",
+            query.to_string()
+        );
+    }
+
+    #[test]
+    fn it_formats_custom_block_queries() {
+        let block = CompileEmittedAsset {
+            lo: 0,
+            hi: 0,
+            tag_name: "i18n".to_owned(),
+            content: String::new(),
+            attrs: to_attrs(&[("lang", "json"), ("locale", "en US"), ("global", "")]),
+        };
+        assert_eq!(
+            "?vue&type=i18n&index=0&locale=en%20US&global&lang.json",
+            VueQuery::custom_block(0, &block).to_string()
+        );
+    }
+
+    #[test]
+    fn it_parses_requests() {
+        let request = VueRequest::parse("/src/App.vue");
+        assert_eq!("/src/App.vue", request.filename);
+        assert_eq!(None, request.query);
+
+        // Not a block
+        assert_eq!(None, VueRequest::parse("/src/App.vue?raw").query);
+        assert_eq!(None, VueRequest::parse("/src/App.vue?vue").query);
+
+        let request = VueRequest::parse(
+            "/src/App.vue?vue&type=style&index=1&scoped=abc&module&lang.module.css",
+        );
+        assert_eq!("/src/App.vue", request.filename);
+        assert_eq!(
+            Some(VueQuery {
+                block_type: VueBlockType::Style,
+                index: 1,
+                lang: Some("css".to_owned()),
+                scoped: Some("abc".to_owned()),
+                module: Some("$style".to_owned()),
+                attrs: vec![],
+            }),
+            request.query
+        );
+
+        let request =
+            VueRequest::parse("/src/App.vue?vue&type=i18n&index=0&locale=en%20US&lang.json");
+        assert_eq!(
+            Some(VueQuery {
+                block_type: VueBlockType::Custom("i18n".to_owned()),
+                index: 0,
+                lang: Some("json".to_owned()),
+                scoped: None,
+                module: None,
+                attrs: to_attrs(&[("locale", "en US")]),
+            }),
+            request.query
+        );
+    }
+
+    #[test]
+    fn it_roundtrips() {
+        for id in [
+            "/src/App.vue?vue&type=style&index=0&scoped=7ba5bd90&lang.css",
+            "/src/App.vue?vue&type=style&index=1&module=classes&lang.module.less",
+            "/src/App.vue?vue&type=i18n&index=2&locale=%F0%9F%8C%8D&global&lang.yaml",
+            "C:/src/App.vue?vue&type=docs&index=0",
+        ] {
+            assert_eq!(id, VueRequest::parse(id).to_string());
+        }
+    }
+
+    #[test]
+    fn it_decodes_invalid_escapes() {
+        assert_eq!("100%", decode("100%"));
+        assert_eq!("%zz a", decode("%zz%20a"));
+    }
+}
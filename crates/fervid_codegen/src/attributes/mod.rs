@@ -119,7 +119,7 @@ impl CodegenContext {
                 }
 
                 // `v-bind` directive without argument needs its own processing
-                AttributeOrBinding::VBind(v_bind) if v_bind.argument.is_none() => {
+                AttributeOrBinding::VBind(v_bind @ VBindDirective { argument: None, .. }) => {
                     // IN:
                     // v-on="ons" v-bind="bounds" @click=""
                     //
@@ -131,7 +131,7 @@ impl CodegenContext {
                 }
 
                 // `v-on` directive without event name also needs its own processing
-                AttributeOrBinding::VOn(v_on) if v_on.event.is_none() => {
+                AttributeOrBinding::VOn(v_on @ VOnDirective { event: None, .. }) => {
                     result_hints.v_on_no_event = Some(v_on);
                 }
 
@@ -288,8 +288,6 @@ impl CodegenContext {
                         }
                     }
                 }
-
-                _ => unreachable!(),
             }
         }
 
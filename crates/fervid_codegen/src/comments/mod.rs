@@ -33,6 +33,7 @@ impl CodegenContext {
 
 #[cfg(test)]
 mod tests {
+    use fervid_core::Node;
     use swc_core::common::DUMMY_SP;
 
     use super::*;
@@ -53,6 +54,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_respects_comments_option() {
+        let nodes = [Node::Comment("some comment".into(), DUMMY_SP)];
+        let mut ctx = CodegenContext::default();
+
+        // Removed in PROD by default
+        ctx.bindings_helper.is_prod = true;
+        let mut out = Vec::new();
+        ctx.generate_node_sequence(&mut nodes.iter(), &mut out, nodes.len(), false);
+        assert!(out.is_empty());
+
+        // Kept when explicitly asked
+        ctx.bindings_helper.comments = Some(true);
+        ctx.generate_node_sequence(&mut nodes.iter(), &mut out, nodes.len(), false);
+        assert_eq!(out.len(), 1);
+        assert_eq!(
+            crate::test_utils::to_str(out.pop().unwrap()),
+            r#"_createCommentVNode("some comment")"#
+        );
+    }
+
     fn test_out(input: &str, expected: &str) {
         let mut ctx = CodegenContext::default();
         let out = ctx.generate_comment_vnode(&input, DUMMY_SP);
@@ -11,6 +11,8 @@ use swc_core::{
     },
 };
 
+use fervid_transform::error::{TemplateError, TemplateErrorKind, TransformError};
+
 use crate::{context::CodegenContext, control_flow::SlottedIterator};
 
 impl CodegenContext {
@@ -365,6 +367,7 @@ impl CodegenContext {
                             ..
                         },
                     children,
+                    span,
                     ..
                 }) = node
                 else {
@@ -386,6 +389,7 @@ impl CodegenContext {
                     v_slot_directive,
                     &children,
                     &directives,
+                    *span,
                     &mut result_static_slots,
                 );
 
@@ -416,6 +420,7 @@ impl CodegenContext {
                     v_slot_directive,
                     &slotted_node.children,
                     &directives,
+                    slotted_node.span,
                     &mut result_static_slots,
                 );
             }
@@ -423,13 +428,9 @@ impl CodegenContext {
             slotted_iterator.toggle_mode();
         }
 
-        // Add default slot children when needed
-        // TODO Error on cases when both `<template v-slot:default>`
-        // and non-slotted children are present (analyzer), e.g.:
-        // <some-component>
-        //   <template v-slot:default>hi</template>
-        //   not hi
-        // </some-component>
+        // Add default slot children when needed.
+        // When both `<template v-slot:default>` and non-slotted children are present,
+        // the error is reported by the template validation in `fervid_transform`.
         if default_slot_children.len() != 0 {
             // withCtx(() => [child1, child2, child3])
             result_static_slots.push(self.generate_slot_shell(
@@ -454,6 +455,7 @@ impl CodegenContext {
         v_slot: &VSlotDirective,
         slot_children: &[Node],
         directives: &VueDirectives,
+        span: Span,
         out_static_slots: &mut Vec<PropOrSpread>,
     ) {
         // Extra logic is needed if this is more than just `<template v-slot>`
@@ -462,8 +464,15 @@ impl CodegenContext {
             || directives.v_else.is_some()
             || directives.v_for.is_some();
 
+        // TODO Generate `createSlots` for the complex slots.
+        // Until then, such slots are reported and generated as if they were static.
         if is_complex {
-            todo!("createSlots is not supported yet");
+            self.bindings_helper
+                .errors
+                .push(TransformError::TemplateError(TemplateError {
+                    span,
+                    kind: TemplateErrorKind::DynamicSlotsUnsupported,
+                }));
             // https://play.vuejs.org/#eNqVVNtuozAQ/ZUpWolGKo2y+xaRqFVfdr9gH0qlOngIVo2NbEMbRfz7js0l0G33okTgmfGcMzfmHN3X9W3bYLSNUpsbUTuw6JoaJFPHXRY5m0X7TImq1sbBGQwW0EFhdAUxucWZylSulXVQ2SPsvP06/o5SavipjeRX8SpT6bqHJiASBpolQY/xWjKHLRoCiktB4PgWeDkWrJHEnykAzhy7XvVnIELXGDVK4MOwW+hDIACv6vyLHvRfRuKwqiUxkgSQctFCm1RYoQ0KyFxabvbnc8CErgM6ThF2Xboma8+QClU3zntrjpKyIo+QVjDypqpOSU5mrchGmWYRCQehOIkH3SjuNXe5FPkLab4QgXKw20PJFJf44PXXvXYVYDPnf+lVksC9lOBKBE0PA1wYzJ1o0QIzCOKotEEOooDnNhHFMwgLhSe8hR/8xeuFiy3k2ng/SJIhZop6LA5FaqV226EH0wWAUkyX14tSfuj/yE+KVSJ/miH4aoLSr3/A+RSPGmXpLAo/RKZBKsy/hBH6SwJKi72zAKFgM7XL5/UbULoemjj1NAzLjLwfHU3y49MoeVKt8v7lp8JzB6PDN0cKqmAE6zCMAbXcUHc8xoEZslxqNt69m2vm8tdBMTCNY7qEr2HLhWUHiX7wiGmWdGprpvalODH6RvzxUg0/XwZHFOhHpiLFskM+5qFY9eA98bZJoc1Y628hznnjLlxLBCq7aOk060d0Ez3o6v3OWi4TCnHcVzS1QqH30Mp/VO9310cLhgqKtELKsEJu+rz/unTI4f/WzbRa5qvk00Uyr0D3Cy9D1W8=
             // idk if that should be wrapped in block or not
             // _createSlots({
@@ -482,32 +491,31 @@ impl CodegenContext {
             //     })
             //   ]), 1040 /* FULL_PROPS, DYNAMIC_SLOTS */)
             // let generated = self.generate_node(slotted_node, false);
-        } else {
-            // Generate the children of the `<template v-slot>`
-            let total_children = slot_children.len();
-            let mut slotted_children_results = Vec::with_capacity(total_children);
-            let mut slotted_children_iter = slot_children.iter();
-
-            self.generate_node_sequence(
-                &mut slotted_children_iter,
-                &mut slotted_children_results,
-                total_children,
-                false,
-            );
-
-            let slot_name = v_slot
-                .slot_name
-                .to_owned()
-                .unwrap_or_else(|| StrOrExpr::Str(fervid_atom!("default")));
-            let span = DUMMY_SP; // todo?
-
-            out_static_slots.push(self.generate_slot_shell(
-                slot_name,
-                slotted_children_results,
-                v_slot.value.as_deref(),
-                span,
-            ));
         }
+
+        // Generate the children of the `<template v-slot>`
+        let total_children = slot_children.len();
+        let mut slotted_children_results = Vec::with_capacity(total_children);
+        let mut slotted_children_iter = slot_children.iter();
+
+        self.generate_node_sequence(
+            &mut slotted_children_iter,
+            &mut slotted_children_results,
+            total_children,
+            false,
+        );
+
+        let slot_name = v_slot
+            .slot_name
+            .to_owned()
+            .unwrap_or_else(|| StrOrExpr::Str(fervid_atom!("default")));
+
+        out_static_slots.push(self.generate_slot_shell(
+            slot_name,
+            slotted_children_results,
+            v_slot.value.as_deref(),
+            span,
+        ));
     }
 
     /// Creates the SWC identifier from a tag name. Will fetch from cache if present
@@ -135,6 +135,11 @@ impl CodegenContext {
         }
 
         while let Some(node) = iter.next() {
+            // Comments may be left in the tree when it was not produced by `fervid_transform`
+            if matches!(node, Node::Comment(_, _)) && !self.bindings_helper.keeps_comments() {
+                continue;
+            }
+
             let generated = self.generate_node(node, false);
             let is_text_node = matches!(node, Node::Text(_, _) | Node::Interpolation { .. });
 
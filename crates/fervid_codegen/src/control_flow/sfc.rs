@@ -212,6 +212,13 @@ impl CodegenContext {
             }))
         };
 
+        // Append the imports from the template plugins
+        script.body.extend(
+            self.generate_template_imports()
+                .into_iter()
+                .map(|import_decl| ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))),
+        );
+
         // Append the Vue imports
         // TODO Smart merging with user imports?
         let used_imports = self.generate_imports();
@@ -522,12 +529,18 @@ impl CodegenContext {
         }
     }
 
+    /// Converts the module AST to a string, optionally producing a source map.
+    ///
+    /// When `input_source_map` is provided, the generated source map is composed with it,
+    /// so that the mappings point to the original file instead of the `source`
+    /// (e.g. when `source` was produced by a preprocessor).
     pub fn stringify<T>(
         source: &str,
         module: &T,
         filename: FileName,
         generate_source_map: bool,
         minify: bool,
+        input_source_map: Option<&sourcemap::SourceMap>,
     ) -> (String, Option<String>)
     where
         T: Node + VisitWith<IdentCollector>,
@@ -575,7 +588,7 @@ impl CodegenContext {
 
             let map = cm.build_source_map_with_config(
                 &source_map_buf,
-                None,
+                input_source_map,
                 SourceMapConfig {
                     source_file_name: Some(filename.to_string().as_str()),
                     names: &source_map_names,
@@ -1,7 +1,10 @@
 use fervid_core::{FervidAtom, IntoIdent, VueImports};
 use swc_core::{
     common::DUMMY_SP,
-    ecma::ast::{ImportNamedSpecifier, ImportSpecifier, ModuleExportName},
+    ecma::ast::{
+        ImportDecl, ImportDefaultSpecifier, ImportNamedSpecifier, ImportSpecifier,
+        ModuleExportName, Str,
+    },
 };
 
 use super::context::CodegenContext;
@@ -44,6 +47,43 @@ impl CodegenContext {
 
         result
     }
+
+    /// Generates the imports added when transforming the template, e.g. by a template plugin.
+    /// Each of them is a separate declaration, e.g. `import foo from 'bar'`.
+    pub fn generate_template_imports(&self) -> Vec<ImportDecl> {
+        self.bindings_helper
+            .template_imports
+            .iter()
+            .map(|template_import| {
+                let local = template_import.local.to_owned().into_ident();
+                let specifier = match template_import.imported {
+                    Some(ref imported) => ImportSpecifier::Named(ImportNamedSpecifier {
+                        span: DUMMY_SP,
+                        local,
+                        imported: Some(ModuleExportName::Ident(imported.to_owned().into_ident())),
+                        is_type_only: false,
+                    }),
+                    None => ImportSpecifier::Default(ImportDefaultSpecifier {
+                        span: DUMMY_SP,
+                        local,
+                    }),
+                };
+
+                ImportDecl {
+                    span: DUMMY_SP,
+                    specifiers: vec![specifier],
+                    src: Box::new(Str {
+                        span: DUMMY_SP,
+                        value: template_import.source.to_owned(),
+                        raw: None,
+                    }),
+                    type_only: false,
+                    with: None,
+                    phase: Default::default(),
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
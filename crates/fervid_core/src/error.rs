@@ -1,3 +1,7 @@
+use std::cmp::Ordering;
+
+use swc_core::common::Span;
+
 pub trait Severity {
     fn get_severity(&self) -> SeverityLevel;
 
@@ -26,10 +30,227 @@ pub trait Severity {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(unused)]
 pub enum SeverityLevel {
     UnrecoverableError,
     RecoverableError,
     Warning,
 }
+
+impl SeverityLevel {
+    /// Lowercase name of the level, as shown in diagnostics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeverityLevel::UnrecoverableError | SeverityLevel::RecoverableError => "error",
+            SeverityLevel::Warning => "warning",
+        }
+    }
+}
+
+impl std::fmt::Display for SeverityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Stable machine-readable identifier of an error, e.g. `X_INVALID_END_TAG`.
+///
+/// Where an equivalent exists, the code is named after Vue's `ErrorCodes`,
+/// so that tooling can treat errors from both compilers the same way.
+pub trait ErrorCode {
+    fn error_code(&self) -> &'static str;
+}
+
+/// Amount of lines shown before and after the highlighted ones in a code frame
+const CODE_FRAME_RANGE: usize = 2;
+
+/// Renders a code frame highlighting the `start..end` byte range of `source`.
+///
+/// The format is the one of `generateCodeFrame` from `@vue/shared`:
+/// ```text
+/// 1  |  <template>
+/// 2  |    <div v-if=">
+///    |             ^^
+/// 3  |  </template>
+/// ```
+pub fn generate_code_frame(source: &str, start: usize, end: usize) -> String {
+    let start = start.min(source.len());
+    let end = end.clamp(start, source.len());
+
+    // Lines together with the length of their line terminators
+    let lines: Vec<(&str, usize)> = source
+        .split_inclusive('\n')
+        .map(|line| {
+            let content = line.trim_end_matches(['\n', '\r']);
+            (content, line.len() - content.len())
+        })
+        .collect();
+
+    let mut result: Vec<String> = Vec::new();
+    let mut count = 0;
+
+    for (i, &(line, newline_len)) in lines.iter().enumerate() {
+        let line_start = count;
+        count += line.len() + newline_len;
+        if count <= start && i + 1 != lines.len() {
+            continue;
+        }
+
+        let first = i.saturating_sub(CODE_FRAME_RANGE);
+        let mut j = first;
+        while j < lines.len() && (j <= i + CODE_FRAME_RANGE || end > count) {
+            let (line_j, newline_len_j) = lines[j];
+            let line_number = (j + 1).to_string();
+            result.push(format!(
+                "{}{}|  {}",
+                line_number,
+                " ".repeat(3usize.saturating_sub(line_number.len())),
+                line_j
+            ));
+
+            match j.cmp(&i) {
+                Ordering::Equal => {
+                    // Underline from `start` till `end` or the end of the line
+                    let pad = start - line_start;
+                    let underline_end = if end > count {
+                        line_j.len()
+                    } else {
+                        end - line_start
+                    };
+                    let pad_width = char_width(line_j, 0, pad);
+                    let length = char_width(line_j, pad, underline_end).max(1);
+                    result.push(format!(
+                        "   |  {}{}",
+                        " ".repeat(pad_width),
+                        "^".repeat(length)
+                    ));
+                }
+                Ordering::Greater => {
+                    if end > count {
+                        let length = char_width(line_j, 0, end - count).max(1);
+                        result.push(format!("   |  {}", "^".repeat(length)));
+                    }
+                    count += line_j.len() + newline_len_j;
+                }
+                Ordering::Less => {}
+            }
+
+            j += 1;
+        }
+        break;
+    }
+
+    result.join("\n")
+}
+
+/// Computes a 1-based line and a 1-based column (in characters) of a byte offset in `source`
+pub fn get_line_column(source: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let column = before[line_start..].chars().count() + 1;
+
+    (line, column)
+}
+
+/// Renders an error for humans:
+/// ```text
+/// error[X_INVALID_END_TAG]: Invalid end tag.
+///  --> Component.vue:3:5
+/// 1  |  <template>
+/// ...
+/// ```
+/// `span` is expected to be relative to `source` with the first byte at position 1,
+/// which is the convention used by the `fervid` parser. Dummy spans omit the location.
+pub fn render_diagnostic<E>(error: &E, span: Span, source: &str, filename: &str) -> String
+where
+    E: Severity + ErrorCode + std::fmt::Display,
+{
+    let mut out = format!(
+        "{}[{}]: {}",
+        error.get_severity(),
+        error.error_code(),
+        error
+    );
+
+    if span.is_dummy() {
+        return out;
+    }
+
+    let start = (span.lo.0 as usize).saturating_sub(1);
+    let end = (span.hi.0 as usize).saturating_sub(1);
+    let (line, column) = get_line_column(source, start);
+    out.push_str(&format!("\n --> {}:{}:{}\n", filename, line, column));
+    out.push_str(&generate_code_frame(source, start, end));
+
+    out
+}
+
+/// Counts characters of `line[from..to]`, clamping the byte range to the line
+fn char_width(line: &str, from: usize, to: usize) -> usize {
+    let to = to.min(line.len());
+    let from = from.min(to);
+    line.as_bytes()[from..to]
+        .iter()
+        .filter(|&&b| (b as i8) >= -0x40)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_generates_code_frame() {
+        let source = "<template>\n  <div v-if=\">\n  </div>\n</template>\n";
+        let start = source.find("v-if").unwrap();
+        let frame = generate_code_frame(source, start, start + 4);
+
+        assert_eq!(
+            frame,
+            [
+                "1  |  <template>",
+                "2  |    <div v-if=\">",
+                "   |         ^^^^",
+                "3  |    </div>",
+                "4  |  </template>",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn it_generates_multiline_code_frame() {
+        let source = "a\nbcd\nef\ng";
+        let start = source.find("cd").unwrap();
+        let end = source.find('f').unwrap();
+        let frame = generate_code_frame(source, start, end);
+
+        assert_eq!(
+            frame,
+            [
+                "1  |  a",
+                "2  |  bcd",
+                "   |   ^^",
+                "3  |  ef",
+                "   |  ^",
+                "4  |  g",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn it_computes_line_column() {
+        let source = "ab\nцde\n";
+        assert_eq!(get_line_column(source, 0), (1, 1));
+        assert_eq!(get_line_column(source, 3), (2, 1));
+        assert_eq!(get_line_column(source, source.find('d').unwrap()), (2, 2));
+    }
+}
@@ -4,6 +4,7 @@ pub mod error;
 mod sfc;
 mod structs;
 mod template;
+pub mod visit;
 mod vue_builtins;
 mod vue_imports;
 mod utils;
@@ -16,3 +17,6 @@ pub use template::is_from_default_slot;
 pub use vue_builtins::VUE_BUILTINS;
 pub use vue_imports::{VueImports, VueImportsSet};
 pub use utils::*;
+
+/// Version of this crate, e.g. for checking the compatibility of the plugins
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -1,8 +1,9 @@
 use swc_core::{common::Span, ecma::ast::Module};
 
-use crate::{FervidAtom, Node, StartingTag};
+use crate::{AttributeOrBinding, FervidAtom, Node, StartingTag};
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SfcDescriptor {
     pub template: Option<SfcTemplateBlock>,
     pub script_legacy: Option<SfcScriptBlock>,
@@ -12,30 +13,42 @@ pub struct SfcDescriptor {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SfcTemplateBlock {
     pub lang: FervidAtom,
     pub roots: Vec<Node>,
+    /// Attributes of `<template>` as written, including `lang`
+    pub attrs: Vec<AttributeOrBinding>,
     pub span: Span,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SfcScriptBlock {
     pub content: Box<Module>,
     pub lang: SfcScriptLang,
     pub is_setup: bool,
+    /// Attributes of `<script>` as written, including `lang` and `setup`
+    pub attrs: Vec<AttributeOrBinding>,
     pub span: Span,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SfcStyleBlock {
     pub lang: FervidAtom,
     pub content: FervidAtom,
     pub is_scoped: bool,
     pub is_module: bool,
+    /// Attributes of `<style>` as written, including `lang`, `scoped` and `module`
+    pub attrs: Vec<AttributeOrBinding>,
     pub span: Span,
+    /// Source map of the transformed `content`, if it was requested
+    pub source_map: Option<String>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SfcCustomBlock {
     pub starting_tag: StartingTag,
     pub content: FervidAtom,
@@ -43,6 +56,7 @@ pub struct SfcCustomBlock {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SfcScriptLang {
     Es,
     Typescript,
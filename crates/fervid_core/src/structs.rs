@@ -42,6 +42,7 @@ impl IntoIdent for FervidAtom {
 
 /// A Node represents a part of the Abstract Syntax Tree (AST).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Node {
     /// `Element` means that the node is a basic HTML tag node.
     ///
@@ -79,6 +80,7 @@ pub enum Node {
 /// 3. It has a `template_scope` assigned, which is responsible
 ///    for the correct compilation of dynamic bindings and expressions.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ElementNode {
     /// Marks the node as either an Element (HTML tag), Builtin (Vue) or Component
     pub kind: ElementKind,
@@ -90,6 +92,7 @@ pub struct ElementNode {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ElementKind {
     Builtin(BuiltinType),
     #[default]
@@ -98,6 +101,7 @@ pub enum ElementKind {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BuiltinType {
     Component,
     KeepAlive,
@@ -116,6 +120,7 @@ pub enum BuiltinType {
 /// - 0 or more `v-else-if` `ElementNode`s;
 /// - 0 or 1 `v-else` `ElementNode`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConditionalNodeSequence {
     pub if_node: Box<Conditional>,
     pub else_if_nodes: Vec<Conditional>,
@@ -125,6 +130,7 @@ pub struct ConditionalNodeSequence {
 /// A wrapper around an `ElementNode` with a condition attached to it.
 /// This is used in `v-if` and `v-else-if` nodes.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Conditional {
     pub condition: Expr,
     pub node: ElementNode,
@@ -133,6 +139,7 @@ pub struct Conditional {
 /// A special Vue `{{ expression }}`,
 /// which would be rendered as a stringified value of executing said expression.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Interpolation {
     pub value: Box<Expr>,
     pub template_scope: u32,
@@ -142,6 +149,7 @@ pub struct Interpolation {
 
 /// Starting tag represents [`ElementNode`]'s tag name and attributes
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StartingTag {
     pub tag_name: FervidAtom,
     pub attributes: Vec<AttributeOrBinding>,
@@ -153,6 +161,7 @@ pub struct StartingTag {
 /// because they bind something to DOM.
 /// `v-model` is not covered here because its code generation is not as trivial.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttributeOrBinding {
     /// `RegularAttribute` is a plain HTML attribute without any associated logic
     RegularAttribute {
@@ -172,6 +181,7 @@ pub enum AttributeOrBinding {
 /// - `:foo="bar"` yields `StrOrExpr::Str("foo")`;
 /// - `:[baz]="qux"` yields `StrOrExpr::Expr(Box::new(Expr::Lit(Lit::Str(Str { value: "baz".into(), .. }))))`
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StrOrExpr {
     Str(FervidAtom),
     Expr(Box<Expr>),
@@ -186,6 +196,7 @@ impl<'s> From<&'s str> for StrOrExpr {
 /// A helper structure attached to `ElementNode`s to handle Patch Flags
 /// and contain the list of dynamic props.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PatchHints {
     /// Patch flags
     pub flags: PatchFlagsSet,
@@ -311,6 +322,7 @@ pub type PatchFlagsSet = flagset::FlagSet<PatchFlags>;
 
 /// A structure which stores all the Vue directives of an `ElementNode`.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VueDirectives {
     pub custom: Vec<VCustomDirective>,
     pub v_cloak: Option<()>,
@@ -330,6 +342,7 @@ pub struct VueDirectives {
 
 /// `v-for`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VForDirective {
     /// `bar` in `v-for="foo in bar"`
     pub iterable: Box<Expr>,
@@ -341,6 +354,7 @@ pub struct VForDirective {
 
 /// `v-on` and its shorthand `@`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VOnDirective {
     /// What event to listen to. If None, it is equivalent to `v-on="..."`.
     pub event: Option<StrOrExpr>,
@@ -354,6 +368,7 @@ pub struct VOnDirective {
 
 /// `v-bind` and its shorthand `:`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VBindDirective {
     /// Attribute name to bind. If None, it is equivalent to `v-bind="..."`.
     pub argument: Option<StrOrExpr>,
@@ -371,6 +386,7 @@ pub struct VBindDirective {
 
 /// `v-model`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VModelDirective {
     /// What to apply v-model to, e.g. `first-name` in `v-model:first-name="first"`
     pub argument: Option<StrOrExpr>,
@@ -385,6 +401,7 @@ pub struct VModelDirective {
 
 /// `v-slot`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VSlotDirective {
     pub slot_name: Option<StrOrExpr>,
     /// What bindings are provided to slot children, e.g. `value` in `v-slot="{ value }"`
@@ -393,6 +410,7 @@ pub struct VSlotDirective {
 
 /// A custom directive defined by a user.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VCustomDirective {
     /// `foo` in `v-foo`
     pub name: FervidAtom,
@@ -409,6 +427,7 @@ pub struct VCustomDirective {
 ///
 /// <https://github.com/vuejs/core/blob/020851e57d9a9f727c6ea07e9c1575430af02b73/packages/compiler-core/src/options.ts#L76>
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BindingTypes {
     /// returned from data()
     Data,
@@ -448,7 +467,7 @@ pub enum BindingTypes {
 }
 
 /// Mode with which the template is attached to the exported SFC object.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum TemplateGenerationMode {
     /// Applies the transformation as if the template is rendered inline
     /// and variables are directly accessible in the function scope.
@@ -0,0 +1,514 @@
+//! Visitors for the template IR, in the style of `swc_ecma_visit`.
+//!
+//! Implement [`Visit`] to analyze or [`VisitMut`] to rewrite the template.
+//! Every method by default visits the children of the node,
+//! so an implementation only overrides the methods for the nodes it is interested in
+//! and calls `node.visit_children_with(self)` (or `visit_mut_children_with`) to keep descending.
+//!
+//! Js expressions and patterns are the leaves: [`Visit::visit_expr`] and [`Visit::visit_pat`]
+//! do not go inside of them, use `swc_ecma_visit` for that.
+//! Texts, comments and regular attributes are the leaves as well,
+//! their hooks (e.g. [`Visit::visit_text`]) receive the fields of the variant.
+
+use swc_core::{
+    common::Span,
+    ecma::ast::{Expr, Pat},
+};
+
+use crate::{
+    AttributeOrBinding, Conditional, ConditionalNodeSequence, ElementNode, FervidAtom,
+    Interpolation, Node, SfcTemplateBlock, StartingTag, StrOrExpr, VBindDirective,
+    VCustomDirective, VForDirective, VModelDirective, VOnDirective, VSlotDirective, VueDirectives,
+};
+
+macro_rules! define_visit {
+    (
+        $(struct $s_ty:ident: $s_visit:ident, $s_visit_mut:ident { $($field:ident),* })*
+        $(enum $e_ty:ident: $e_visit:ident, $e_visit_mut:ident {
+            $($variant:ident),*
+            $(; $h_pat:pat => $h_visit:ident, $h_visit_mut:ident($($h_arg:ident: $h_arg_ty:ty),*))*
+        })*
+        $(leaf $l_ty:ident: $l_visit:ident, $l_visit_mut:ident)*
+    ) => {
+        /// Read-only visitor of the template IR
+        pub trait Visit {
+            $(
+                fn $s_visit(&mut self, n: &$s_ty) {
+                    n.visit_children_with(self)
+                }
+            )*
+            $(
+                fn $e_visit(&mut self, n: &$e_ty) {
+                    n.visit_children_with(self)
+                }
+            )*
+            $($(
+                #[allow(unused_variables)]
+                fn $h_visit(&mut self, $($h_arg: &$h_arg_ty),*) {}
+            )*)*
+            $(
+                fn $l_visit(&mut self, _n: &$l_ty) {}
+            )*
+        }
+
+        /// Mutable visitor of the template IR
+        pub trait VisitMut {
+            $(
+                fn $s_visit_mut(&mut self, n: &mut $s_ty) {
+                    n.visit_mut_children_with(self)
+                }
+            )*
+            $(
+                fn $e_visit_mut(&mut self, n: &mut $e_ty) {
+                    n.visit_mut_children_with(self)
+                }
+            )*
+            $($(
+                #[allow(unused_variables)]
+                fn $h_visit_mut(&mut self, $($h_arg: &mut $h_arg_ty),*) {}
+            )*)*
+            $(
+                fn $l_visit_mut(&mut self, _n: &mut $l_ty) {}
+            )*
+        }
+
+        $(
+            impl<V: ?Sized + Visit> VisitWith<V> for $s_ty {
+                fn visit_with(&self, visitor: &mut V) {
+                    visitor.$s_visit(self)
+                }
+
+                fn visit_children_with(&self, _visitor: &mut V) {
+                    $(self.$field.visit_with(_visitor);)*
+                }
+            }
+
+            impl<V: ?Sized + VisitMut> VisitMutWith<V> for $s_ty {
+                fn visit_mut_with(&mut self, visitor: &mut V) {
+                    visitor.$s_visit_mut(self)
+                }
+
+                fn visit_mut_children_with(&mut self, _visitor: &mut V) {
+                    $(self.$field.visit_mut_with(_visitor);)*
+                }
+            }
+        )*
+
+        $(
+            impl<V: ?Sized + Visit> VisitWith<V> for $e_ty {
+                fn visit_with(&self, visitor: &mut V) {
+                    visitor.$e_visit(self)
+                }
+
+                fn visit_children_with(&self, visitor: &mut V) {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        $($e_ty::$variant(n) => n.visit_with(visitor),)*
+                        $($h_pat => visitor.$h_visit($($h_arg),*),)*
+                        _ => {}
+                    }
+                }
+            }
+
+            impl<V: ?Sized + VisitMut> VisitMutWith<V> for $e_ty {
+                fn visit_mut_with(&mut self, visitor: &mut V) {
+                    visitor.$e_visit_mut(self)
+                }
+
+                fn visit_mut_children_with(&mut self, visitor: &mut V) {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        $($e_ty::$variant(n) => n.visit_mut_with(visitor),)*
+                        $($h_pat => visitor.$h_visit_mut($($h_arg),*),)*
+                        _ => {}
+                    }
+                }
+            }
+        )*
+
+        $(
+            impl<V: ?Sized + Visit> VisitWith<V> for $l_ty {
+                fn visit_with(&self, visitor: &mut V) {
+                    visitor.$l_visit(self)
+                }
+
+                fn visit_children_with(&self, _visitor: &mut V) {}
+            }
+
+            impl<V: ?Sized + VisitMut> VisitMutWith<V> for $l_ty {
+                fn visit_mut_with(&mut self, visitor: &mut V) {
+                    visitor.$l_visit_mut(self)
+                }
+
+                fn visit_mut_children_with(&mut self, _visitor: &mut V) {}
+            }
+        )*
+    };
+}
+
+define_visit! {
+    struct SfcTemplateBlock: visit_sfc_template_block, visit_mut_sfc_template_block { roots }
+    struct ElementNode: visit_element_node, visit_mut_element_node { starting_tag, children }
+    struct StartingTag: visit_starting_tag, visit_mut_starting_tag { attributes, directives }
+    struct ConditionalNodeSequence: visit_conditional_node_sequence, visit_mut_conditional_node_sequence {
+        if_node, else_if_nodes, else_node
+    }
+    struct Conditional: visit_conditional, visit_mut_conditional { condition, node }
+    struct Interpolation: visit_interpolation, visit_mut_interpolation { value }
+    struct VueDirectives: visit_vue_directives, visit_mut_vue_directives {
+        custom, v_else_if, v_for, v_html, v_if, v_memo, v_model, v_show, v_slot, v_text
+    }
+    struct VBindDirective: visit_v_bind_directive, visit_mut_v_bind_directive { argument, value }
+    struct VOnDirective: visit_v_on_directive, visit_mut_v_on_directive { event, handler }
+    struct VForDirective: visit_v_for_directive, visit_mut_v_for_directive { itervar, iterable }
+    struct VModelDirective: visit_v_model_directive, visit_mut_v_model_directive {
+        argument, value, update_handler
+    }
+    struct VSlotDirective: visit_v_slot_directive, visit_mut_v_slot_directive { slot_name, value }
+    struct VCustomDirective: visit_v_custom_directive, visit_mut_v_custom_directive { argument, value }
+
+    enum Node: visit_node, visit_mut_node {
+        Element, Interpolation, ConditionalSeq;
+        Node::Text(text, span) => visit_text, visit_mut_text(text: FervidAtom, span: Span);
+        Node::Comment(text, span) => visit_comment, visit_mut_comment(text: FervidAtom, span: Span)
+    }
+    enum AttributeOrBinding: visit_attribute_or_binding, visit_mut_attribute_or_binding {
+        VBind, VOn;
+        AttributeOrBinding::RegularAttribute { name, value, span } =>
+            visit_regular_attribute, visit_mut_regular_attribute(name: FervidAtom, value: FervidAtom, span: Span)
+    }
+    enum StrOrExpr: visit_str_or_expr, visit_mut_str_or_expr { Expr }
+
+    leaf Expr: visit_expr, visit_mut_expr
+    leaf Pat: visit_pat, visit_mut_pat
+}
+
+/// A node which can be visited by a [`Visit`]or
+pub trait VisitWith<V: ?Sized + Visit> {
+    /// Calls the visitor method corresponding to this node
+    fn visit_with(&self, visitor: &mut V);
+
+    /// Visits the children of this node with the visitor
+    fn visit_children_with(&self, visitor: &mut V);
+}
+
+/// A node which can be visited by a [`VisitMut`]or
+pub trait VisitMutWith<V: ?Sized + VisitMut> {
+    /// Calls the visitor method corresponding to this node
+    fn visit_mut_with(&mut self, visitor: &mut V);
+
+    /// Visits the children of this node with the visitor
+    fn visit_mut_children_with(&mut self, visitor: &mut V);
+}
+
+impl<V: ?Sized + Visit, T: VisitWith<V>> VisitWith<V> for Box<T> {
+    fn visit_with(&self, visitor: &mut V) {
+        (**self).visit_with(visitor)
+    }
+
+    fn visit_children_with(&self, visitor: &mut V) {
+        (**self).visit_children_with(visitor)
+    }
+}
+
+impl<V: ?Sized + VisitMut, T: VisitMutWith<V>> VisitMutWith<V> for Box<T> {
+    fn visit_mut_with(&mut self, visitor: &mut V) {
+        (**self).visit_mut_with(visitor)
+    }
+
+    fn visit_mut_children_with(&mut self, visitor: &mut V) {
+        (**self).visit_mut_children_with(visitor)
+    }
+}
+
+impl<V: ?Sized + Visit, T: VisitWith<V>> VisitWith<V> for Option<T> {
+    fn visit_with(&self, visitor: &mut V) {
+        if let Some(n) = self {
+            n.visit_with(visitor)
+        }
+    }
+
+    fn visit_children_with(&self, visitor: &mut V) {
+        if let Some(n) = self {
+            n.visit_children_with(visitor)
+        }
+    }
+}
+
+impl<V: ?Sized + VisitMut, T: VisitMutWith<V>> VisitMutWith<V> for Option<T> {
+    fn visit_mut_with(&mut self, visitor: &mut V) {
+        if let Some(n) = self {
+            n.visit_mut_with(visitor)
+        }
+    }
+
+    fn visit_mut_children_with(&mut self, visitor: &mut V) {
+        if let Some(n) = self {
+            n.visit_mut_children_with(visitor)
+        }
+    }
+}
+
+impl<V: ?Sized + Visit, T: VisitWith<V>> VisitWith<V> for Vec<T> {
+    fn visit_with(&self, visitor: &mut V) {
+        for n in self.iter() {
+            n.visit_with(visitor)
+        }
+    }
+
+    fn visit_children_with(&self, visitor: &mut V) {
+        for n in self.iter() {
+            n.visit_children_with(visitor)
+        }
+    }
+}
+
+impl<V: ?Sized + VisitMut, T: VisitMutWith<V>> VisitMutWith<V> for Vec<T> {
+    fn visit_mut_with(&mut self, visitor: &mut V) {
+        for n in self.iter_mut() {
+            n.visit_mut_with(visitor)
+        }
+    }
+
+    fn visit_mut_children_with(&mut self, visitor: &mut V) {
+        for n in self.iter_mut() {
+            n.visit_mut_children_with(visitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::{
+        common::DUMMY_SP,
+        ecma::ast::{Ident, Lit, Str},
+    };
+
+    use super::*;
+    use crate::{ElementKind, FervidAtom, IntoIdent, PatchHints};
+
+    fn ident(sym: &str) -> Box<Expr> {
+        Box::new(Expr::Ident(FervidAtom::from(sym).into_ident()))
+    }
+
+    fn interpolation(sym: &str) -> Node {
+        Node::Interpolation(Interpolation {
+            value: ident(sym),
+            template_scope: 0,
+            patch_flag: false,
+            span: DUMMY_SP,
+        })
+    }
+
+    /// `<div class="a" v-if="cond" :foo="bar" @click="baz">{{ qux }} text<!-- comment --></div>`
+    fn template() -> SfcTemplateBlock {
+        let div = ElementNode {
+            kind: ElementKind::Element,
+            starting_tag: StartingTag {
+                tag_name: FervidAtom::from("div"),
+                attributes: vec![
+                    AttributeOrBinding::RegularAttribute {
+                        name: FervidAtom::from("class"),
+                        value: FervidAtom::from("a"),
+                        span: DUMMY_SP,
+                    },
+                    AttributeOrBinding::VBind(VBindDirective {
+                        argument: Some("foo".into()),
+                        value: ident("bar"),
+                        is_camel: false,
+                        is_prop: false,
+                        is_attr: false,
+                        span: DUMMY_SP,
+                    }),
+                    AttributeOrBinding::VOn(VOnDirective {
+                        event: Some("click".into()),
+                        handler: Some(ident("baz")),
+                        modifiers: vec![],
+                        span: DUMMY_SP,
+                    }),
+                ],
+                directives: Some(Box::new(VueDirectives {
+                    v_if: Some(ident("cond")),
+                    ..Default::default()
+                })),
+            },
+            children: vec![
+                interpolation("qux"),
+                Node::Text(FervidAtom::from(" text"), DUMMY_SP),
+                Node::Comment(FervidAtom::from(" comment "), DUMMY_SP),
+            ],
+            template_scope: 0,
+            patch_hints: PatchHints::default(),
+            span: DUMMY_SP,
+        };
+
+        SfcTemplateBlock {
+            lang: FervidAtom::from("html"),
+            roots: vec![Node::Element(div)],
+            attrs: vec![],
+            span: DUMMY_SP,
+        }
+    }
+
+    #[test]
+    fn it_visits_all_expressions() {
+        struct IdentCollector(Vec<FervidAtom>);
+
+        impl Visit for IdentCollector {
+            fn visit_expr(&mut self, n: &Expr) {
+                if let Expr::Ident(ident) = n {
+                    self.0.push(ident.sym.to_owned());
+                }
+            }
+        }
+
+        let mut collector = IdentCollector(vec![]);
+        template().visit_with(&mut collector);
+        assert_eq!(
+            vec!["bar", "baz", "cond", "qux"],
+            collector.0.iter().map(|s| s.as_ref()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_stops_when_children_are_not_visited() {
+        struct InterpolationCounter(usize);
+
+        impl Visit for InterpolationCounter {
+            fn visit_interpolation(&mut self, _n: &Interpolation) {
+                self.0 += 1;
+            }
+
+            // Do not go inside of the `v-if` elements
+            fn visit_element_node(&mut self, n: &ElementNode) {
+                let has_v_if = n
+                    .starting_tag
+                    .directives
+                    .as_ref()
+                    .is_some_and(|d| d.v_if.is_some());
+                if !has_v_if {
+                    n.visit_children_with(self);
+                }
+            }
+        }
+
+        let mut counter = InterpolationCounter(0);
+        template().visit_with(&mut counter);
+        assert_eq!(0, counter.0);
+
+        let mut counter = InterpolationCounter(0);
+        interpolation("foo").visit_with(&mut counter);
+        assert_eq!(1, counter.0);
+    }
+
+    #[test]
+    fn it_rewrites_expressions() {
+        struct Stringifier;
+
+        impl VisitMut for Stringifier {
+            fn visit_mut_expr(&mut self, n: &mut Expr) {
+                if let Expr::Ident(Ident { sym, span, .. }) = n {
+                    *n = Expr::Lit(Lit::Str(Str {
+                        span: *span,
+                        value: sym.to_owned(),
+                        raw: None,
+                    }));
+                }
+            }
+        }
+
+        let mut template = template();
+        template.visit_mut_with(&mut Stringifier);
+
+        let Node::Element(ref div) = template.roots[0] else {
+            panic!("Expected element")
+        };
+        let Node::Interpolation(ref interpolation) = div.children[0] else {
+            panic!("Expected interpolation")
+        };
+        assert!(matches!(*interpolation.value, Expr::Lit(Lit::Str(ref s)) if s.value == "qux"));
+
+        let Some(ref directives) = div.starting_tag.directives else {
+            panic!("Expected directives")
+        };
+        assert!(matches!(directives.v_if.as_deref(), Some(Expr::Lit(_))));
+    }
+
+    #[test]
+    fn it_visits_static_leaves() {
+        #[derive(Default)]
+        struct LeafCollector(Vec<String>);
+
+        impl Visit for LeafCollector {
+            fn visit_text(&mut self, text: &FervidAtom, _span: &Span) {
+                self.0.push(format!("text:{text}"));
+            }
+
+            fn visit_comment(&mut self, text: &FervidAtom, _span: &Span) {
+                self.0.push(format!("comment:{text}"));
+            }
+
+            fn visit_regular_attribute(
+                &mut self,
+                name: &FervidAtom,
+                value: &FervidAtom,
+                _span: &Span,
+            ) {
+                self.0.push(format!("attr:{name}={value}"));
+            }
+        }
+
+        let mut collector = LeafCollector::default();
+        template().visit_with(&mut collector);
+        assert_eq!(
+            vec!["attr:class=a", "text: text", "comment: comment "],
+            collector.0
+        );
+    }
+
+    #[test]
+    fn it_rewrites_static_leaves() {
+        struct Trimmer;
+
+        impl VisitMut for Trimmer {
+            fn visit_mut_text(&mut self, text: &mut FervidAtom, _span: &mut Span) {
+                *text = FervidAtom::from(text.trim());
+            }
+
+            fn visit_mut_regular_attribute(
+                &mut self,
+                _name: &mut FervidAtom,
+                value: &mut FervidAtom,
+                _span: &mut Span,
+            ) {
+                *value = FervidAtom::from("b");
+            }
+        }
+
+        let mut template = template();
+        template.visit_mut_with(&mut Trimmer);
+
+        let Node::Element(ref div) = template.roots[0] else {
+            panic!("Expected element")
+        };
+        assert!(matches!(div.children[1], Node::Text(ref text, _) if text == "text"));
+        assert!(matches!(
+            div.starting_tag.attributes[0],
+            AttributeOrBinding::RegularAttribute { ref value, .. } if value == "b"
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_serializes_to_json() {
+        let json = serde_json::to_value(template()).unwrap();
+        assert_eq!("html", json["lang"]);
+        assert_eq!(
+            "div",
+            json["roots"][0]["Element"]["starting_tag"]["tag_name"]
+        );
+
+        let deserialized: SfcTemplateBlock = serde_json::from_value(json).unwrap();
+        assert_eq!(1, deserialized.roots.len());
+    }
+}
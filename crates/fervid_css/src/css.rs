@@ -1,21 +1,31 @@
 mod codegen;
 mod error;
 mod parse;
+mod source_map;
 mod transform;
 
 use fervid_core::error::Severity;
 use swc_core::common::Span;
 use swc_css_parser::parser::ParserConfig;
 
-pub use codegen::{stringify, StringifyOptions};
+pub use codegen::{stringify, stringify_with_mappings, StringifyOptions};
 pub use error::CssError;
 pub use parse::parse_stylesheet;
+pub use source_map::{build_source_map, CssSourceMapConfig};
 pub use transform::ScopedTransformer;
 
 #[derive(Default)]
-pub struct TransformCssConfig {
+pub struct TransformCssConfig<'s> {
     pub parse: ParserConfig,
     pub stringify: StringifyOptions,
+    /// When present, a source map is generated for the transformed CSS
+    pub source_map: Option<CssSourceMapConfig<'s>>,
+}
+
+pub struct TransformCssResult {
+    pub code: String,
+    /// JSON source map, only present when requested in [`TransformCssConfig`]
+    pub source_map: Option<String>,
 }
 
 /// Transforms raw CSS, also handles the scopes.
@@ -25,7 +35,7 @@ pub fn transform_css(
     scope: Option<&str>,
     errors: &mut Vec<CssError>,
     config: TransformCssConfig,
-) -> Option<String> {
+) -> Option<TransformCssResult> {
     // Parse and collect errors
     let mut parse_errors = Vec::new();
     let parse_result = parse_stylesheet(content, span, config.parse, &mut parse_errors);
@@ -48,5 +58,19 @@ pub fn transform_css(
         return None;
     }
 
-    Some(stringify(&stylesheet, config.stringify))
+    let Some(source_map_config) = config.source_map else {
+        return Some(TransformCssResult {
+            code: stringify(&stylesheet, config.stringify),
+            source_map: None,
+        });
+    };
+
+    let mut mappings = Vec::new();
+    let code = stringify_with_mappings(&stylesheet, config.stringify, &mut mappings);
+    let source_map = build_source_map(&mappings, &source_map_config);
+
+    Some(TransformCssResult {
+        code,
+        source_map: Some(source_map),
+    })
 }
@@ -1,3 +1,4 @@
+use swc_core::common::{BytePos, LineCol};
 use swc_css_ast::{PseudoClassSelectorChildren, PseudoElementSelectorChildren, Stylesheet};
 use swc_css_codegen::{
     writer::basic::{BasicCssWriter, BasicCssWriterConfig},
@@ -20,8 +21,26 @@ impl Default for StringifyOptions {
 
 /// Stringifies the [`Stylesheet`]
 pub fn stringify(node: &Stylesheet, options: StringifyOptions) -> String {
+    stringify_impl(node, options, None)
+}
+
+/// Stringifies the [`Stylesheet`] and collects the mappings
+/// from source positions to the generated lines and columns
+pub fn stringify_with_mappings(
+    node: &Stylesheet,
+    options: StringifyOptions,
+    mappings: &mut Vec<(BytePos, LineCol)>,
+) -> String {
+    stringify_impl(node, options, Some(mappings))
+}
+
+fn stringify_impl(
+    node: &Stylesheet,
+    options: StringifyOptions,
+    mappings: Option<&mut Vec<(BytePos, LineCol)>>,
+) -> String {
     let mut buf = String::new();
-    let writer = BasicCssWriter::new(&mut buf, None, options.basic_css_writer);
+    let writer = BasicCssWriter::new(&mut buf, mappings, options.basic_css_writer);
     let mut codegen = CodeGenerator::new(
         writer,
         CodegenConfig {
@@ -1,5 +1,5 @@
-use fervid_core::error::{Severity, SeverityLevel};
-use swc_core::common::{Spanned, Span};
+use fervid_core::error::{ErrorCode, Severity, SeverityLevel};
+use swc_core::common::{Spanned, Span, DUMMY_SP};
 use swc_css_parser::error::{ErrorKind as ParseErrorKind, Error as ParseError};
 
 #[derive(Debug)]
@@ -52,3 +52,35 @@ impl Spanned for CssError {
         self.span
     }
 }
+
+impl ErrorCode for CssError {
+    fn error_code(&self) -> &'static str {
+        match &self.kind {
+            CssErrorKind::ParseRecoverable(_) | CssErrorKind::ParseUnrecoverable(_) => {
+                "CSS_SYNTAX_ERROR"
+            }
+            CssErrorKind::ParseDeepRecoverable(_) | CssErrorKind::ParseDeepUnrecoverable(_) => {
+                "CSS_DEEP_SELECTOR_SYNTAX_ERROR"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for CssErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            CssErrorKind::ParseRecoverable(kind)
+            | CssErrorKind::ParseUnrecoverable(kind)
+            | CssErrorKind::ParseDeepRecoverable(kind)
+            | CssErrorKind::ParseDeepUnrecoverable(kind) => kind,
+        };
+
+        f.write_str(&ParseError::new(DUMMY_SP, kind.clone()).message())
+    }
+}
+
+impl std::fmt::Display for CssError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.kind.fmt(f)
+    }
+}
@@ -0,0 +1,38 @@
+use swc_core::common::{
+    source_map::SourceMapGenConfig, sync::Lrc, BytePos, FileName, LineCol, SourceMap,
+};
+
+/// Options for generating a source map of the transformed CSS
+#[derive(Clone, Copy)]
+pub struct CssSourceMapConfig<'s> {
+    /// The whole source which the `Span`s point into, e.g. the SFC containing the `<style>` block.
+    /// It is expected to start at `BytePos(1)`.
+    pub source: &'s str,
+    /// Name of the source file used in the source map
+    pub filename: &'s str,
+}
+
+/// Builds a JSON source map from the mappings collected during stringification
+pub fn build_source_map(mappings: &[(BytePos, LineCol)], config: &CssSourceMapConfig) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    let filename = FileName::Custom(config.filename.to_string());
+    cm.new_source_file(Lrc::new(filename), config.source.to_owned());
+
+    let map = cm.build_source_map_with_config(mappings, None, InlineSourcesConfig);
+
+    let mut buf = vec![];
+    map.to_writer(&mut buf).expect("Failed to write source map");
+    String::from_utf8(buf).expect("Invalid UTF-8 in source map")
+}
+
+struct InlineSourcesConfig;
+
+impl SourceMapGenConfig for InlineSourcesConfig {
+    fn file_name_to_source(&self, f: &FileName) -> String {
+        f.to_string()
+    }
+
+    fn inline_sources_content(&self, _f: &FileName) -> bool {
+        true
+    }
+}
@@ -20,7 +20,7 @@
 //! let result = fervid_css::transform_css(input, span, Some("data-v-abcd1234"), &mut errors, Default::default());
 //!
 //! if let Some(transformed_css) = result {
-//!     assert_eq!(".example[data-v-abcd1234]{background:#ff0}", transformed_css);
+//!     assert_eq!(".example[data-v-abcd1234]{background:#ff0}", transformed_css.code);
 //! }
 //! ```
 
@@ -52,7 +52,7 @@ mod tests {
             );
             let mut errors = Vec::new();
             let out = css::transform_css($input, span, Some("data-v-abcd1234"), &mut errors, Default::default());
-            assert_eq!(out.ok_or(()), $expected);
+            assert_eq!(out.map(|r| r.code).ok_or(()), $expected);
         };
     }
 
@@ -194,4 +194,39 @@ mod tests {
             minify_yes!()
         );
     }
+
+    #[test]
+    fn it_generates_source_map() {
+        // Style block content inside an SFC
+        let sfc =
+            "<template><div></div></template>\n<style scoped>\n.foo {\n  color: red\n}\n</style>";
+        let content = "\n.foo {\n  color: red\n}\n";
+        let lo = sfc.find(content).unwrap() as u32 + 1;
+        let span = Span::new(BytePos(lo), BytePos(lo + content.len() as u32));
+
+        let mut errors = Vec::new();
+        let config = css::TransformCssConfig {
+            source_map: Some(css::CssSourceMapConfig {
+                source: sfc,
+                filename: "Component.vue",
+            }),
+            ..Default::default()
+        };
+        let out = css::transform_css(content, span, Some("data-v-abcd1234"), &mut errors, config)
+            .expect("Should transform");
+        assert_eq!(".foo[data-v-abcd1234]{color:red}", out.code);
+
+        let map = out.source_map.expect("Should have source map");
+        let map = sourcemap::SourceMap::from_slice(map.as_bytes()).unwrap();
+        assert_eq!(Some("Component.vue"), map.get_source(0));
+
+        // `.foo` is on the third line (index 2) of the SFC
+        let foo_token = map.lookup_token(0, 0).expect("Should map `.foo`");
+        assert_eq!((2, 0), (foo_token.get_src_line(), foo_token.get_src_col()));
+
+        // `red` is on the fourth line (index 3) of the SFC
+        let red_col = out.code.find("red").unwrap() as u32;
+        let red_token = map.lookup_token(0, red_col).expect("Should map `red`");
+        assert_eq!((3, 9), (red_token.get_src_line(), red_token.get_src_col()));
+    }
 }
@@ -1,2 +1,16 @@
 const binPath: string;
 export default binPath;
+
+/** Options of the plugin, e.g. `plugins: [['farm-plugin-vue-fervid', { exclude: ['src/legacy/**'] }]]` */
+export interface FervidPluginOptions {
+  /** Globs of the SFCs to compile, relative to the project root. Default: `['**\/*.vue']` */
+  include?: string[];
+  /** Globs of the SFCs to skip, relative to the project root */
+  exclude?: string[];
+  /** Apply production optimizations. Default: `true` when Farm runs in the `production` mode */
+  isProduction?: boolean;
+  /** Compile for server-side rendering. Default: `false` */
+  ssr?: boolean;
+  /** Globs of the SFCs compiled as custom elements. Default: `['**\/*.ce.vue']` */
+  customElement?: string[];
+}
@@ -0,0 +1,149 @@
+//! Hot module replacement of the compiled SFCs, same as in `@vitejs/plugin-vue`:
+//! template-only changes re-render the mounted instances, other changes reload the component.
+
+use std::hash::{Hash, Hasher};
+
+use farmfe_core::parking_lot::Mutex;
+use fervid::{parse, ParseOptions, ParsedSfcBlock};
+use fxhash::{FxHashMap, FxHasher};
+
+/// Name of the component object in the compiled code, see `CompileOptions::gen_default_as`
+pub const COMPONENT_NAME: &str = "_sfc_main";
+
+/// Remembers the previous version of each SFC to find out what has changed
+#[derive(Default)]
+pub struct HmrState {
+    previous: Mutex<FxHashMap<String, SfcHashes>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct SfcHashes {
+    template: u64,
+    rest: u64,
+}
+
+impl HmrState {
+    /// Whether only the `<template>` of the SFC at `path` has changed since the previous call
+    pub fn is_template_only_change(&self, path: &str, source: &str) -> bool {
+        let Some(hashes) = SfcHashes::new(source) else {
+            self.previous.lock().remove(path);
+            return false;
+        };
+
+        match self.previous.lock().insert(path.to_owned(), hashes) {
+            Some(previous) => previous.rest == hashes.rest && previous.template != hashes.template,
+            None => false,
+        }
+    }
+}
+
+impl SfcHashes {
+    fn new(source: &str) -> Option<Self> {
+        let parsed = parse(source, ParseOptions::default()).ok()?;
+
+        let mut template = FxHasher::default();
+        hash_block(parsed.template.as_ref(), &mut template);
+
+        let mut rest = FxHasher::default();
+        hash_block(parsed.script.as_ref(), &mut rest);
+        hash_block(parsed.script_setup.as_ref(), &mut rest);
+        for block in parsed.styles.iter().chain(parsed.custom_blocks.iter()) {
+            hash_block(Some(block), &mut rest);
+        }
+
+        Some(SfcHashes {
+            template: template.finish(),
+            rest: rest.finish(),
+        })
+    }
+}
+
+fn hash_block(block: Option<&ParsedSfcBlock>, hasher: &mut FxHasher) {
+    match block {
+        Some(block) => {
+            block.block_type.hash(hasher);
+            block.content.hash(hasher);
+            block.attrs.hash(hasher);
+        }
+        None => 0u8.hash(hasher),
+    }
+}
+
+/// Code registering the component in the Vue HMR runtime and accepting the updates of the module.
+/// The default export is generated here, because the compiled code only declares [`COMPONENT_NAME`]
+pub fn hmr_code(hmr_id: &str, rerender_only: bool) -> String {
+    let mut code = format!(
+        r#"
+{COMPONENT_NAME}.__hmrId = "{hmr_id}"
+typeof __VUE_HMR_RUNTIME__ !== "undefined" && __VUE_HMR_RUNTIME__.createRecord({COMPONENT_NAME}.__hmrId, {COMPONENT_NAME})
+"#
+    );
+
+    if rerender_only {
+        code.push_str("export const _rerender_only = true\n");
+    }
+
+    code.push_str(
+        r#"if (import.meta.hot) {
+  import.meta.hot.accept((mod) => {
+    if (!mod) return
+    const { default: updated, _rerender_only } = mod
+    if (_rerender_only) {
+      __VUE_HMR_RUNTIME__.rerender(updated.__hmrId, updated.render)
+    } else {
+      __VUE_HMR_RUNTIME__.reload(updated.__hmrId, updated)
+    }
+  })
+}
+"#,
+    );
+    code.push_str(&format!("export default {COMPONENT_NAME}\n"));
+
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SFC: &str = "<template><div>{{ a }}</div></template>\n<script setup>\nconst a = 1\n</script>\n<style scoped>\n.a { color: red }\n</style>\n";
+
+    #[test]
+    fn it_detects_template_only_changes() {
+        let state = HmrState::default();
+
+        // First compilation
+        assert!(!state.is_template_only_change("App.vue", SFC));
+
+        // Nothing changed
+        assert!(!state.is_template_only_change("App.vue", SFC));
+
+        let template_changed = SFC.replace("<div>", "<span>").replace("</div>", "</span>");
+        assert!(state.is_template_only_change("App.vue", &template_changed));
+
+        let script_changed = template_changed.replace("const a = 1", "const a = 2");
+        assert!(!state.is_template_only_change("App.vue", &script_changed));
+
+        let both_changed = SFC.replace("const a = 1", "const a = 3");
+        assert!(!state.is_template_only_change("App.vue", &both_changed));
+
+        let style_changed = both_changed
+            .replace("<div>", "<p>")
+            .replace("</div>", "</p>")
+            .replace("red", "blue");
+        assert!(!state.is_template_only_change("App.vue", &style_changed));
+
+        // Other files are tracked separately
+        assert!(!state.is_template_only_change("Other.vue", &template_changed));
+    }
+
+    #[test]
+    fn it_generates_hmr_code() {
+        let code = hmr_code("abcd", false);
+        assert!(code.contains("_sfc_main.__hmrId = \"abcd\""));
+        assert!(!code.contains("export const _rerender_only"));
+        assert!(code.ends_with("export default _sfc_main\n"));
+
+        assert!(hmr_code("abcd", true).contains("export const _rerender_only = true"));
+    }
+}
@@ -1,30 +1,63 @@
 #![deny(clippy::all)]
 
-use std::fs;
+use std::{
+    fs,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 use farmfe_core::{
     config::Config,
+    context::CompilationContext,
     error::CompilationError,
-    module::ModuleType,
+    module::{ModuleId, ModuleType},
     parking_lot::Mutex,
-    plugin::{Plugin, PluginLoadHookResult, PluginTransformHookResult},
+    plugin::{
+        Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult,
+        PluginTransformHookParam, PluginTransformHookResult,
+    },
+};
+use fervid::{
+    error::{Severity, SeverityLevel},
+    errors::CompileError,
+    CompileEmittedStyle, CompileOptions, CompileResult,
 };
-use fervid::CompileOptions;
-use fxhash::FxHashMap;
+use fervid_bundler::{
+    extract_block, generate_css_modules_code, generate_custom_blocks_code, generate_prelude,
+    VueBlock, VueQuery, VueRequest,
+};
+use fxhash::{FxHashMap, FxHasher32};
 
 use farmfe_macro_plugin::farm_plugin;
+use hmr::{hmr_code, HmrState, COMPONENT_NAME};
+use options::ResolvedOptions;
+
+mod hmr;
+mod options;
 
 #[farm_plugin]
 pub struct FarmPluginVueFervid {
-    virtual_modules: Mutex<FxHashMap<String, PluginLoadHookResult>>,
+    /// Invalid options are reported by the first hook, because `new` cannot fail
+    options: Result<ResolvedOptions, String>,
+    /// Compilation results of the SFCs by their paths, the blocks are loaded from them
+    compiled: Mutex<FxHashMap<String, CompileResult>>,
+    hmr: HmrState,
 }
 
 impl FarmPluginVueFervid {
-    fn new(_config: &Config, _options: String) -> Self {
+    fn new(config: &Config, options: String) -> Self {
         Self {
-            virtual_modules: Default::default(),
+            options: ResolvedOptions::new(config, &options),
+            compiled: Default::default(),
+            hmr: Default::default(),
         }
     }
+
+    fn options(&self) -> farmfe_core::error::Result<&ResolvedOptions> {
+        self.options.as_ref().map_err(|e| {
+            CompilationError::GenericError(format!("Invalid options of FarmPluginVueFervid: {}", e))
+        })
+    }
 }
 
 impl Plugin for FarmPluginVueFervid {
@@ -32,34 +65,35 @@ impl Plugin for FarmPluginVueFervid {
         "FarmPluginVueFervid"
     }
 
+    fn config(&self, _config: &mut Config) -> farmfe_core::error::Result<Option<()>> {
+        self.options()?;
+        Ok(None)
+    }
+
     fn load(
         &self,
-        param: &farmfe_core::plugin::PluginLoadHookParam,
-        _context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
-        _hook_context: &farmfe_core::plugin::PluginHookContext,
-    ) -> farmfe_core::error::Result<Option<farmfe_core::plugin::PluginLoadHookResult>> {
-        // println!(
-        //     "load path: {:?}, id: {:?}",
-        //     param.resolved_path, param.module_id
-        // );
-
-        // Virtual modules
-        if param.query.iter().any(|it| it.0 == "vue") {
-            let virtual_modules = self.virtual_modules.lock();
-            let Some(load_result) = virtual_modules.get(&param.module_id) else {
+        param: &PluginLoadHookParam,
+        _context: &Arc<CompilationContext>,
+        _hook_context: &PluginHookContext,
+    ) -> farmfe_core::error::Result<Option<PluginLoadHookResult>> {
+        // Blocks of the compiled SFCs
+        if let Some(query) = VueRequest::parse(&param.module_id).query {
+            let compiled = self.compiled.lock();
+            let Some(compile_result) = compiled.get(param.resolved_path) else {
                 return Ok(None);
             };
 
-            // We have to re-create because this struct is not `Clone`
-            return Ok(Some(PluginLoadHookResult {
-                content: load_result.content.to_owned(),
-                module_type: load_result.module_type.to_owned(),
-                source_map: load_result.source_map.to_owned(),
-            }));
+            return Ok(load_block(compile_result, &query));
         }
 
-        if param.resolved_path.ends_with(".vue") {
-            let content = fs::read_to_string(param.resolved_path).expect("Should exist");
+        if param.resolved_path.ends_with(".vue") && self.options()?.is_included(param.resolved_path)
+        {
+            let content = fs::read_to_string(param.resolved_path).map_err(|e| {
+                CompilationError::LoadError {
+                    resolved_path: param.resolved_path.to_owned(),
+                    source: Some(Box::new(e)),
+                }
+            })?;
 
             return Ok(Some(PluginLoadHookResult {
                 content,
@@ -73,72 +107,223 @@ impl Plugin for FarmPluginVueFervid {
 
     fn transform(
         &self,
-        param: &farmfe_core::plugin::PluginTransformHookParam,
-        _context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
-    ) -> farmfe_core::error::Result<Option<farmfe_core::plugin::PluginTransformHookResult>> {
+        param: &PluginTransformHookParam,
+        context: &Arc<CompilationContext>,
+    ) -> farmfe_core::error::Result<Option<PluginTransformHookResult>> {
         // Guard
         if !matches!(param.module_type, ModuleType::Custom(ref typ) if typ == "vue") {
             return Ok(None);
         }
 
-        let is_custom_element = param.resolved_path.ends_with(".ce.vue");
+        let options = self.options()?;
+        let is_prod = options.is_prod;
+        let is_hmr = !is_prod && !options.ssr;
+        let source_map = context.sourcemap_enabled(&param.module_id);
+
+        // Stable between the compilations, unlike the hash of the content
+        let hmr_id = {
+            let mut hasher = FxHasher32::default();
+            param.module_id.hash(&mut hasher);
+            format!("{:x}", hasher.finish())
+        };
 
         let file_compile_result = fervid::compile(
             &param.content,
             CompileOptions {
                 filename: std::borrow::Cow::Borrowed(param.resolved_path),
-                id: param.module_id.clone().into(),
-                is_prod: Some(true),
-                is_custom_element: Some(is_custom_element),
-                props_destructure: None,
-                ssr: None,
-                gen_default_as: None,
-                source_map: None,
+                id: hmr_id.as_str().into(),
+                is_prod: Some(is_prod),
+                is_custom_element: Some(options.is_custom_element(param.resolved_path)),
+                ssr: Some(options.ssr),
+                gen_default_as: Some(COMPONENT_NAME.into()),
+                source_map: Some(source_map),
+                ..Default::default()
             },
         );
 
-        let Ok(compile_result) = file_compile_result else {
-            return Err(CompilationError::TransformError {
-                resolved_path: param.resolved_path.to_owned(),
-                msg: "Failed to compile".into(),
-            });
+        let mut compile_result = match file_compile_result {
+            Ok(compile_result) => compile_result,
+            Err(error) => return Err(transform_error(param, &[error])),
         };
 
-        let mut prepend = String::new();
-        if !compile_result.styles.is_empty() {
-            let mut virtual_modules = self.virtual_modules.lock();
-
-            let base_path = param.resolved_path;
-            let module_id = &param.module_id;
-
-            for (idx, style) in compile_result.styles.into_iter().enumerate() {
-                let lang = style.lang;
-                let query = format!("?vue&type=style&idx={idx}&lang={lang}");
-                let virtual_module_id = format!("{module_id}{query}");
-                prepend.push_str(&format!("import '{base_path}{query}'\n"));
-
-                virtual_modules.insert(
-                    virtual_module_id,
-                    PluginLoadHookResult {
-                        content: style.code,
-                        // TODO Determine based on style lang
-                        module_type: ModuleType::Css,
-                        source_map: None,
-                    },
+        // Only the unrecoverable errors fail the module, the rest is reported like the warnings
+        let (errors, diagnostics) =
+            partition_diagnostics(std::mem::take(&mut compile_result.errors));
+        if !errors.is_empty() {
+            return Err(transform_error(param, &errors));
+        }
+        if !diagnostics.is_empty() {
+            let mut log_store = context.log_store.lock();
+            for diagnostic in diagnostics {
+                log_store.add_warning(
+                    diagnostic.to_diagnostic_string(&param.content, param.resolved_path),
                 );
             }
         }
 
-        let mut content = compile_result.code;
-        if !prepend.is_empty() {
-            content.insert_str(0, &prepend);
+        // Recompile when the files the SFC depends on change, e.g. the types of `defineProps`
+        if !compile_result.dependencies.is_empty() {
+            context.add_watch_files(
+                ModuleId::from(param.module_id.as_str()),
+                compile_result
+                    .dependencies
+                    .iter()
+                    .map(|dependency| context.str_to_module_id(dependency))
+                    .collect(),
+            )?;
         }
 
-        return Ok(Some(PluginTransformHookResult {
+        // Imports are appended rather than prepended to keep the source map of the code intact
+        let mut content = std::mem::take(&mut compile_result.code);
+        content.push('\n');
+        content.push_str(&generate_prelude(param.resolved_path, &compile_result));
+        content.push_str(&generate_css_modules_code(COMPONENT_NAME, &compile_result));
+        content.push_str(&generate_custom_blocks_code(
+            COMPONENT_NAME,
+            &compile_result,
+        ));
+
+        if is_hmr {
+            let rerender_only = self
+                .hmr
+                .is_template_only_change(param.resolved_path, &param.content);
+            content.push_str(&hmr_code(&hmr_id, rerender_only));
+        } else {
+            content.push_str(&format!("export default {COMPONENT_NAME}\n"));
+        }
+
+        let module_type = if compile_result.is_ts {
+            ModuleType::Ts
+        } else {
+            ModuleType::Js
+        };
+        let source_map = compile_result.source_map.take();
+
+        self.compiled
+            .lock()
+            .insert(param.resolved_path.to_owned(), compile_result);
+
+        Ok(Some(PluginTransformHookResult {
             content,
-            module_type: Some(ModuleType::Ts),
-            source_map: None,
+            module_type: Some(module_type),
+            source_map,
             ignore_previous_source_map: false,
-        }));
+        }))
+    }
+}
+
+/// Module of the block requested by `query`, or `None` when there is no such block
+fn load_block(compile_result: &CompileResult, query: &VueQuery) -> Option<PluginLoadHookResult> {
+    match extract_block(compile_result, query)? {
+        VueBlock::Style(style) => Some(PluginLoadHookResult {
+            content: style.code.to_owned(),
+            module_type: style_module_type(style),
+            source_map: style.source_map.to_owned(),
+        }),
+
+        // Custom blocks are left to the plugins handling their `lang` or tag name, e.g. `i18n`
+        VueBlock::Custom(block) => Some(PluginLoadHookResult {
+            content: block.content.to_owned(),
+            module_type: ModuleType::from(query.lang.as_deref().unwrap_or(&block.tag_name)),
+            source_map: None,
+        }),
+    }
+}
+
+/// Compiled styles are plain CSS, the others are left to the plugins of their languages
+/// (e.g. `@farmfe/plugin-sass` handles the `sass` module type)
+fn style_module_type(style: &CompileEmittedStyle) -> ModuleType {
+    if style.is_compiled {
+        return ModuleType::Css;
+    }
+
+    match style.lang.as_str() {
+        "css" => ModuleType::Css,
+        "scss" | "sass" => ModuleType::Custom("sass".to_owned()),
+        "styl" | "stylus" => ModuleType::Custom("stylus".to_owned()),
+        lang => ModuleType::Custom(lang.to_owned()),
+    }
+}
+
+/// Splits the diagnostics into the unrecoverable errors and the rest,
+/// i.e. the warnings and the errors the compiler has recovered from
+fn partition_diagnostics(diagnostics: Vec<CompileError>) -> (Vec<CompileError>, Vec<CompileError>) {
+    diagnostics
+        .into_iter()
+        .partition(|error| matches!(error.get_severity(), SeverityLevel::UnrecoverableError))
+}
+
+fn transform_error(param: &PluginTransformHookParam, errors: &[CompileError]) -> CompilationError {
+    let msg = errors
+        .iter()
+        .map(|error| error.to_diagnostic_string(&param.content, param.resolved_path))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    CompilationError::TransformError {
+        resolved_path: param.resolved_path.to_owned(),
+        msg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(lang: &str, is_compiled: bool) -> CompileEmittedStyle {
+        CompileEmittedStyle {
+            code: String::new(),
+            is_compiled,
+            lang: lang.to_owned(),
+            is_scoped: false,
+            source_map: None,
+            attrs: vec![],
+        }
+    }
+
+    #[test]
+    fn it_fails_the_config_hook_on_invalid_options() {
+        let plugin = FarmPluginVueFervid::new(&Config::default(), "{ \"include\": 1 }".to_owned());
+
+        let error = plugin.config(&mut Config::default()).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Invalid options of FarmPluginVueFervid"));
+    }
+
+    #[test]
+    fn it_fails_only_on_unrecoverable_errors() {
+        let compile = |source: &str| {
+            fervid::compile(source, CompileOptions::default())
+                .unwrap()
+                .errors
+        };
+
+        // Malformed directive
+        let (errors, diagnostics) =
+            partition_diagnostics(compile("<template><div :>hi</div></template>"));
+        assert!(errors.is_empty());
+        assert_eq!(1, diagnostics.len());
+
+        let (errors, _) = partition_diagnostics(compile("<script setup>const</script>"));
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn it_maps_style_languages() {
+        assert_eq!(ModuleType::Css, style_module_type(&style("css", true)));
+        assert_eq!(ModuleType::Css, style_module_type(&style("css", false)));
+        assert_eq!(
+            ModuleType::Custom("sass".to_owned()),
+            style_module_type(&style("scss", false))
+        );
+        assert_eq!(
+            ModuleType::Custom("less".to_owned()),
+            style_module_type(&style("less", false))
+        );
+        assert_eq!(
+            ModuleType::Custom("stylus".to_owned()),
+            style_module_type(&style("styl", false))
+        );
     }
 }
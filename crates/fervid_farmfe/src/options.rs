@@ -0,0 +1,154 @@
+use farmfe_core::{
+    config::{Config, Mode},
+    serde::Deserialize,
+    serde_json,
+    wax::{Glob, Pattern},
+};
+
+/// Options passed from the JS side as JSON, e.g.
+/// `["farm-plugin-vue-fervid", { "exclude": ["**/legacy/**"], "customElement": ["**/*.ce.vue"] }]`
+#[derive(Debug, Default, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase", default)]
+pub struct FervidPluginOptions {
+    /// Globs of the SFCs to compile, relative to the project root. Default: `["**/*.vue"]`
+    pub include: Vec<String>,
+    /// Globs of the SFCs to skip, relative to the project root
+    pub exclude: Vec<String>,
+    /// Apply production optimizations. Default: derived from the `mode` of Farm
+    pub is_production: Option<bool>,
+    /// Compile for server-side rendering. Default: false
+    pub ssr: Option<bool>,
+    /// Globs of the SFCs compiled as custom elements. Default: `["**/*.ce.vue"]`
+    pub custom_element: Option<Vec<String>>,
+}
+
+/// Options normalized against the Farm config
+pub struct ResolvedOptions {
+    pub include: Vec<Glob<'static>>,
+    pub exclude: Vec<Glob<'static>>,
+    pub is_prod: bool,
+    pub ssr: bool,
+    pub custom_element: Vec<Glob<'static>>,
+    root: String,
+}
+
+impl ResolvedOptions {
+    pub fn new(config: &Config, options: &str) -> Result<Self, String> {
+        let options: FervidPluginOptions = if options.trim().is_empty() {
+            Default::default()
+        } else {
+            serde_json::from_str(options).map_err(|e| e.to_string())?
+        };
+
+        let include = if options.include.is_empty() {
+            vec!["**/*.vue".to_owned()]
+        } else {
+            options.include
+        };
+        let custom_element = options
+            .custom_element
+            .unwrap_or_else(|| vec!["**/*.ce.vue".to_owned()]);
+
+        Ok(ResolvedOptions {
+            include: compile_globs(&include)?,
+            exclude: compile_globs(&options.exclude)?,
+            is_prod: options
+                .is_production
+                .unwrap_or(matches!(config.mode, Mode::Production)),
+            ssr: options.ssr.unwrap_or(false),
+            custom_element: compile_globs(&custom_element)?,
+            root: config.root.replace('\\', "/"),
+        })
+    }
+
+    /// Whether the SFC at `resolved_path` must be compiled
+    pub fn is_included(&self, resolved_path: &str) -> bool {
+        let path = self.relative_path(resolved_path);
+        self.include.iter().any(|glob| glob.is_match(path.as_str()))
+            && !self.exclude.iter().any(|glob| glob.is_match(path.as_str()))
+    }
+
+    pub fn is_custom_element(&self, resolved_path: &str) -> bool {
+        let path = self.relative_path(resolved_path);
+        self.custom_element
+            .iter()
+            .any(|glob| glob.is_match(path.as_str()))
+    }
+
+    /// Path relative to the project root with forward slashes, or the full path when outside of the root
+    fn relative_path(&self, resolved_path: &str) -> String {
+        let path = resolved_path.replace('\\', "/");
+        match path.strip_prefix(&self.root) {
+            Some(relative) => relative.trim_start_matches('/').to_owned(),
+            None => path,
+        }
+    }
+}
+
+fn compile_globs(globs: &[String]) -> Result<Vec<Glob<'static>>, String> {
+    globs
+        .iter()
+        .map(|glob| {
+            Glob::new(glob)
+                .map(Glob::into_owned)
+                .map_err(|e| format!("Invalid glob `{}`: {}", glob, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: Mode) -> Config {
+        Config {
+            root: "/project".to_owned(),
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_uses_defaults() {
+        let options = ResolvedOptions::new(&config(Mode::Development), "").unwrap();
+        assert!(!options.is_prod);
+        assert!(!options.ssr);
+        assert!(options.is_included("/project/src/App.vue"));
+        assert!(!options.is_included("/project/src/main.ts"));
+        assert!(options.is_custom_element("/project/src/Button.ce.vue"));
+        assert!(!options.is_custom_element("/project/src/App.vue"));
+
+        let options = ResolvedOptions::new(&config(Mode::Production), "{}").unwrap();
+        assert!(options.is_prod);
+    }
+
+    #[test]
+    fn it_parses_options() {
+        let options = ResolvedOptions::new(
+            &config(Mode::Production),
+            r#"{
+                "include": ["src/**/*.vue"],
+                "exclude": ["src/legacy/**"],
+                "isProduction": false,
+                "ssr": true,
+                "customElement": ["src/elements/**/*.vue"]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(!options.is_prod);
+        assert!(options.ssr);
+        assert!(options.is_included("/project/src/App.vue"));
+        assert!(!options.is_included("/project/src/legacy/Old.vue"));
+        assert!(!options.is_included("/project/other/App.vue"));
+        assert!(options.is_custom_element("/project/src/elements/Button.vue"));
+        assert!(!options.is_custom_element("/project/src/Button.ce.vue"));
+    }
+
+    #[test]
+    fn it_reports_invalid_options() {
+        let config = config(Mode::Development);
+        assert!(ResolvedOptions::new(&config, r#"{ "ssr": "yes" }"#).is_err());
+        assert!(ResolvedOptions::new(&config, r#"{ "include": ["src/[.vue"] }"#).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+import { test, expect } from 'vitest'
+
+import { Compiler } from '../index'
+
+const SFC = `<template><div class="a"></div></template>
+<style scoped>.a { color: red }</style>
+<style lang="scss" module="classes">.b { color: blue }</style>
+<i18n lang="json" locale="en">{ "hello": "Hello" }</i18n>
+`
+
+test('should generate the ids of the blocks', () => {
+  const result = new Compiler().compileSync(SFC, { filename: '/src/App.vue', id: '' })
+
+  const [scoped, module] = result.styles
+  expect(scoped.query).toMatch(/^\?vue&type=style&index=0&scoped=\w+&lang\.css$/)
+  expect(module.query).toBe('?vue&type=style&index=1&module=classes&lang.module.scss')
+  expect(result.customBlocks[0].query).toBe('?vue&type=i18n&index=0&locale=en&lang.json')
+
+  expect(result.prelude.split('\n')).toEqual([
+    `import "/src/App.vue${scoped.query}"`,
+    `import style1 from "/src/App.vue${module.query}"`,
+    `import _sfc_block0 from "/src/App.vue${result.customBlocks[0].query}"`,
+    '',
+  ])
+})
+
+test('should not generate a prelude without blocks', () => {
+  const result = new Compiler().compileSync('<template><div></div></template>', { filename: 'App.vue', id: '' })
+
+  expect(result.prelude).toBe('')
+})
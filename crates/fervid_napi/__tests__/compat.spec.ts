@@ -0,0 +1,190 @@
+import { test, expect, describe } from 'vitest'
+import * as official from '@vue/compiler-sfc'
+
+import * as compat from '../compat'
+
+const SFC = `<template>
+  <div :class="$style.a">{{ msg }}</div>
+</template>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+const msg = ref('Hello')
+const count = 1
+</script>
+
+<style scoped>
+.a { color: v-bind(msg) }
+</style>
+`
+
+describe('parse', () => {
+  test('should match the official descriptor', () => {
+    const { descriptor, errors } = compat.parse(SFC, { filename: 'App.vue' })
+    const expected = official.parse(SFC, { filename: 'App.vue' }).descriptor
+
+    expect(errors).toHaveLength(0)
+    expect(descriptor.filename).toBe(expected.filename)
+    expect(descriptor.script).toBeNull()
+    expect(descriptor.cssVars).toEqual(expected.cssVars)
+    expect(descriptor.slotted).toBe(expected.slotted)
+
+    for (const key of ['template', 'scriptSetup'] as const) {
+      const block = descriptor[key]!
+      const expectedBlock = expected[key]!
+      expect(block.type).toBe(expectedBlock.type)
+      expect(block.content).toBe(expectedBlock.content)
+      expect(block.attrs).toEqual(expectedBlock.attrs)
+      expect(block.lang).toBe(expectedBlock.lang)
+      expect(block.loc).toEqual(expectedBlock.loc)
+    }
+
+    expect(descriptor.styles[0].scoped).toBe(true)
+    expect(descriptor.styles[0].content).toBe(expected.styles[0].content)
+  })
+
+  test('should report errors', () => {
+    const { errors } = compat.parse('<template><div></div></template><template></template>')
+
+    expect(errors).toHaveLength(1)
+    expect(errors[0]).toBeInstanceOf(SyntaxError)
+    expect(errors[0].code).toBe('SFC_DUPLICATE_TEMPLATE')
+  })
+})
+
+describe('compileScript', () => {
+  const SCRIPT_SFC = `<template>
+  <div>{{ msg }}</div>
+</template>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+const msg = ref('Hello')
+const count = 1
+</script>
+`
+
+  test('should match the official script', () => {
+    const { descriptor } = compat.parse(SCRIPT_SFC, { filename: 'App.vue' })
+    const script = compat.compileScript(descriptor, { id: 'data-v-7ba5bd90' })
+    const expected = official.compileScript(official.parse(SCRIPT_SFC, { filename: 'App.vue' }).descriptor, {
+      id: 'data-v-7ba5bd90',
+    })
+
+    // Unlike the official implementation, imports from `vue` are not included
+    const { ref, ...expectedBindings } = expected.bindings!
+    expect(ref).toBe('setup-const')
+    expect(script.bindings).toEqual(expectedBindings)
+
+    expect(script.setup).toBe(expected.setup)
+    expect(script.lang).toBe(expected.lang)
+    expect(script.attrs).toEqual(expected.attrs)
+    expect(script.deps).toEqual([])
+
+    // Only the script, the template is compiled separately
+    for (const content of [script.content, expected.content]) {
+      expect(content).toContain('export default')
+      expect(content).toContain('__expose()')
+      expect(content).not.toContain('render')
+      expect(content).not.toContain('__scopeId')
+    }
+  })
+
+  test('should compile the blocks of the descriptor', () => {
+    const { descriptor } = compat.parse(SCRIPT_SFC, { filename: 'App.vue' })
+    descriptor.scriptSetup!.content = descriptor.scriptSetup!.content.replace('count', 'total')
+
+    const script = compat.compileScript(descriptor, { id: 'abc' })
+
+    expect(script.bindings.total).toBe('literal-const')
+    expect(script.bindings.count).toBeUndefined()
+  })
+
+  test('should map to the original lines', () => {
+    const { descriptor } = compat.parse(SCRIPT_SFC, { filename: 'App.vue' })
+    const script = compat.compileScript(descriptor, { id: 'abc', sourceMap: true })
+
+    // The first generated line is `import { ref } from 'vue'`, the 6th line of the SFC
+    expect(script.map!.mappings.startsWith('AAKA')).toBe(true)
+  })
+
+  test('should inline the template', () => {
+    const { descriptor } = compat.parse(SFC, { filename: 'App.vue' })
+    const script = compat.compileScript(descriptor, { id: 'abc', isProd: true, inlineTemplate: true })
+
+    // The render function is returned from `setup` and uses the bindings directly
+    expect(script.content).toContain('return (_ctx, _cache)=>')
+    expect(script.content).toContain('_toDisplayString(msg.value)')
+    expect(script.bindings.msg).toBe('setup-ref')
+  })
+
+  test('should bind the css vars', () => {
+    const { descriptor } = compat.parse(SFC, { filename: 'App.vue' })
+    const expected = official.compileScript(official.parse(SFC).descriptor, { id: 'abc' }).content
+    const script = compat.compileScript(descriptor, { id: 'abc' })
+
+    expect(expected).toContain('"abc-msg"')
+    expect(script.content).toContain('"abc-msg": (_unref(msg))')
+    expect(compat.compileStyle({ source: '.a { color: v-bind(msg) }', filename: 'App.vue', id: 'data-v-abc' }).code).toBe(
+      '.a{color:var(--abc-msg)}',
+    )
+
+    const normal = compat.parse(
+      `<script>\nexport default { data: () => ({ color: 'red' }) }\n</script>\n<style>.a { color: v-bind(color) }</style>`,
+      { filename: 'App.vue' },
+    ).descriptor
+    const normalScript = compat.compileScript(normal, { id: 'abc' })
+    expect(normalScript.content).toContain('"abc-color": (_ctx.color)')
+    expect(normalScript.content).toContain('export default __default__')
+  })
+
+  test('should throw without scripts', () => {
+    const { descriptor } = compat.parse('<template><div></div></template>', { filename: 'App.vue' })
+
+    expect(() => compat.compileScript(descriptor, { id: 'abc' })).toThrow()
+  })
+})
+
+describe('compileTemplate', () => {
+  test('should use the binding metadata', () => {
+    const result = compat.compileTemplate({
+      source: '<div>{{ msg }}</div>',
+      filename: 'App.vue',
+      id: 'abc',
+      compilerOptions: { bindingMetadata: { __isScriptSetup: true, msg: 'setup-ref' } },
+    })
+
+    expect(result.errors).toHaveLength(0)
+    expect(result.code).toContain('$setup.msg')
+  })
+
+  test('should report unsupported options', () => {
+    const result = compat.compileTemplate({ source: '<div></div>', filename: 'App.vue', id: 'abc', ssr: true })
+
+    expect(result.errors).toHaveLength(1)
+  })
+})
+
+describe('compileStyle', () => {
+  test('should scope the selectors', () => {
+    const options = { source: '.a { color: red }', filename: 'App.vue', id: 'data-v-abc', scoped: true }
+
+    expect(compat.compileStyle(options).code).toBe('.a[data-v-abc]{color:red}')
+    expect(official.compileStyle(options).code.replace(/\s/g, '')).toBe('.a[data-v-abc]{color:red}')
+  })
+
+  test('should report errors', async () => {
+    const result = await compat.compileStyleAsync({ source: '.a {', filename: 'App.vue', id: 'abc' })
+    expect(result.errors).toHaveLength(1)
+
+    const preprocessed = compat.compileStyle({ source: '', filename: 'App.vue', id: 'abc', preprocessLang: 'scss' })
+    expect(preprocessed.errors).toHaveLength(1)
+  })
+})
+
+test('rewriteDefault', () => {
+  for (const input of ['export default {}', 'const a = 1\nexport { a as default }', 'export const a = 1']) {
+    const expected = official.rewriteDefault(input, '_sfc_main')
+    expect(compat.rewriteDefault(input, '_sfc_main').replace(/\s/g, '')).toBe(expected.replace(/\s/g, ''))
+  }
+})
@@ -0,0 +1,48 @@
+import { test, expect } from 'vitest'
+
+import { Compiler, FervidCompileManyInput } from '../index'
+
+function input(source: string, filename: string): FervidCompileManyInput {
+  return {
+    source,
+    options: {
+      filename,
+      id: '',
+    },
+  }
+}
+
+const inputs = Array.from({ length: 16 }, (_, i) =>
+  input(`<template><div>component ${i}</div></template>`, `Component${i}.vue`),
+)
+
+test('should compile many in order', () => {
+  const results = new Compiler().compileManySync(inputs, { threads: 2 })
+
+  expect(results).toHaveLength(inputs.length)
+  results.forEach((result, i) => {
+    expect(result.errors).toHaveLength(0)
+    expect(result.code).toContain(`"component ${i}"`)
+    expect(result.dependencies).toEqual([])
+  })
+})
+
+test('should compile many asynchronously', async () => {
+  const results = (await new Compiler().compileManyAsync(inputs)) as ReturnType<Compiler['compileManySync']>
+
+  expect(results).toHaveLength(inputs.length)
+  results.forEach((result, i) => {
+    expect(result.code).toContain(`"component ${i}"`)
+  })
+})
+
+test('should report the errors per input', () => {
+  const results = new Compiler().compileManySync([
+    input(`<template><div>ok</div></template>`, 'Ok.vue'),
+    input(`<template><div>ok</div></template><template></template>`, 'Duplicate.vue'),
+  ])
+
+  expect(results[0].errors).toHaveLength(0)
+  expect(results[1].code).toContain('"ok"')
+  expect(results[1].errors.map((error) => error.code)).toEqual(['SFC_DUPLICATE_TEMPLATE'])
+})
@@ -0,0 +1,36 @@
+import { test, expect } from 'vitest'
+
+import { BindingTypes, Compiler } from '../index'
+
+const TEMPLATE = `<div @click="count++">{{ msg }} {{ count }}</div>`
+
+const bindingMetadata = {
+  count: BindingTypes.SETUP_REF,
+  msg: BindingTypes.PROPS,
+}
+
+test('should compile a render function module', () => {
+  const result = new Compiler().compileTemplate(TEMPLATE, {
+    filename: 'anonymous.vue',
+    bindingMetadata,
+  })
+
+  expect(result.errors).toHaveLength(0)
+  expect(result.code).toContain('export function render(_ctx, _cache, $props, $setup, $data, $options)')
+  expect(result.code).toContain('$setup.count++')
+  expect(result.code).toContain('$props.msg')
+  expect(result.imports).toContain('toDisplayString')
+})
+
+test('should compile an inline render function', () => {
+  const result = new Compiler().compileTemplate(TEMPLATE, {
+    filename: 'anonymous.vue',
+    bindingMetadata,
+    inline: true,
+  })
+
+  expect(result.errors).toHaveLength(0)
+  expect(result.code).toContain('count.value++')
+  expect(result.code).not.toContain('import')
+  expect(result.imports).toContain('createElementBlock')
+})
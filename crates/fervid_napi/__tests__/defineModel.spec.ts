@@ -372,7 +372,7 @@ describe('defineModel()', () => {
     )
     
     expect(errors.length).toBe(1)
-    expect(errors[0].message).toMatch('DuplicateDefineModelName')
+    expect(errors[0].code).toBe('DUPLICATE_DEFINE_MODEL_NAME')
   })
 
   test('error on duplicate model name: user', () => {
@@ -386,6 +386,6 @@ describe('defineModel()', () => {
     )
     
     expect(errors.length).toBe(1)
-    expect(errors[0].message).toMatch('DuplicateDefineModelName')
+    expect(errors[0].code).toBe('DUPLICATE_DEFINE_MODEL_NAME')
   })
 })
@@ -0,0 +1,62 @@
+import { test, expect } from 'vitest'
+
+import { Compiler } from '../index'
+
+const SFC = `<template lang="html">
+  <div>{{ msg }}</div>
+</template>
+
+<script setup lang="ts" generic="T">
+const msg = 'Hello'
+</script>
+
+<style scoped module="classes" src="./foo.css"></style>
+<style scoped>
+.a { color: red }
+</style>
+
+<i18n locale="en">{ "hello": "Hello" }</i18n>
+`
+
+test('should parse blocks with attributes and locations', () => {
+  const { descriptor, errors } = new Compiler().parse(SFC)
+
+  expect(errors).toHaveLength(0)
+  expect(descriptor.script).toBeUndefined()
+
+  const template = descriptor.template!
+  expect(template.type).toBe('template')
+  expect(template.content).toBe('\n  <div>{{ msg }}</div>\n')
+  expect(template.lang).toBe('html')
+  expect(template.loc.start).toEqual({ offset: 22, line: 1, column: 23 })
+  expect(SFC.slice(template.loc.start.offset, template.loc.end.offset)).toBe(template.content)
+
+  const scriptSetup = descriptor.scriptSetup!
+  expect(scriptSetup.content).toBe("\nconst msg = 'Hello'\n")
+  expect(scriptSetup.setup).toBe(true)
+  expect(scriptSetup.attrs).toEqual({ setup: true, lang: 'ts', generic: 'T' })
+
+  // Empty styles are skipped by default
+  expect(descriptor.styles).toHaveLength(1)
+  expect(descriptor.styles[0].scoped).toBe(true)
+  expect(descriptor.styles[0].module).toBeUndefined()
+
+  expect(descriptor.customBlocks).toHaveLength(1)
+  expect(descriptor.customBlocks[0].type).toBe('i18n')
+  expect(descriptor.customBlocks[0].attrs).toEqual({ locale: 'en' })
+})
+
+test('should keep empty blocks', () => {
+  const { descriptor } = new Compiler().parse(SFC, { ignoreEmpty: false })
+
+  expect(descriptor.styles).toHaveLength(2)
+  expect(descriptor.styles[0].content).toBe('')
+  expect(descriptor.styles[0].src).toBe('./foo.css')
+  expect(descriptor.styles[0].module).toBe('classes')
+})
+
+test('should report errors', () => {
+  const { errors } = new Compiler().parse('<template><div></div></template><template></template>')
+
+  expect(errors.map((error) => error.code)).toEqual(['SFC_DUPLICATE_TEMPLATE'])
+})
@@ -0,0 +1,176 @@
+// Types of the `@vue/compiler-sfc` compatible facade, see `compat.js`.
+// Only the options and the fields supported by fervid are listed.
+
+import type { SourceLocation as FervidSourceLocation, SourcePosition } from './index'
+
+export declare const version: string
+
+export interface SourceLocation extends FervidSourceLocation {
+  source: string
+}
+
+export interface CompilerError extends SyntaxError {
+  code: string
+  loc?: SourceLocation
+}
+
+export type BindingTypes =
+  | 'data'
+  | 'props'
+  | 'props-aliased'
+  | 'setup-let'
+  | 'setup-const'
+  | 'setup-reactive-const'
+  | 'setup-maybe-ref'
+  | 'setup-ref'
+  | 'options'
+  | 'literal-const'
+
+export type BindingMetadata = {
+  [key: string]: BindingTypes | undefined
+} & {
+  __isScriptSetup?: boolean
+}
+
+export interface SFCBlock {
+  type: string
+  content: string
+  attrs: Record<string, string | true>
+  loc: SourceLocation
+  lang?: string
+  src?: string
+}
+
+export interface SFCTemplateBlock extends SFCBlock {
+  type: 'template'
+}
+
+export interface SFCScriptBlock extends SFCBlock {
+  type: 'script'
+  setup?: string | boolean
+  bindings?: BindingMetadata
+  deps?: string[]
+  warnings?: string[]
+}
+
+export interface SFCStyleBlock extends SFCBlock {
+  type: 'style'
+  scoped?: boolean
+  module?: string | boolean
+}
+
+export interface SFCDescriptor {
+  filename: string
+  source: string
+  template: SFCTemplateBlock | null
+  script: SFCScriptBlock | null
+  scriptSetup: SFCScriptBlock | null
+  styles: SFCStyleBlock[]
+  customBlocks: SFCBlock[]
+  cssVars: string[]
+  slotted: boolean
+  shouldForceReload: (prevImports: Record<string, unknown>) => boolean
+}
+
+export interface SFCParseOptions {
+  filename?: string
+  ignoreEmpty?: boolean
+  templateParseOptions?: {
+    delimiters?: [string, string]
+  }
+}
+
+export interface SFCParseResult {
+  descriptor: SFCDescriptor
+  errors: CompilerError[]
+}
+
+export declare function parse(source: string, options?: SFCParseOptions): SFCParseResult
+
+export interface TemplateCompilerOptions {
+  delimiters?: [string, string]
+  whitespace?: 'preserve' | 'condense'
+  comments?: boolean
+  /** Functions are not supported, only the matchers of fervid */
+  isCustomElement?: { prefixes?: string[]; patterns?: string[] }
+  bindingMetadata?: BindingMetadata
+}
+
+export interface SFCScriptCompileOptions {
+  id: string
+  isProd?: boolean
+  sourceMap?: boolean
+  genDefaultAs?: string
+  propsDestructure?: boolean
+  customElement?: boolean | ((filename: string) => boolean)
+  /** Compile the template together with `<script setup>` and inline it into `setup` */
+  inlineTemplate?: boolean
+  /** Only `compilerOptions` are used, and only with `inlineTemplate` */
+  templateOptions?: { compilerOptions?: TemplateCompilerOptions }
+}
+
+/**
+ * Compiles the `<script>` blocks of the descriptor, the template is compiled with `compileTemplate`
+ * unless it is inlined. Throws on the first error, same as the official implementation.
+ * `v-bind()` in the styles only supports identifiers and member expressions, e.g. `theme.color`.
+ */
+export declare function compileScript(sfc: SFCDescriptor, options: SFCScriptCompileOptions): SFCScriptBlock
+
+export interface SFCTemplateCompileOptions {
+  source: string
+  filename: string
+  id: string
+  isProd?: boolean
+  /** Not supported, reported as an error */
+  ssr?: boolean
+  /** Only `html` is supported */
+  preprocessLang?: string
+  compilerOptions?: TemplateCompilerOptions
+}
+
+export interface SFCTemplateCompileResults {
+  code: string
+  ast?: undefined
+  preamble?: undefined
+  source: string
+  tips: string[]
+  errors: (string | CompilerError)[]
+  map?: object
+}
+
+export declare function compileTemplate(options: SFCTemplateCompileOptions): SFCTemplateCompileResults
+
+export interface SFCStyleCompileOptions {
+  source: string
+  filename: string
+  id: string
+  /** Ignored, `v-bind()` is always named as in development */
+  isProd?: boolean
+  scoped?: boolean
+  /** Only `css` is supported, preprocessors must be run beforehand */
+  preprocessLang?: string
+  /** Not supported, reported as an error */
+  modules?: boolean
+  map?: object
+}
+
+export interface SFCStyleCompileResults {
+  code: string
+  map?: object
+  rawResult?: undefined
+  errors: Error[]
+  modules?: undefined
+  dependencies: Set<string>
+}
+
+export declare function compileStyle(options: SFCStyleCompileOptions): SFCStyleCompileResults
+export declare function compileStyleAsync(options: SFCStyleCompileOptions): Promise<SFCStyleCompileResults>
+
+export declare function rewriteDefault(input: string, as: string, parserPlugins?: string[]): string
+
+/** No-op, types are resolved by fervid */
+export declare function registerTS(ts: () => unknown): void
+/** No-op, types are resolved by fervid */
+export declare function invalidateTypeCache(filename: string): void
+
+export type { SourcePosition }
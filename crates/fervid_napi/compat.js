@@ -0,0 +1,351 @@
+// A facade with the API of `@vue/compiler-sfc`, so that the tooling built for it
+// (e.g. `@vitejs/plugin-vue` or `vue-loader`) can use fervid via an alias:
+// `alias: { '@vue/compiler-sfc': '@fervid/napi/compat' }`
+
+const { Compiler, BindingTypes } = require('./index')
+
+const version = require('./package.json').version
+
+// Official binding types by the ones of fervid
+const OFFICIAL_BINDING_TYPES = {
+  [BindingTypes.DATA]: 'data',
+  [BindingTypes.PROPS]: 'props',
+  [BindingTypes.PROPS_ALIASED]: 'props-aliased',
+  [BindingTypes.SETUP_LET]: 'setup-let',
+  [BindingTypes.SETUP_CONST]: 'setup-const',
+  [BindingTypes.SETUP_REACTIVE_CONST]: 'setup-reactive-const',
+  [BindingTypes.SETUP_MAYBE_REF]: 'setup-maybe-ref',
+  [BindingTypes.SETUP_REF]: 'setup-ref',
+  [BindingTypes.OPTIONS]: 'options',
+  [BindingTypes.LITERAL_CONST]: 'literal-const',
+  // Introduced by fervid
+  [BindingTypes.COMPONENT]: 'setup-const',
+  [BindingTypes.IMPORTED]: 'setup-maybe-ref',
+}
+
+const FERVID_BINDING_TYPES = {
+  data: BindingTypes.DATA,
+  props: BindingTypes.PROPS,
+  'props-aliased': BindingTypes.PROPS_ALIASED,
+  'setup-let': BindingTypes.SETUP_LET,
+  'setup-const': BindingTypes.SETUP_CONST,
+  'setup-reactive-const': BindingTypes.SETUP_REACTIVE_CONST,
+  'setup-maybe-ref': BindingTypes.SETUP_MAYBE_REF,
+  'setup-ref': BindingTypes.SETUP_REF,
+  options: BindingTypes.OPTIONS,
+  'literal-const': BindingTypes.LITERAL_CONST,
+}
+
+const CSS_VARS_RE = /v-bind\s*\(\s*(?:'([^']+)'|"([^"]+)"|([^'"][^)]*))\s*\)/g
+const CSS_VAR_NAME_ESCAPE_RE = /[ !"#$%&'()*+,./:;<=>?@[\\\]^`{|}~]/g
+const SLOTTED_RE = /(?:::v-|:)slotted\(/
+
+function parse(source, options = {}) {
+  const { filename = 'anonymous.vue', ignoreEmpty = true, templateParseOptions = {} } = options
+
+  const compiler = new Compiler({
+    template: { delimiters: templateParseOptions.delimiters },
+  })
+  const { descriptor, errors } = compiler.parse(source, { ignoreEmpty })
+
+  const block = (raw) => {
+    if (!raw) {
+      return null
+    }
+
+    const result = {
+      type: raw.type,
+      content: raw.content,
+      attrs: raw.attrs,
+      loc: { ...raw.loc, source: raw.content },
+    }
+    for (const key of ['lang', 'src', 'setup', 'scoped', 'module']) {
+      if (raw[key] !== undefined && raw[key] !== null) {
+        result[key] = raw[key]
+      }
+    }
+    return result
+  }
+
+  const styles = descriptor.styles.map(block)
+
+  return {
+    descriptor: {
+      filename,
+      source,
+      template: block(descriptor.template),
+      script: block(descriptor.script),
+      scriptSetup: block(descriptor.scriptSetup),
+      styles,
+      customBlocks: descriptor.customBlocks.map(block),
+      cssVars: parseCssVars(styles),
+      slotted: styles.some((style) => style.scoped && SLOTTED_RE.test(style.content)),
+      shouldForceReload: () => false,
+    },
+    errors: errors.map(toCompilerError),
+  }
+}
+
+function compileScript(sfc, options) {
+  const block = sfc.scriptSetup || sfc.script
+  if (!block) {
+    throw new Error(`[@vue/compiler-sfc] SFC contains no <script> tags.`)
+  }
+
+  // Same as the official implementation, the template is only inlined into `<script setup>`.
+  // Otherwise it is up to the caller, and so are the styles
+  const inlineTemplate = !!(options.inlineTemplate && sfc.scriptSetup && sfc.template)
+
+  // `v-bind()` in the styles, `<script setup>` binds them in `setup` itself
+  // and the default export of `<script>` is wrapped after the compilation
+  const cssVarsTarget = sfc.cssVars.length && !sfc.scriptSetup ? options.genDefaultAs || '__default__' : undefined
+  const scriptSetupPrefix = sfc.cssVars.length && sfc.scriptSetup ? genScriptSetupCssVars(sfc.cssVars, options.id) : ''
+
+  const compiler = new Compiler({
+    isProduction: options.isProd,
+    sourceMap: options.sourceMap !== false,
+    template: inlineTemplate ? toTemplateOptions((options.templateOptions || {}).compilerOptions) : undefined,
+  })
+
+  const isCustomElement =
+    typeof options.customElement === 'function' ? options.customElement(sfc.filename) : !!options.customElement
+
+  const result = compiler.compileSync(blocksSource(sfc, inlineTemplate, scriptSetupPrefix), {
+    filename: sfc.filename,
+    id: options.id,
+    genDefaultAs: cssVarsTarget || options.genDefaultAs,
+    propsDestructure: options.propsDestructure,
+    isCustomElement,
+    outputSetupBindings: true,
+  })
+
+  const error = result.errors.find((e) => e.severity === 'error')
+  if (error) {
+    throw toCompilerError(error)
+  }
+
+  const bindings = sfc.scriptSetup ? { __isScriptSetup: true } : {}
+  for (const [name, bindingType] of Object.entries(result.setupBindings || {})) {
+    const official = OFFICIAL_BINDING_TYPES[bindingType]
+    if (official) {
+      bindings[name] = official
+    }
+  }
+
+  let content = result.code
+  if (cssVarsTarget) {
+    content += genNormalScriptCssVars(sfc.cssVars, options.id, cssVarsTarget, !options.genDefaultAs)
+  }
+
+  return {
+    ...block,
+    content,
+    bindings,
+    map: result.sourceMap ? JSON.parse(result.sourceMap) : undefined,
+    deps: result.dependencies,
+    warnings: result.errors.map((e) => e.message),
+  }
+}
+
+function compileTemplate(options) {
+  const { source, filename, compilerOptions = {}, ssr, preprocessLang } = options
+  const errors = []
+  const tips = []
+
+  if (ssr) {
+    errors.push(`[@fervid/napi] SSR templates are not supported`)
+  }
+  if (preprocessLang && preprocessLang !== 'html') {
+    errors.push(`[@fervid/napi] template preprocessing with "${preprocessLang}" is not supported`)
+  }
+  if (typeof compilerOptions.isCustomElement === 'function') {
+    tips.push(`[@fervid/napi] isCustomElement functions are not supported, use { prefixes, patterns } instead`)
+  }
+
+  const bindingMetadata = {}
+  for (const [name, bindingType] of Object.entries(compilerOptions.bindingMetadata || {})) {
+    // e.g. `__isScriptSetup: true`
+    if (typeof bindingType !== 'string') {
+      continue
+    }
+    const fervid = FERVID_BINDING_TYPES[bindingType]
+    if (fervid !== undefined) {
+      bindingMetadata[name] = fervid
+    }
+  }
+
+  const compiler = new Compiler({
+    isProduction: options.isProd,
+    sourceMap: true,
+    template: toTemplateOptions(compilerOptions),
+  })
+  const result = compiler.compileTemplate(source, { filename, bindingMetadata })
+
+  return {
+    code: result.code,
+    ast: undefined,
+    preamble: undefined,
+    source,
+    tips,
+    errors: errors.concat(result.errors.filter((e) => e.severity === 'error').map(toCompilerError)),
+    map: result.sourceMap ? JSON.parse(result.sourceMap) : undefined,
+  }
+}
+
+function compileStyle(options) {
+  const { source, filename, id, scoped = false, preprocessLang, modules, map } = options
+
+  if (preprocessLang && preprocessLang !== 'css') {
+    return styleError(`[@fervid/napi] style preprocessing with "${preprocessLang}" is not supported`)
+  }
+  if (modules) {
+    return styleError(`[@fervid/napi] CSS modules are not supported`)
+  }
+
+  // `v-bind()` becomes the variable bound by `compileScript`
+  const shortId = id.replace(/^data-v-/, '')
+  const rewritten = source.replace(CSS_VARS_RE, (_, ...groups) => `var(--${cssVarName(shortId, cssVarSource(groups))})`)
+
+  const compiler = new Compiler({ sourceMap: !!map })
+  const result = compiler.compileStyle(rewritten, { filename, id, scoped })
+
+  return {
+    code: result.code,
+    map: result.sourceMap ? JSON.parse(result.sourceMap) : undefined,
+    rawResult: undefined,
+    errors: result.errors.map(toCompilerError),
+    modules: undefined,
+    dependencies: new Set(),
+  }
+}
+
+function compileStyleAsync(options) {
+  return Promise.resolve(compileStyle(options))
+}
+
+function rewriteDefault(input, as, parserPlugins = []) {
+  return new Compiler().rewriteDefault(input, as, parserPlugins.includes('typescript'))
+}
+
+// Type resolution is done by fervid itself, these are accepted for compatibility
+function registerTS() {}
+function invalidateTypeCache() {}
+
+// An SFC with only the `<script>` blocks of the descriptor, and its `<template>` when it is inlined.
+// Each block starts on its original line, so that the locations in the source map and the errors match.
+// `scriptSetupPrefix` is put on the line of the `<script setup>` tag
+function blocksSource(sfc, withTemplate, scriptSetupPrefix) {
+  const blocks = [sfc.script, sfc.scriptSetup, withTemplate && sfc.template].filter(Boolean)
+  blocks.sort((a, b) => a.loc.start.offset - b.loc.start.offset)
+
+  let source = ''
+  let line = 1
+  for (const block of blocks) {
+    const tag = block.type
+    const padding = Math.max(block.loc.start.line - line, 0)
+    const attrs = Object.entries(block.attrs)
+      .map(([name, value]) => (value === true ? ` ${name}` : ` ${name}="${value.replace(/"/g, '&quot;')}"`))
+      .join('')
+    const prefix = block === sfc.scriptSetup ? scriptSetupPrefix : ''
+    source += `${'\n'.repeat(padding)}<${tag}${attrs}>${prefix}${block.content}</${tag}>\n`
+    line += padding + block.content.split('\n').length
+  }
+  return source
+}
+
+// Same as `useCssVars` generated by the official implementation, the bindings are used directly in `setup`
+function genScriptSetupCssVars(vars, id) {
+  const entries = vars.map((v) => `"${cssVarName(id, v, true)}": (${cssVarExpression(v, (root) => `_unref(${root})`)})`)
+  return `import { useCssVars as _useCssVars, unref as _unref } from 'vue';_useCssVars(_ctx => ({ ${entries.join(', ')} }));`
+}
+
+// Same as `useCssVars` generated by the official implementation, the component instance is used in `setup`
+function genNormalScriptCssVars(vars, id, target, isDefaultExport) {
+  const entries = vars.map((v) => `"${cssVarName(id, v, true)}": (${cssVarExpression(v, (root) => `_ctx.${root}`)})`)
+  return (
+    `\nimport { useCssVars as _useCssVars } from 'vue'\n` +
+    `const __injectCSSVars__ = () => {\n_useCssVars(_ctx => ({ ${entries.join(', ')} }))}\n` +
+    `const __setup__ = ${target}.setup\n` +
+    `${target}.setup = __setup__\n` +
+    `  ? (props, ctx) => { __injectCSSVars__();return __setup__(props, ctx) }\n` +
+    `  : __injectCSSVars__\n` +
+    (isDefaultExport ? `export default ${target}\n` : '')
+  )
+}
+
+// Only identifiers and member expressions are supported in `v-bind()`, e.g. `color` or `theme.color`
+function cssVarExpression(source, bindRoot) {
+  const match = /^([A-Za-z_$][\w$]*)((?:\s*\.\s*[A-Za-z_$][\w$]*)*)$/.exec(source)
+  if (!match) {
+    throw new Error(`[@fervid/napi] v-bind() in <style> only supports identifiers and member expressions, got "${source}"`)
+  }
+  return bindRoot(match[1]) + match[2]
+}
+
+// The development naming of the official implementation, also in production
+function cssVarName(id, source, isJsString = false) {
+  const escaped = source.replace(CSS_VAR_NAME_ESCAPE_RE, (s) =>
+    isJsString ? (s === '"' ? '\\\\\\"' : `\\\\${s}`) : `\\${s}`,
+  )
+  return `${id}-${escaped}`
+}
+
+function cssVarSource(groups) {
+  return (groups[0] || groups[1] || groups[2]).trim()
+}
+
+function toTemplateOptions(compilerOptions = {}) {
+  const { delimiters, whitespace, comments, isCustomElement } = compilerOptions
+  return {
+    delimiters,
+    whitespace,
+    comments,
+    isCustomElement: isCustomElement && typeof isCustomElement === 'object' ? isCustomElement : undefined,
+  }
+}
+
+function toCompilerError(error) {
+  const compilerError = new SyntaxError(error.message)
+  compilerError.code = error.code
+  compilerError.loc = {
+    start: { offset: Math.max(error.lo - 1, 0), line: error.startLine, column: error.startColumn },
+    end: { offset: Math.max(error.hi - 1, 0), line: error.endLine, column: error.endColumn },
+  }
+  return compilerError
+}
+
+function styleError(message) {
+  return {
+    code: '',
+    map: undefined,
+    rawResult: undefined,
+    errors: [new Error(message)],
+    modules: undefined,
+    dependencies: new Set(),
+  }
+}
+
+function parseCssVars(styles) {
+  const vars = []
+  for (const style of styles) {
+    for (const match of style.content.matchAll(CSS_VARS_RE)) {
+      const variable = cssVarSource(match.slice(1))
+      if (!vars.includes(variable)) {
+        vars.push(variable)
+      }
+    }
+  }
+  return vars
+}
+
+module.exports = {
+  version,
+  parse,
+  compileScript,
+  compileTemplate,
+  compileStyle,
+  compileStyleAsync,
+  rewriteDefault,
+  registerTS,
+  invalidateTypeCache,
+}
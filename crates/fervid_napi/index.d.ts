@@ -34,7 +34,26 @@ export interface FervidJsCompilerOptions {
    */
   customElement?: undefined
 }
-export interface FervidJsCompilerOptionsTemplate {}
+export interface FervidJsCompilerOptionsTemplate {
+  /**
+   * Interpolation delimiters, e.g. `['${', '}']`. Default: `['{{', '}}']`.
+   * A `delimiters` option of the component takes precedence.
+   */
+  delimiters?: [string, string]
+  /** How whitespace in the template is handled. Default: `'condense'` */
+  whitespace?: 'condense' | 'preserve'
+  /** Tags which are native custom elements (e.g. `<ion-button>`) and must not be resolved as components */
+  isCustomElement?: FervidJsTagMatcher
+  /** Keep the HTML comments of the template. Default: `true` in development, `false` in production */
+  comments?: boolean
+}
+/** Matches a tag when it starts with any of `prefixes` or matches any of `patterns` */
+export interface FervidJsTagMatcher {
+  /** Tag prefixes, e.g. `['ion-']` */
+  prefixes?: Array<string>
+  /** Regular expressions in Rust syntax, e.g. `['^model-']` */
+  patterns?: Array<string>
+}
 export interface FervidJsCompilerOptionsScript {
   /**
    * Ignored
@@ -67,6 +86,46 @@ export interface FervidCompileOptions {
   propsDestructure?: boolean | 'error'
   /** Whether setup bindings need to be serialized */
   outputSetupBindings?: boolean
+  /**
+   * JSON-serialized source map of the input, e.g. when the SFC was preprocessed by another loader.
+   * The resulting source map will point to the original file.
+   */
+  inSourceMap?: string
+}
+export interface FervidCompileTemplateOptions {
+  /** Filename used in the source map */
+  filename: string
+  /**
+   * Bindings which the template refers to, e.g. `setupBindings` of `compileSync`.
+   * Same as `bindingMetadata` in `@vue/compiler-sfc`
+   */
+  bindingMetadata?: Record<string, BindingTypes>
+  /**
+   * Generate a render arrow function to be returned from `setup` with the bindings accessed directly,
+   * instead of a module exporting `render`. Default: false
+   */
+  inline?: boolean
+}
+export interface FervidCompileStyleOptions {
+  /** Filename used in the source map */
+  filename: string
+  /** Scope ID, with or without the `data-v-` prefix */
+  id: string
+  /** Scope the selectors with `id`, same as `<style scoped>`. Default: false */
+  scoped?: boolean
+}
+/** A single SFC for `compileManySync` and `compileManyAsync` */
+export interface FervidCompileManyInput {
+  source: string
+  options: FervidCompileOptions
+}
+export interface FervidCompileManyOptions {
+  /** Number of threads to compile with. Default: a thread per CPU */
+  threads?: number
+}
+export interface FervidParseOptions {
+  /** Skip the blocks with empty or whitespace-only content. Default: true */
+  ignoreEmpty?: boolean
 }
 export interface CompileResult {
   code: string
@@ -75,23 +134,108 @@ export interface CompileResult {
   customBlocks: Array<CustomBlock>
   sourceMap?: string
   setupBindings?: Record<string, BindingTypes> | undefined
+  /**
+   * Files the compilation depended on, e.g. the ones the types of `defineProps` were imported from.
+   * Bundler plugins should watch them (e.g. using `addWatchFile`) and compile the SFC again on change
+   */
+  dependencies: Array<string>
+  /**
+   * Imports of the `styles` and `customBlocks` to put in front of `code`, one per line.
+   * The imported ids are `filename` followed by the `query` of the block.
+   * CSS modules are imported as `style{index}`, they belong in `__cssModules` of the component by their names
+   */
+  prelude: string
+}
+export interface CompileTemplateResult {
+  code: string
+  /**
+   * Helpers imported from `vue`, e.g. `createElementBlock`.
+   * In the code they are used with an underscore, e.g. `_createElementBlock`
+   */
+  imports: Array<string>
+  errors: Array<SerializedError>
+  sourceMap?: string
+}
+export interface CompileStyleResult {
+  /** Transformed CSS, empty when it could not be parsed */
+  code: string
+  errors: Array<SerializedError>
+  sourceMap?: string
+}
+export interface ParseResult {
+  descriptor: SfcDescriptor
+  errors: Array<SerializedError>
+}
+/** Blocks of an SFC, same as `SFCDescriptor` of `@vue/compiler-sfc` without the ASTs */
+export interface SfcDescriptor {
+  template?: SfcBlock
+  script?: SfcBlock
+  scriptSetup?: SfcBlock
+  styles: Array<SfcBlock>
+  customBlocks: Array<SfcBlock>
+}
+export interface SfcBlock {
+  /** Tag name of the block, e.g. `template`, `script`, `style` or `i18n` */
+  type: string
+  /** Raw content between the starting and the ending tags */
+  content: string
+  /** Attributes as written, attributes without a value (e.g. `scoped`) are `true` */
+  attrs: Record<string, string | true>
+  /** Location of `content` */
+  loc: SourceLocation
+  lang?: string
+  src?: string
+  setup?: boolean
+  scoped?: boolean
+  module?: string | true
+}
+export interface SourceLocation {
+  start: SourcePosition
+  end: SourcePosition
+}
+export interface SourcePosition {
+  /** 0-based byte offset */
+  offset: number
+  /** 1-based line */
+  line: number
+  /** 1-based column */
+  column: number
 }
 export interface Style {
   code: string
   isCompiled: boolean
   lang: string
   isScoped: boolean
+  /** Source map pointing into the SFC, only present for compiled styles */
+  sourceMap?: string
+  /** Query of the style module in `prelude`, e.g. `?vue&type=style&index=0&lang.css` */
+  query: string
 }
 export interface CustomBlock {
   content: string
   lo: number
   hi: number
   tagName: string
+  /** Query of the block module in `prelude`, e.g. `?vue&type=i18n&index=0&lang.json` */
+  query: string
 }
 export interface SerializedError {
   lo: number
   hi: number
   message: string
+  /** Stable error code, e.g. `X_INVALID_END_TAG` */
+  code: string
+  severity: 'error' | 'warning'
+  /** 1-based line of `lo` */
+  startLine: number
+  /** 1-based column of `lo` */
+  startColumn: number
+  /** 1-based line of `hi` */
+  endLine: number
+  /** 1-based column of `hi` */
+  endColumn: number
+  /** Source code excerpt with the error location underlined */
+  frame?: string
 }
 /**
  * This is a copied enum from `fervid_core` with `napi` implementation to avoid littering the core crate.
@@ -147,4 +291,24 @@ export declare class Compiler {
   constructor(options?: FervidJsCompilerOptions | undefined | null)
   compileSync(source: string, options: FervidCompileOptions): CompileResult
   compileAsync(source: string, options: FervidCompileOptions, signal?: AbortSignal | undefined | null): Promise<unknown>
+  /**
+   * Compiles many SFCs in parallel, crossing the JS boundary once.
+   * A fatal error of one SFC is reported in its `errors` and does not affect the others
+   */
+  compileManySync(inputs: Array<FervidCompileManyInput>, options?: FervidCompileManyOptions | undefined | null): Array<CompileResult>
+  compileManyAsync(inputs: Array<FervidCompileManyInput>, options?: FervidCompileManyOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<unknown>
+  /** Compiles a template without the SFC around it, e.g. for template-only HMR updates */
+  compileTemplate(source: string, options: FervidCompileTemplateOptions): CompileTemplateResult
+  /**
+   * Compiles the content of a `<style>` block, e.g. when the bundler handles the styles separately.
+   * Only plain CSS is supported, preprocessors must be run beforehand
+   */
+  compileStyle(source: string, options: FervidCompileStyleOptions): CompileStyleResult
+  /** Rewrites `export default` of a script to a `const` named `as_name`, same as `rewriteDefault` of `@vue/compiler-sfc` */
+  rewriteDefault(input: string, asName: string, isTs?: boolean | undefined | null): string
+  /**
+   * Splits an SFC into its blocks without compiling it, similar to `parse` of `@vue/compiler-sfc`.
+   * Meant for tooling which needs the raw content, attributes and locations of the blocks
+   */
+  parse(source: string, options?: FervidParseOptions | undefined | null): ParseResult
 }
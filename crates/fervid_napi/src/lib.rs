@@ -10,9 +10,18 @@ use fervid_transform::PropsDestructureConfig;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
-use fervid::{compile, CompileOptions};
+use fervid::{
+    compile, compile_many, compile_style, compile_template, errors::CompileError, parse,
+    rewrite_default, CompileManyInput, CompileManyOptions, CompileOptions, CompileStyleOptions,
+    CompileTemplateOptions, ParseOptions, SetupBinding, TagPredicate, TemplateGenerationMode,
+    WhitespaceMode,
+};
 use structs::{
-    BindingTypes, CompileResult, FervidCompileOptions, FervidJsCompiler, FervidJsCompilerOptions,
+    BindingTypes, CompileResult, CompileStyleResult, CompileTemplateResult, CustomBlock,
+    FervidCompileManyInput, FervidCompileManyOptions, FervidCompileOptions,
+    FervidCompileStyleOptions, FervidCompileTemplateOptions, FervidJsCompiler,
+    FervidJsCompilerOptions, FervidParseOptions, ParseResult, SerializedError, SfcDescriptor,
+    Style,
 };
 
 mod structs;
@@ -20,9 +29,28 @@ mod structs;
 #[napi]
 impl FervidJsCompiler {
     #[napi(constructor)]
-    pub fn new(options: Option<FervidJsCompilerOptions>) -> Self {
-        let options = options.unwrap_or_else(Default::default);
-        FervidJsCompiler { options }
+    pub fn new(options: Option<FervidJsCompilerOptions>) -> Result<Self> {
+        let options = options.unwrap_or_default();
+
+        let is_custom_element_tag = match options
+            .template
+            .as_ref()
+            .and_then(|template| template.is_custom_element.as_ref())
+        {
+            Some(matcher) => Some(
+                TagPredicate::from_prefixes_and_patterns(
+                    matcher.prefixes.to_owned().unwrap_or_default(),
+                    matcher.patterns.as_deref().unwrap_or_default(),
+                )
+                .map_err(|e| Error::from_reason(e.to_string()))?,
+            ),
+            None => None,
+        };
+
+        Ok(FervidJsCompiler {
+            options,
+            is_custom_element_tag,
+        })
     }
 
     #[napi]
@@ -33,7 +61,7 @@ impl FervidJsCompiler {
         options: FervidCompileOptions,
     ) -> Result<CompileResult> {
         let compiled = compile_impl(self, &source, &options)?;
-        Ok(convert(env, compiled, &options))
+        Ok(convert(env, compiled, &source, &options))
     }
 
     #[napi]
@@ -50,6 +78,160 @@ impl FervidJsCompiler {
         };
         AsyncTask::with_optional_signal(task, signal)
     }
+
+    /// Compiles many SFCs in parallel, crossing the JS boundary once.
+    /// A fatal error of one SFC is reported in its `errors` and does not affect the others
+    #[napi]
+    pub fn compile_many_sync(
+        &self,
+        env: Env,
+        inputs: Vec<FervidCompileManyInput>,
+        options: Option<FervidCompileManyOptions>,
+    ) -> Result<Vec<CompileResult>> {
+        let results = compile_many_impl(self, &inputs, options.as_ref())?;
+        Ok(convert_many(env, results, &inputs))
+    }
+
+    #[napi]
+    pub fn compile_many_async(
+        &self,
+        inputs: Vec<FervidCompileManyInput>,
+        options: Option<FervidCompileManyOptions>,
+        signal: Option<AbortSignal>,
+    ) -> AsyncTask<CompileManyTask> {
+        let task = CompileManyTask {
+            compiler: self.to_owned(),
+            inputs,
+            options,
+        };
+        AsyncTask::with_optional_signal(task, signal)
+    }
+
+    /// Compiles a template without the SFC around it, e.g. for template-only HMR updates
+    #[napi]
+    pub fn compile_template(
+        &self,
+        source: String,
+        options: FervidCompileTemplateOptions,
+    ) -> Result<CompileTemplateResult> {
+        let binding_metadata = options
+            .binding_metadata
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, binding_type)| SetupBinding(name.into(), binding_type.into()))
+            .collect();
+
+        let generation_mode = if matches!(options.inline, Some(true)) {
+            TemplateGenerationMode::Inline
+        } else {
+            TemplateGenerationMode::RenderFn
+        };
+
+        let compile_options = CompileTemplateOptions {
+            filename: Cow::Borrowed(&options.filename),
+            is_prod: self.options.is_production,
+            binding_metadata,
+            generation_mode: Some(generation_mode),
+            delimiters: template_delimiters(self)?,
+            whitespace: template_whitespace(self)?,
+            is_custom_element_tag: self.is_custom_element_tag.to_owned(),
+            comments: self
+                .options
+                .template
+                .as_ref()
+                .and_then(|template| template.comments),
+            source_map: self.options.source_map,
+            parse_mode: None,
+        };
+
+        let result = compile_template(&source, compile_options)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+
+        Ok(CompileTemplateResult {
+            code: result.code,
+            imports: result.imports,
+            errors: result
+                .errors
+                .into_iter()
+                .map(|e| SerializedError::from_compile_error(e, &source))
+                .collect(),
+            source_map: result.source_map,
+        })
+    }
+
+    /// Compiles the content of a `<style>` block, e.g. when the bundler handles the styles separately.
+    /// Only plain CSS is supported, preprocessors must be run beforehand
+    #[napi]
+    pub fn compile_style(
+        &self,
+        source: String,
+        options: FervidCompileStyleOptions,
+    ) -> CompileStyleResult {
+        let compile_options = CompileStyleOptions {
+            filename: Cow::Borrowed(&options.filename),
+            id: Cow::Borrowed(&options.id),
+            scoped: options.scoped.unwrap_or(false),
+            source_map: self.options.source_map,
+        };
+
+        let result = compile_style(&source, compile_options);
+
+        CompileStyleResult {
+            code: result.code,
+            errors: result
+                .errors
+                .into_iter()
+                .map(|e| SerializedError::from_compile_error(e, &source))
+                .collect(),
+            source_map: result.source_map,
+        }
+    }
+
+    /// Rewrites `export default` of a script to a `const` named `as_name`, same as `rewriteDefault` of `@vue/compiler-sfc`
+    #[napi]
+    pub fn rewrite_default(
+        &self,
+        input: String,
+        as_name: String,
+        is_ts: Option<bool>,
+    ) -> Result<String> {
+        rewrite_default(&input, &as_name, is_ts.unwrap_or(false))
+            .map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Splits an SFC into its blocks without compiling it, similar to `parse` of `@vue/compiler-sfc`.
+    /// Meant for tooling which needs the raw content, attributes and locations of the blocks
+    #[napi]
+    pub fn parse(
+        &self,
+        source: String,
+        options: Option<FervidParseOptions>,
+    ) -> Result<ParseResult> {
+        let options = options.unwrap_or_default();
+        let parse_options = ParseOptions {
+            delimiters: template_delimiters(self)?,
+            parse_mode: None,
+            ignore_empty: options.ignore_empty,
+        };
+
+        let parsed =
+            parse(&source, parse_options).map_err(|e| Error::from_reason(e.to_string()))?;
+
+        Ok(ParseResult {
+            descriptor: SfcDescriptor {
+                template: parsed.template.map(From::from),
+                script: parsed.script.map(From::from),
+                script_setup: parsed.script_setup.map(From::from),
+                styles: parsed.styles.into_iter().map(From::from).collect(),
+                custom_blocks: parsed.custom_blocks.into_iter().map(From::from).collect(),
+            },
+            errors: parsed
+                .errors
+                .into_iter()
+                .map(|e| SerializedError::from_compile_error(e, &source))
+                .collect(),
+        })
+    }
 }
 
 fn compile_impl(
@@ -57,6 +239,39 @@ fn compile_impl(
     source: &str,
     options: &FervidCompileOptions,
 ) -> Result<fervid::CompileResult> {
+    let compile_options = compile_options(compiler, options)?;
+    compile(source, compile_options).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+fn compile_many_impl(
+    compiler: &FervidJsCompiler,
+    inputs: &[FervidCompileManyInput],
+    options: Option<&FervidCompileManyOptions>,
+) -> Result<Vec<std::result::Result<fervid::CompileResult, CompileError>>> {
+    let compile_inputs = inputs
+        .iter()
+        .map(|input| {
+            Ok(CompileManyInput {
+                source: Cow::Borrowed(&input.source),
+                options: compile_options(compiler, &input.options)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let compile_many_options = CompileManyOptions {
+        threads: options
+            .and_then(|options| options.threads)
+            .map(|threads| threads as usize),
+    };
+
+    Ok(compile_many(&compile_inputs, &compile_many_options))
+}
+
+/// Normalizes the options to the ones defined in fervid
+fn compile_options<'a>(
+    compiler: &'a FervidJsCompiler,
+    options: &'a FervidCompileOptions,
+) -> Result<CompileOptions<'a>> {
     let props_destructure = match options.props_destructure {
         Some(Either::A(true)) => Some(PropsDestructureConfig::True),
         Some(Either::A(false)) => Some(PropsDestructureConfig::False),
@@ -64,8 +279,10 @@ fn compile_impl(
         _ => None,
     };
 
-    // Normalize options to the ones defined in fervid
-    let compile_options = CompileOptions {
+    let delimiters = template_delimiters(compiler)?;
+    let whitespace = template_whitespace(compiler)?;
+
+    Ok(CompileOptions {
         filename: Cow::Borrowed(&options.filename),
         id: Cow::Borrowed(&options.id),
         is_prod: compiler.options.is_production,
@@ -77,14 +294,62 @@ fn compile_impl(
             .as_ref()
             .map(|v| Cow::Borrowed(v.as_str())),
         source_map: compiler.options.source_map,
-    };
+        in_source_map: options
+            .in_source_map
+            .as_ref()
+            .map(|v| Cow::Borrowed(v.as_str())),
+        delimiters,
+        whitespace,
+        is_custom_element_tag: compiler.is_custom_element_tag.to_owned(),
+        comments: compiler
+            .options
+            .template
+            .as_ref()
+            .and_then(|template| template.comments),
+        ..Default::default()
+    })
+}
 
-    compile(source, compile_options).map_err(|e| Error::from_reason(e.to_string()))
+fn template_delimiters(
+    compiler: &FervidJsCompiler,
+) -> Result<Option<(Cow<'_, str>, Cow<'_, str>)>> {
+    match compiler
+        .options
+        .template
+        .as_ref()
+        .and_then(|template| template.delimiters.as_deref())
+    {
+        Some([start, end]) => Ok(Some((
+            Cow::Borrowed(start.as_str()),
+            Cow::Borrowed(end.as_str()),
+        ))),
+        Some(_) => Err(Error::from_reason(
+            "`template.delimiters` must contain exactly two strings",
+        )),
+        None => Ok(None),
+    }
+}
+
+fn template_whitespace(compiler: &FervidJsCompiler) -> Result<Option<WhitespaceMode>> {
+    match compiler
+        .options
+        .template
+        .as_ref()
+        .and_then(|template| template.whitespace.as_deref())
+    {
+        Some("condense") => Ok(Some(WhitespaceMode::Condense)),
+        Some("preserve") => Ok(Some(WhitespaceMode::Preserve)),
+        Some(_) => Err(Error::from_reason(
+            "`template.whitespace` must be either 'condense' or 'preserve'",
+        )),
+        None => Ok(None),
+    }
 }
 
 fn convert(
     env: Env,
     mut result: fervid::CompileResult,
+    source: &str,
     options: &FervidCompileOptions,
 ) -> CompileResult {
     // Serialize bindings if requested
@@ -101,24 +366,62 @@ fn convert(
         None
     };
 
+    // Same ids as in the other integrations, e.g. the Farm plugin
+    let prelude = fervid_bundler::generate_prelude(&options.filename, &result);
+    let mut queries = fervid_bundler::block_queries(&result)
+        .into_iter()
+        .map(|query| query.to_string());
+
     CompileResult {
         code: result.code,
         source_map: result.source_map,
+        styles: result
+            .styles
+            .into_iter()
+            .zip(queries.by_ref())
+            .map(|(style, query)| Style::new(style, query))
+            .collect(),
         custom_blocks: result
             .other_assets
             .into_iter()
-            .map(|asset| asset.into())
+            .zip(queries)
+            .map(|(asset, query)| CustomBlock::new(asset, query))
             .collect(),
-        errors: result.errors.into_iter().map(|e| e.into()).collect(),
-        styles: result
-            .styles
+        errors: result
+            .errors
             .into_iter()
-            .map(|style| style.into())
+            .map(|e| SerializedError::from_compile_error(e, source))
             .collect(),
         setup_bindings,
+        dependencies: result.dependencies,
+        prelude,
     }
 }
 
+fn convert_many(
+    env: Env,
+    results: Vec<std::result::Result<fervid::CompileResult, CompileError>>,
+    inputs: &[FervidCompileManyInput],
+) -> Vec<CompileResult> {
+    results
+        .into_iter()
+        .zip(inputs)
+        .map(|(result, input)| match result {
+            Ok(result) => convert(env, result, &input.source, &input.options),
+            Err(error) => CompileResult {
+                code: String::new(),
+                styles: vec![],
+                errors: vec![SerializedError::from_compile_error(error, &input.source)],
+                custom_blocks: vec![],
+                source_map: None,
+                setup_bindings: None,
+                dependencies: vec![],
+                prelude: String::new(),
+            },
+        })
+        .collect()
+}
+
 pub struct CompileTask {
     compiler: FervidJsCompiler,
     input: String,
@@ -135,6 +438,26 @@ impl Task for CompileTask {
     }
 
     fn resolve(&mut self, env: Env, result: Self::Output) -> napi::Result<Self::JsValue> {
-        Ok(convert(env, result, &self.options))
+        Ok(convert(env, result, &self.input, &self.options))
+    }
+}
+
+pub struct CompileManyTask {
+    compiler: FervidJsCompiler,
+    inputs: Vec<FervidCompileManyInput>,
+    options: Option<FervidCompileManyOptions>,
+}
+
+#[napi]
+impl Task for CompileManyTask {
+    type JsValue = Vec<CompileResult>;
+    type Output = Vec<std::result::Result<fervid::CompileResult, CompileError>>;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        compile_many_impl(&self.compiler, &self.inputs, self.options.as_ref())
+    }
+
+    fn resolve(&mut self, env: Env, results: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(convert_many(env, results, &self.inputs))
     }
 }
@@ -1,3 +1,9 @@
+use std::collections::HashMap;
+
+use fervid::{
+    error::{generate_code_frame, get_line_column, ErrorCode, Severity},
+    TagPredicate,
+};
 use napi::{Either, JsObject};
 use napi_derive::napi;
 use swc_core::common::Spanned;
@@ -7,6 +13,8 @@ use swc_core::common::Spanned;
 #[derive(Clone)]
 pub struct FervidJsCompiler {
     pub options: FervidJsCompilerOptions,
+    /// Compiled from `options.template.is_custom_element`
+    pub(crate) is_custom_element_tag: Option<TagPredicate>,
 }
 
 /// Raw options passed from the Node.js side
@@ -50,7 +58,32 @@ pub struct FervidJsCompilerOptions {
 
 #[napi(object)]
 #[derive(Clone)]
-pub struct FervidJsCompilerOptionsTemplate {}
+pub struct FervidJsCompilerOptionsTemplate {
+    /// Interpolation delimiters, e.g. `['${', '}']`. Default: `['{{', '}}']`.
+    /// A `delimiters` option of the component takes precedence.
+    #[napi(ts_type = "[string, string]")]
+    pub delimiters: Option<Vec<String>>,
+
+    /// How whitespace in the template is handled. Default: `'condense'`
+    #[napi(ts_type = "'condense' | 'preserve'")]
+    pub whitespace: Option<String>,
+
+    /// Tags which are native custom elements (e.g. `<ion-button>`) and must not be resolved as components
+    pub is_custom_element: Option<FervidJsTagMatcher>,
+
+    /// Keep the HTML comments of the template. Default: `true` in development, `false` in production
+    pub comments: Option<bool>,
+}
+
+/// Matches a tag when it starts with any of `prefixes` or matches any of `patterns`
+#[napi(object)]
+#[derive(Clone)]
+pub struct FervidJsTagMatcher {
+    /// Tag prefixes, e.g. `['ion-']`
+    pub prefixes: Option<Vec<String>>,
+    /// Regular expressions in Rust syntax, e.g. `['^model-']`
+    pub patterns: Option<Vec<String>>,
+}
 
 #[napi(object)]
 #[derive(Clone)]
@@ -94,6 +127,61 @@ pub struct FervidCompileOptions {
 
     /// Whether setup bindings need to be serialized
     pub output_setup_bindings: Option<bool>,
+
+    /// JSON-serialized source map of the input, e.g. when the SFC was preprocessed by another loader.
+    /// The resulting source map will point to the original file.
+    pub in_source_map: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct FervidCompileTemplateOptions {
+    /// Filename used in the source map
+    pub filename: String,
+
+    /// Bindings which the template refers to, e.g. `setupBindings` of `compileSync`.
+    /// Same as `bindingMetadata` in `@vue/compiler-sfc`
+    #[napi(ts_type = "Record<string, BindingTypes>")]
+    pub binding_metadata: Option<HashMap<String, BindingTypes>>,
+
+    /// Generate a render arrow function to be returned from `setup` with the bindings accessed directly,
+    /// instead of a module exporting `render`. Default: false
+    pub inline: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct FervidCompileStyleOptions {
+    /// Filename used in the source map
+    pub filename: String,
+
+    /// Scope ID, with or without the `data-v-` prefix
+    pub id: String,
+
+    /// Scope the selectors with `id`, same as `<style scoped>`. Default: false
+    pub scoped: Option<bool>,
+}
+
+/// A single SFC for `compileManySync` and `compileManyAsync`
+#[napi(object)]
+#[derive(Clone)]
+pub struct FervidCompileManyInput {
+    pub source: String,
+    pub options: FervidCompileOptions,
+}
+
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct FervidCompileManyOptions {
+    /// Number of threads to compile with. Default: a thread per CPU
+    pub threads: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct FervidParseOptions {
+    /// Skip the blocks with empty or whitespace-only content. Default: true
+    pub ignore_empty: Option<bool>,
 }
 
 #[napi(object)]
@@ -105,6 +193,83 @@ pub struct CompileResult {
     pub source_map: Option<String>,
     #[napi(ts_type = "Record<string, BindingTypes> | undefined")]
     pub setup_bindings: Option<JsObject>,
+    /// Files the compilation depended on, e.g. the ones the types of `defineProps` were imported from.
+    /// Bundler plugins should watch them (e.g. using `addWatchFile`) and compile the SFC again on change
+    pub dependencies: Vec<String>,
+    /// Imports of the `styles` and `customBlocks` to put in front of `code`, one per line.
+    /// The imported ids are `filename` followed by the `query` of the block.
+    /// CSS modules are imported as `style{index}`, they belong in `__cssModules` of the component by their names
+    pub prelude: String,
+}
+
+#[napi(object)]
+pub struct CompileTemplateResult {
+    pub code: String,
+    /// Helpers imported from `vue`, e.g. `createElementBlock`.
+    /// In the code they are used with an underscore, e.g. `_createElementBlock`
+    pub imports: Vec<String>,
+    pub errors: Vec<SerializedError>,
+    pub source_map: Option<String>,
+}
+
+#[napi(object)]
+pub struct CompileStyleResult {
+    /// Transformed CSS, empty when it could not be parsed
+    pub code: String,
+    pub errors: Vec<SerializedError>,
+    pub source_map: Option<String>,
+}
+
+#[napi(object)]
+pub struct ParseResult {
+    pub descriptor: SfcDescriptor,
+    pub errors: Vec<SerializedError>,
+}
+
+/// Blocks of an SFC, same as `SFCDescriptor` of `@vue/compiler-sfc` without the ASTs
+#[napi(object)]
+pub struct SfcDescriptor {
+    pub template: Option<SfcBlock>,
+    pub script: Option<SfcBlock>,
+    pub script_setup: Option<SfcBlock>,
+    pub styles: Vec<SfcBlock>,
+    pub custom_blocks: Vec<SfcBlock>,
+}
+
+#[napi(object)]
+pub struct SfcBlock {
+    /// Tag name of the block, e.g. `template`, `script`, `style` or `i18n`
+    #[napi(js_name = "type")]
+    pub block_type: String,
+    /// Raw content between the starting and the ending tags
+    pub content: String,
+    /// Attributes as written, attributes without a value (e.g. `scoped`) are `true`
+    #[napi(ts_type = "Record<string, string | true>")]
+    pub attrs: HashMap<String, Either<String, bool>>,
+    /// Location of `content`
+    pub loc: SourceLocation,
+    pub lang: Option<String>,
+    pub src: Option<String>,
+    pub setup: Option<bool>,
+    pub scoped: Option<bool>,
+    #[napi(ts_type = "string | true")]
+    pub module: Option<Either<String, bool>>,
+}
+
+#[napi(object)]
+pub struct SourceLocation {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+#[napi(object)]
+pub struct SourcePosition {
+    /// 0-based byte offset
+    pub offset: u32,
+    /// 1-based line
+    pub line: u32,
+    /// 1-based column
+    pub column: u32,
 }
 
 #[napi(object)]
@@ -113,6 +278,10 @@ pub struct Style {
     pub is_compiled: bool,
     pub lang: String,
     pub is_scoped: bool,
+    /// Source map pointing into the SFC, only present for compiled styles
+    pub source_map: Option<String>,
+    /// Query of the style module in `prelude`, e.g. `?vue&type=style&index=0&lang.css`
+    pub query: String,
 }
 
 #[napi(object)]
@@ -121,6 +290,8 @@ pub struct CustomBlock {
     pub lo: u32,
     pub hi: u32,
     pub tag_name: String,
+    /// Query of the block module in `prelude`, e.g. `?vue&type=i18n&index=0&lang.json`
+    pub query: String,
 }
 
 #[napi(object)]
@@ -128,6 +299,20 @@ pub struct SerializedError {
     pub lo: u32,
     pub hi: u32,
     pub message: String,
+    /// Stable error code, e.g. `X_INVALID_END_TAG`
+    pub code: String,
+    #[napi(ts_type = "'error' | 'warning'")]
+    pub severity: String,
+    /// 1-based line of `lo`
+    pub start_line: u32,
+    /// 1-based column of `lo`
+    pub start_column: u32,
+    /// 1-based line of `hi`
+    pub end_line: u32,
+    /// 1-based column of `hi`
+    pub end_column: u32,
+    /// Source code excerpt with the error location underlined
+    pub frame: Option<String>,
 }
 
 /// This is a copied enum from `fervid_core` with `napi` implementation to avoid littering the core crate.
@@ -137,7 +322,7 @@ pub struct SerializedError {
 ///
 /// <https://github.com/vuejs/core/blob/020851e57d9a9f727c6ea07e9c1575430af02b73/packages/compiler-core/src/options.ts#L76>
 #[napi]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum BindingTypes {
     /// returned from data()
     DATA,
@@ -198,35 +383,116 @@ impl From<fervid::BindingTypes> for BindingTypes {
     }
 }
 
-impl From<fervid::CompileEmittedStyle> for Style {
-    fn from(value: fervid::CompileEmittedStyle) -> Self {
+impl From<BindingTypes> for fervid::BindingTypes {
+    fn from(value: BindingTypes) -> Self {
+        match value {
+            BindingTypes::DATA => fervid::BindingTypes::Data,
+            BindingTypes::PROPS => fervid::BindingTypes::Props,
+            BindingTypes::PROPS_ALIASED => fervid::BindingTypes::PropsAliased,
+            BindingTypes::SETUP_LET => fervid::BindingTypes::SetupLet,
+            BindingTypes::SETUP_CONST => fervid::BindingTypes::SetupConst,
+            BindingTypes::SETUP_REACTIVE_CONST => fervid::BindingTypes::SetupReactiveConst,
+            BindingTypes::SETUP_MAYBE_REF => fervid::BindingTypes::SetupMaybeRef,
+            BindingTypes::SETUP_REF => fervid::BindingTypes::SetupRef,
+            BindingTypes::OPTIONS => fervid::BindingTypes::Options,
+            BindingTypes::LITERAL_CONST => fervid::BindingTypes::LiteralConst,
+            BindingTypes::COMPONENT => fervid::BindingTypes::Component,
+            BindingTypes::IMPORTED => fervid::BindingTypes::Imported,
+            BindingTypes::TEMPLATE_LOCAL => fervid::BindingTypes::TemplateLocal,
+            BindingTypes::JS_GLOBAL => fervid::BindingTypes::JsGlobal,
+            BindingTypes::UNRESOLVED => fervid::BindingTypes::Unresolved,
+        }
+    }
+}
+
+impl Style {
+    pub fn new(value: fervid::CompileEmittedStyle, query: String) -> Self {
         Self {
             code: value.code,
             is_compiled: value.is_compiled,
             lang: value.lang,
             is_scoped: value.is_scoped,
+            source_map: value.source_map,
+            query,
         }
     }
 }
 
-impl From<fervid::errors::CompileError> for SerializedError {
-    fn from(value: fervid::errors::CompileError) -> Self {
+impl SerializedError {
+    pub fn from_compile_error(value: fervid::errors::CompileError, source: &str) -> Self {
         let span = value.span();
+        let start = (span.lo.0 as usize).saturating_sub(1);
+        let end = (span.hi.0 as usize).saturating_sub(1);
+        let (start_line, start_column) = get_line_column(source, start);
+        let (end_line, end_column) = get_line_column(source, end);
+
         SerializedError {
             lo: span.lo.0,
             hi: span.hi.0,
             message: value.to_string(),
+            code: value.error_code().to_owned(),
+            severity: value.get_severity().as_str().to_owned(),
+            start_line: start_line as u32,
+            start_column: start_column as u32,
+            end_line: end_line as u32,
+            end_column: end_column as u32,
+            frame: (!span.is_dummy()).then(|| generate_code_frame(source, start, end)),
         }
     }
 }
 
-impl From<fervid::CompileEmittedAsset> for CustomBlock {
-    fn from(value: fervid::CompileEmittedAsset) -> Self {
+impl CustomBlock {
+    pub fn new(value: fervid::CompileEmittedAsset, query: String) -> Self {
         CustomBlock {
             content: value.content,
             lo: value.lo,
             hi: value.hi,
             tag_name: value.tag_name,
+            query,
+        }
+    }
+}
+
+impl From<fervid::ParsedSfcBlock> for SfcBlock {
+    fn from(value: fervid::ParsedSfcBlock) -> Self {
+        // Same as `@vue/compiler-sfc`, an empty value is `true`
+        let attr_value = |value: String| {
+            if value.is_empty() {
+                Either::B(true)
+            } else {
+                Either::A(value)
+            }
+        };
+
+        SfcBlock {
+            block_type: value.block_type,
+            content: value.content,
+            loc: value.loc.into(),
+            lang: value.lang,
+            src: value.src,
+            setup: value.setup.then_some(true),
+            scoped: value.scoped.then_some(true),
+            module: value.module.map(attr_value),
+            attrs: value
+                .attrs
+                .into_iter()
+                .map(|(name, value)| (name, attr_value(value)))
+                .collect(),
+        }
+    }
+}
+
+impl From<fervid::SourceLocation> for SourceLocation {
+    fn from(value: fervid::SourceLocation) -> Self {
+        let position = |position: fervid::SourcePosition| SourcePosition {
+            offset: position.offset as u32,
+            line: position.line as u32,
+            column: position.column as u32,
+        };
+
+        SourceLocation {
+            start: position(value.start),
+            end: position(value.end),
         }
     }
 }
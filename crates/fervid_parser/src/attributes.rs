@@ -49,11 +49,15 @@ impl SfcParser<'_, '_, '_> {
             let raw_idx_end = raw_idx_start + raw_attribute.name.len();
             raw_attribute.name = FervidAtom::from(&self.input[raw_idx_start..raw_idx_end]);
 
+            let errors_before = self.errors.len();
             match self.try_parse_directive(raw_attribute, attrs_or_bindings, vue_directives) {
                 Ok(()) => {
                     // do nothing, we are good already
                 }
 
+                // Malformed directive is skipped unless placeholders are requested
+                Err(_) if self.errors.len() > errors_before && !self.keeps_placeholders() => {}
+
                 // parse as a raw attribute
                 Err(raw_attribute) => {
                     attrs_or_bindings.push(create_regular_attribute(raw_attribute))
@@ -1,4 +1,5 @@
-use swc_core::common::{Span, Spanned};
+use fervid_core::error::{ErrorCode, Severity, SeverityLevel};
+use swc_core::common::{Span, Spanned, DUMMY_SP};
 
 #[derive(Debug)]
 pub struct ParseError {
@@ -32,8 +33,12 @@ pub enum ParseErrorKind {
     EcmaSyntaxError(Box<swc_ecma_parser::error::SyntaxError>),
     /// Unrecoverable error while parsing HTML
     InvalidHtml(Box<swc_html_parser::error::ErrorKind>),
+    /// Interpolation was started, but not closed (e.g. `{{ foo`)
+    MissingInterpolationEnd,
     /// Both `<template>` and `<script>` are missing
     MissingTemplateOrScript,
+    /// `<!DOCTYPE>` inside `<template>`
+    UnexpectedDoctype,
     /// `<script>`/`<style>` content was not Text
     UnexpectedNonRawTextContent,
     /// Language not supported
@@ -53,13 +58,172 @@ impl From<swc_ecma_parser::error::Error> for ParseError {
 
 impl std::fmt::Display for ParseErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            ParseErrorKind::DirectiveSyntax => f.write_str("Malformed directive."),
+            ParseErrorKind::DirectiveSyntaxDirectiveName => {
+                f.write_str("Legal directive name was expected.")
+            }
+            ParseErrorKind::DirectiveSyntaxArgument => {
+                f.write_str("Directive argument was expected.")
+            }
+            ParseErrorKind::DirectiveSyntaxDynamicArgument => f.write_str(
+                "End bracket for dynamic directive argument was not found. \
+                Note that dynamic directive argument cannot contain spaces.",
+            ),
+            ParseErrorKind::DirectiveSyntaxUnexpectedCharacterAfterDynamicArgument => f.write_str(
+                "Unexpected character after dynamic directive argument. Expected a modifier.",
+            ),
+            ParseErrorKind::DirectiveSyntaxModifier => {
+                f.write_str("Directive modifier was expected.")
+            }
+            ParseErrorKind::DuplicateScriptOptions => {
+                f.write_str("Single file component can contain only one <script> element.")
+            }
+            ParseErrorKind::DuplicateScriptSetup => {
+                f.write_str("Single file component can contain only one <script setup> element.")
+            }
+            ParseErrorKind::DuplicateTemplate => {
+                f.write_str("Single file component can contain only one <template> element.")
+            }
+            ParseErrorKind::DuplicateAttribute => f.write_str("Duplicate attribute."),
+            ParseErrorKind::EcmaSyntaxError(e) => f.write_str(&e.msg()),
+            ParseErrorKind::InvalidHtml(kind) => f.write_str(
+                &swc_html_parser::error::Error::new(DUMMY_SP, (**kind).clone()).message(),
+            ),
+            ParseErrorKind::MissingInterpolationEnd => {
+                f.write_str("Interpolation end sign was not found.")
+            }
+            ParseErrorKind::MissingTemplateOrScript => f.write_str(
+                "At least one <template> or <script> is required in a single file component.",
+            ),
+            ParseErrorKind::UnexpectedDoctype => {
+                f.write_str("Doctype is not supported in templates.")
+            }
+            ParseErrorKind::UnexpectedNonRawTextContent => {
+                f.write_str("Content of <script> and <style> must be plain text.")
+            }
+            ParseErrorKind::UnsupportedLang => f.write_str("Unsupported language."),
+        }
     }
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        self.kind.fmt(f)
+    }
+}
+
+impl Severity for ParseError {
+    fn get_severity(&self) -> SeverityLevel {
+        self.kind.get_severity()
+    }
+}
+
+impl Severity for ParseErrorKind {
+    fn get_severity(&self) -> SeverityLevel {
+        match self {
+            // Only the offending attribute, directive or node is affected
+            ParseErrorKind::DirectiveSyntax
+            | ParseErrorKind::DirectiveSyntaxDirectiveName
+            | ParseErrorKind::DirectiveSyntaxArgument
+            | ParseErrorKind::DirectiveSyntaxDynamicArgument
+            | ParseErrorKind::DirectiveSyntaxUnexpectedCharacterAfterDynamicArgument
+            | ParseErrorKind::DirectiveSyntaxModifier
+            | ParseErrorKind::DuplicateAttribute
+            | ParseErrorKind::InvalidHtml(_)
+            | ParseErrorKind::MissingInterpolationEnd
+            | ParseErrorKind::UnexpectedDoctype => SeverityLevel::RecoverableError,
+
+            // The code cannot be compiled as written, a whole block or the SFC is lost
+            ParseErrorKind::DuplicateScriptOptions
+            | ParseErrorKind::DuplicateScriptSetup
+            | ParseErrorKind::DuplicateTemplate
+            | ParseErrorKind::EcmaSyntaxError(_)
+            | ParseErrorKind::MissingTemplateOrScript
+            | ParseErrorKind::UnexpectedNonRawTextContent
+            | ParseErrorKind::UnsupportedLang => SeverityLevel::UnrecoverableError,
+        }
+    }
+}
+
+impl ErrorCode for ParseError {
+    fn error_code(&self) -> &'static str {
+        self.kind.error_code()
+    }
+}
+
+impl ErrorCode for ParseErrorKind {
+    fn error_code(&self) -> &'static str {
+        match self {
+            ParseErrorKind::DirectiveSyntax => "X_INVALID_DIRECTIVE",
+            ParseErrorKind::DirectiveSyntaxDirectiveName => "X_MISSING_DIRECTIVE_NAME",
+            ParseErrorKind::DirectiveSyntaxArgument => "X_MISSING_DIRECTIVE_ARGUMENT",
+            ParseErrorKind::DirectiveSyntaxDynamicArgument => {
+                "X_MISSING_DYNAMIC_DIRECTIVE_ARGUMENT_END"
+            }
+            ParseErrorKind::DirectiveSyntaxUnexpectedCharacterAfterDynamicArgument => {
+                "X_UNEXPECTED_CHARACTER_AFTER_DYNAMIC_ARGUMENT"
+            }
+            ParseErrorKind::DirectiveSyntaxModifier => "X_MISSING_DIRECTIVE_MODIFIER",
+            ParseErrorKind::DuplicateScriptOptions => "SFC_DUPLICATE_SCRIPT",
+            ParseErrorKind::DuplicateScriptSetup => "SFC_DUPLICATE_SCRIPT_SETUP",
+            ParseErrorKind::DuplicateTemplate => "SFC_DUPLICATE_TEMPLATE",
+            ParseErrorKind::DuplicateAttribute => "DUPLICATE_ATTRIBUTE",
+            ParseErrorKind::EcmaSyntaxError(_) => "X_INVALID_EXPRESSION",
+            ParseErrorKind::InvalidHtml(kind) => html_error_code(kind),
+            ParseErrorKind::MissingInterpolationEnd => "X_MISSING_INTERPOLATION_END",
+            ParseErrorKind::MissingTemplateOrScript => "SFC_MISSING_TEMPLATE_OR_SCRIPT",
+            ParseErrorKind::UnexpectedDoctype => "X_UNEXPECTED_DOCTYPE",
+            ParseErrorKind::UnexpectedNonRawTextContent => "SFC_UNEXPECTED_NON_RAW_TEXT_CONTENT",
+            ParseErrorKind::UnsupportedLang => "SFC_UNSUPPORTED_LANG",
+        }
+    }
+}
+
+/// Maps an HTML parsing error to the Vue `ErrorCodes` name.
+/// Vue uses the names from the HTML spec, and so does SWC.
+fn html_error_code(kind: &swc_html_parser::error::ErrorKind) -> &'static str {
+    use swc_html_parser::error::ErrorKind;
+
+    match kind {
+        ErrorKind::AbruptClosingOfEmptyComment => "ABRUPT_CLOSING_OF_EMPTY_COMMENT",
+        ErrorKind::CdataInHtmlContent => "CDATA_IN_HTML_CONTENT",
+        ErrorKind::DuplicateAttribute => "DUPLICATE_ATTRIBUTE",
+        ErrorKind::EndTagWithAttributes => "END_TAG_WITH_ATTRIBUTES",
+        ErrorKind::EndTagWithTrailingSolidus => "END_TAG_WITH_TRAILING_SOLIDUS",
+        ErrorKind::EofBeforeTagName => "EOF_BEFORE_TAG_NAME",
+        ErrorKind::EofInCdata => "EOF_IN_CDATA",
+        ErrorKind::EofInComment => "EOF_IN_COMMENT",
+        ErrorKind::EofInScriptHtmlCommentLikeText => "EOF_IN_SCRIPT_HTML_COMMENT_LIKE_TEXT",
+        ErrorKind::EofInTag => "EOF_IN_TAG",
+        ErrorKind::IncorrectlyClosedComment => "INCORRECTLY_CLOSED_COMMENT",
+        ErrorKind::IncorrectlyOpenedComment => "INCORRECTLY_OPENED_COMMENT",
+        ErrorKind::InvalidFirstCharacterOfTagName => "INVALID_FIRST_CHARACTER_OF_TAG_NAME",
+        ErrorKind::MissingAttributeValue => "MISSING_ATTRIBUTE_VALUE",
+        ErrorKind::MissingEndTagName => "MISSING_END_TAG_NAME",
+        ErrorKind::MissingWhitespaceBetweenAttributes => "MISSING_WHITESPACE_BETWEEN_ATTRIBUTES",
+        ErrorKind::NestedComment => "NESTED_COMMENT",
+        ErrorKind::UnexpectedCharacterInAttributeName => "UNEXPECTED_CHARACTER_IN_ATTRIBUTE_NAME",
+        ErrorKind::UnexpectedCharacterInUnquotedAttributeValue => {
+            "UNEXPECTED_CHARACTER_IN_UNQUOTED_ATTRIBUTE_VALUE"
+        }
+        ErrorKind::UnexpectedEqualsSignBeforeAttributeName => {
+            "UNEXPECTED_EQUALS_SIGN_BEFORE_ATTRIBUTE_NAME"
+        }
+        ErrorKind::UnexpectedNullCharacter => "UNEXPECTED_NULL_CHARACTER",
+        ErrorKind::UnexpectedQuestionMarkInsteadOfTagName => {
+            "UNEXPECTED_QUESTION_MARK_INSTEAD_OF_TAG_NAME"
+        }
+        ErrorKind::UnexpectedSolidusInTag => "UNEXPECTED_SOLIDUS_IN_TAG",
+        ErrorKind::StrayEndTag(_)
+        | ErrorKind::EndTagDidNotMatchCurrentOpenElement(_, _)
+        | ErrorKind::NoElementToCloseButEndTagSeen(_) => "X_INVALID_END_TAG",
+        ErrorKind::UnclosedElements(_)
+        | ErrorKind::UnclosedElementsImplied(_)
+        | ErrorKind::UnclosedElementsOnStack
+        | ErrorKind::EofWithUnclosedElements
+        | ErrorKind::EndTagWithUnclosedElements(_) => "X_MISSING_END_TAG",
+        _ => "HTML_SYNTAX_ERROR",
     }
 }
 
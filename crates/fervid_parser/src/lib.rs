@@ -6,51 +6,111 @@ mod sfc;
 mod style;
 mod template;
 
-pub use error::ParseError;
+pub use error::{ParseError, ParseErrorKind};
 use swc_core::common::comments::SingleThreadedComments;
 
 // Default patterns for interpolation
 pub const INTERPOLATION_START_PAT_DEFAULT: &str = "{{";
 pub const INTERPOLATION_END_PAT_DEFAULT: &str = "}}";
 
+/// Controls what the parser does when it encounters an HTML or EcmaScript error
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Any error is fatal, parsing stops at the first one
+    /// and `parse_sfc` returns it as `Err`.
+    HardFailOnError,
+    /// Any error is fatal, but parsing continues as far as possible.
+    /// All the errors are reported and `parse_sfc` returns the first one as `Err`.
+    SoftReportAll,
+    /// Errors are reported and the offending nodes are skipped:
+    /// malformed attributes (the element itself is kept), directives and interpolations
+    /// with bad expressions, and scripts which failed to parse.
+    #[default]
+    SoftRecoverSafe,
+    /// Errors are reported, but the offending nodes are kept as placeholders as much as possible:
+    /// bad directives become regular attributes, bad interpolations become text
+    /// and scripts which failed to parse become empty.
+    /// This is meant for tooling which needs a best-effort descriptor of a broken file.
+    SoftRecoverUnsafe,
+}
+
 #[derive(Debug)]
 pub struct SfcParser<'i, 'e, 'p> {
     input: &'i str,
     errors: &'e mut Vec<ParseError>,
+    /// Index of the first error reported during the current `parse_sfc`
+    errors_start: usize,
     comments: SingleThreadedComments,
     is_pre: bool,
     interpolation_start_pat: &'p str,
     interpolation_end_pat: &'p str,
     pub ignore_empty: bool,
+    pub mode: ParseMode,
 }
 
 impl<'i, 'e> SfcParser<'i, 'e, 'static> {
     pub fn new(input: &'i str, errors: &'e mut Vec<ParseError>) -> Self {
-        // TODO When should it fail? What do we do with errors?..
-        // I was thinking of 4 strategies:
-        // `HARD_FAIL_ON_ERROR` (any error = fail (incl. recoverable), stop at the first error),
-        // `SOFT_REPORT_ALL` (any error = fail (incl. recoverable), continue as far as possible and report after),
-        // `SOFT_RECOVER_SAFE` (try to ignore recoverable, report the rest),
-        // `SOFT_RECOVER_UNSAFE` (ignore as much as is possible, but still report).
-
         SfcParser {
             input,
             errors,
+            errors_start: 0,
             comments: SingleThreadedComments::default(),
             is_pre: false,
             interpolation_start_pat: INTERPOLATION_START_PAT_DEFAULT,
             interpolation_end_pat: INTERPOLATION_END_PAT_DEFAULT,
             ignore_empty: true,
+            mode: ParseMode::default(),
+        }
+    }
+}
+
+impl<'i, 'e, 'p> SfcParser<'i, 'e, 'p> {
+    /// Sets the patterns which start and end an interpolation, `{{` and `}}` by default.
+    /// Empty patterns are ignored and the current ones are kept.
+    pub fn with_delimiters<'d>(self, start: &'d str, end: &'d str) -> SfcParser<'i, 'e, 'd>
+    where
+        'p: 'd,
+    {
+        let keep_current = start.is_empty() || end.is_empty();
+        let (interpolation_start_pat, interpolation_end_pat) = if keep_current {
+            (self.interpolation_start_pat, self.interpolation_end_pat)
+        } else {
+            (start, end)
+        };
+
+        SfcParser {
+            input: self.input,
+            errors: self.errors,
+            errors_start: self.errors_start,
+            comments: self.comments,
+            is_pre: self.is_pre,
+            interpolation_start_pat,
+            interpolation_end_pat,
+            ignore_empty: self.ignore_empty,
+            mode: self.mode,
         }
     }
+
+    /// Sets how the parser handles errors, see [`ParseMode`]
+    pub fn with_mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use fervid_core::{Node, SfcDescriptor, SfcScriptLang};
-    use swc_core::ecma::ast::{ModuleDecl, ModuleItem};
-
-    use crate::{error::ParseErrorKind, ParseError, SfcParser};
+    use fervid_core::{
+        error::{Severity, SeverityLevel},
+        AttributeOrBinding, Node, SfcDescriptor, SfcScriptLang,
+    };
+    use swc_core::{
+        common::{BytePos, Span},
+        ecma::ast::{ModuleDecl, ModuleItem},
+    };
+    use swc_ecma_parser::Syntax;
+
+    use crate::{error::ParseErrorKind, ParseError, ParseMode, SfcParser};
 
     const SHOULD_EXIST: &str = "Should exist";
 
@@ -255,6 +315,46 @@ h1 { color: red }
         assert!(parse("<custom> \n\t </custom>").custom_blocks.is_empty());
     }
 
+    #[test]
+    fn it_keeps_block_attributes() {
+        let descriptor = parse(
+            r#"<template lang="html" functional><div></div></template>
+<script setup lang="ts" generic="T">const a = 1</script>
+<style scoped module="classes" src="./foo.css">a {}</style>"#,
+        );
+
+        fn names_and_values(attrs: &[AttributeOrBinding]) -> Vec<(&str, &str)> {
+            attrs
+                .iter()
+                .map(|attr| match attr {
+                    AttributeOrBinding::RegularAttribute { name, value, .. } => {
+                        (name.as_str(), value.as_str())
+                    }
+                    _ => panic!("Expected a regular attribute"),
+                })
+                .collect()
+        }
+
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        assert_eq!(
+            vec![("lang", "html"), ("functional", "")],
+            names_and_values(&template.attrs)
+        );
+
+        let script_setup = descriptor.script_setup.expect(SHOULD_EXIST);
+        assert_eq!(
+            vec![("setup", ""), ("lang", "ts"), ("generic", "T")],
+            names_and_values(&script_setup.attrs)
+        );
+
+        let style = &descriptor.styles[0];
+        assert!(style.is_scoped && style.is_module);
+        assert_eq!(
+            vec![("scoped", ""), ("module", "classes"), ("src", "./foo.css")],
+            names_and_values(&style.attrs)
+        );
+    }
+
     #[test]
     fn handle_empty_nodes_with_src_attribute() {
         // src imports not supported
@@ -420,6 +520,249 @@ h1 { color: red }
             .any(|e| matches!(&e.kind, ParseErrorKind::MissingTemplateOrScript)));
     }
 
+    #[test]
+    fn hard_fail_on_error_returns_first_error() {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(
+            "<template><div>{{ foo + }}</div></template><script>const</script>",
+            &mut errors,
+        );
+        parser.mode = ParseMode::HardFailOnError;
+
+        let error = parser.parse_sfc().expect_err("Should fail");
+        assert!(matches!(error.kind, ParseErrorKind::EcmaSyntaxError(_)));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn hard_fail_on_error_accepts_self_closing_tags() {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(
+            "<template><MyComp /><div /></template><script setup>import MyComp from './MyComp.vue'</script>",
+            &mut errors,
+        )
+        .with_mode(ParseMode::HardFailOnError);
+
+        let descriptor = parser.parse_sfc().expect("Should parse");
+        assert_eq!(descriptor.template.expect(SHOULD_EXIST).roots.len(), 2);
+        assert!(errors.is_empty());
+
+        // Real HTML errors are still fatal
+        let mut parser = SfcParser::new("<template><div a=></div></template>", &mut errors)
+            .with_mode(ParseMode::HardFailOnError);
+        let error = parser.parse_sfc().expect_err("Should fail");
+        assert!(matches!(error.kind, ParseErrorKind::InvalidHtml(_)));
+    }
+
+    #[test]
+    fn soft_report_all_reports_all_errors() {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(
+            "<template><div>{{ foo + }}</div></template><script>const</script>",
+            &mut errors,
+        );
+        parser.mode = ParseMode::SoftReportAll;
+
+        let error = parser.parse_sfc().expect_err("Should fail");
+        assert!(matches!(error.kind, ParseErrorKind::EcmaSyntaxError(_)));
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn soft_recover_safe_skips_bad_nodes() {
+        let (descriptor, errors) = parse_with_errors(
+            r#"<template><span :foo="a +">{{ b + }}</span><div a="1" a="2"></div></template>"#,
+        );
+        assert_eq!(3, errors.len());
+
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        let [Node::Element(span), Node::Element(div)] = template.roots.as_slice() else {
+            panic!("Expected span and div")
+        };
+        assert!(span.starting_tag.attributes.is_empty());
+        assert!(span.children.is_empty());
+
+        // Only the duplicate attribute is dropped
+        assert!(matches!(
+            div.starting_tag.attributes.as_slice(),
+            [AttributeOrBinding::RegularAttribute { name, value, .. }] if name == "a" && value == "1"
+        ));
+    }
+
+    #[test]
+    fn soft_recover_safe_keeps_elements_with_malformed_attributes() {
+        let (descriptor, errors) = parse_with_errors(
+            r#"<template><div a="1"b="2" c="3"><span>hi</span></div></template>"#,
+        );
+        assert_eq!(1, errors.len());
+
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        let [Node::Element(div)] = template.roots.as_slice() else {
+            panic!("Expected div")
+        };
+        let names: Vec<&str> = div
+            .starting_tag
+            .attributes
+            .iter()
+            .filter_map(|attr| match attr {
+                AttributeOrBinding::RegularAttribute { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec!["a", "c"], names);
+        assert!(matches!(div.children.as_slice(), [Node::Element(_)]));
+    }
+
+    #[test]
+    fn it_reports_severities() {
+        let (_, errors) = parse_with_errors(
+            "<template><div a=\"1\" a=\"2\"></div></template><template></template>",
+        );
+        let severities: Vec<SeverityLevel> = errors.iter().map(|e| e.get_severity()).collect();
+        assert_eq!(
+            vec![
+                SeverityLevel::RecoverableError,
+                SeverityLevel::UnrecoverableError
+            ],
+            severities
+        );
+    }
+
+    #[test]
+    fn soft_recover_unsafe_keeps_placeholders() {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(
+            r#"<template><span :foo="a +">{{ b + }}</span><div a="1" a="2"></div></template>
+<script>if (</script>"#,
+            &mut errors,
+        );
+        parser.mode = ParseMode::SoftRecoverUnsafe;
+        let descriptor = parser.parse_sfc().expect("Should parse");
+        assert_eq!(4, errors.len());
+
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        let [Node::Element(span), Node::Element(div)] = template.roots.as_slice() else {
+            panic!("Expected span and div")
+        };
+        assert!(matches!(
+            span.starting_tag.attributes.as_slice(),
+            [AttributeOrBinding::RegularAttribute { name, .. }] if name == ":foo"
+        ));
+        assert!(matches!(span.children.as_slice(), [Node::Text(t, _)] if t == "{{ b + }}"));
+        assert!(div.starting_tag.tag_name == "div");
+
+        let script = descriptor.script_legacy.expect(SHOULD_EXIST);
+        assert!(script.content.body.is_empty());
+    }
+
+    #[test]
+    fn reports_missing_interpolation_end() {
+        let (descriptor, errors) = parse_with_errors("<template>{{ foo </template>");
+        let [error] = errors.as_slice() else {
+            panic!("Expected one error")
+        };
+        assert!(matches!(
+            error.kind,
+            ParseErrorKind::MissingInterpolationEnd
+        ));
+        assert_eq!(11, error.span.lo.0);
+
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        assert!(matches!(template.roots.as_slice(), [Node::Text(t, _)] if t == "{{ foo "));
+    }
+
+    #[test]
+    fn it_uses_custom_delimiters() {
+        let source = "<template>{{ raw }} ${ foo }<span>[[bar]]</span></template>";
+
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(source, &mut errors).with_delimiters("${", "}");
+        let descriptor = parser.parse_sfc().unwrap();
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        let [Node::Text(t, _), Node::Interpolation(interpolation), Node::Element(_)] =
+            template.roots.as_slice()
+        else {
+            panic!("Expected text, interpolation and element")
+        };
+        assert_eq!("{{ raw }} ", t);
+        assert!(interpolation.value.is_ident());
+        assert_eq!(23, interpolation.span.lo.0);
+
+        let owned_delimiters = (String::from("[["), String::from("]]"));
+        let mut parser = SfcParser::new(source, &mut errors)
+            .with_delimiters(&owned_delimiters.0, &owned_delimiters.1)
+            .with_mode(ParseMode::HardFailOnError);
+        let descriptor = parser.parse_sfc().unwrap();
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        let Some(Node::Element(span)) = template.roots.last() else {
+            panic!("Expected element")
+        };
+        assert!(matches!(span.children.as_slice(), [Node::Interpolation(_)]));
+
+        // Empty delimiters are ignored
+        let mut parser = SfcParser::new(source, &mut errors).with_delimiters("", "");
+        let descriptor = parser.parse_sfc().unwrap();
+        let template = descriptor.template.expect(SHOULD_EXIST);
+        assert!(matches!(
+            template.roots.first(),
+            Some(Node::Interpolation(_))
+        ));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn it_parses_sfc_template() {
+        let source = "<script>\nexport default { delimiters: ['${', '}'] }\n</script>\n<template><div>${ msg }</div></template>\n<docs></i></docs>";
+
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new(source, &mut errors)
+            .with_delimiters("${", "}")
+            .with_mode(ParseMode::SoftReportAll);
+        let template = parser.parse_sfc_template().unwrap().expect(SHOULD_EXIST);
+        let [Node::Element(div)] = template.roots.as_slice() else {
+            panic!("Expected element")
+        };
+        assert!(matches!(div.children.as_slice(), [Node::Interpolation(_)]));
+        // Errors outside of `<template>` are not reported again
+        assert!(errors.is_empty());
+
+        let mut parser = SfcParser::new("<script></script>", &mut errors);
+        assert!(parser.parse_sfc_template().unwrap().is_none());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn it_parses_standalone_template() {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new("<div>{{ msg }}</div><span></span>", &mut errors);
+        let template = parser.parse_template().unwrap();
+        assert!(template.lang == "html");
+        let [Node::Element(div), Node::Element(_)] = template.roots.as_slice() else {
+            panic!("Expected two elements")
+        };
+        let [Node::Interpolation(interpolation)] = div.children.as_slice() else {
+            panic!("Expected interpolation")
+        };
+        assert_eq!(8, interpolation.span.lo.0);
+
+        // `<template>` is a regular element here
+        let mut parser = SfcParser::new("<template>foo</template><script></script>", &mut errors);
+        let template = parser.parse_template().unwrap();
+        assert_eq!(2, template.roots.len());
+        assert!(errors.is_empty());
+
+        let mut parser =
+            SfcParser::new("<div>{{ foo </div>", &mut errors).with_mode(ParseMode::SoftReportAll);
+        let result = parser.parse_template();
+        assert!(matches!(
+            result,
+            Err(ParseError {
+                kind: ParseErrorKind::MissingInterpolationEnd,
+                ..
+            })
+        ));
+    }
+
     fn parse(source: &str) -> SfcDescriptor {
         let mut errors = Vec::new();
         let mut parser = SfcParser::new(source, &mut errors);
@@ -441,4 +784,30 @@ h1 { color: red }
         let padding = 4;
         ("\n".repeat(padding), padding)
     }
+
+    #[test]
+    fn it_rejects_html_like_comments_in_expressions() {
+        let mut errors = Vec::new();
+        let mut parser = SfcParser::new("", &mut errors);
+
+        for raw in ["<!-- comment -->\n/foo/", "a\n--> comment\n/foo/"] {
+            let span = Span::new(BytePos(1), BytePos(raw.len() as u32 + 1));
+            let result = parser.parse_expr(raw, Syntax::Es(Default::default()), span);
+            assert!(matches!(
+                result,
+                Err(ParseError {
+                    kind: ParseErrorKind::EcmaSyntaxError(_),
+                    ..
+                })
+            ));
+        }
+
+        // Not comments
+        for raw in ["a-->b", "'<!--' + a", "`\n--> text`\n/ a", "a // <!--\n/ b"] {
+            let span = Span::new(BytePos(1), BytePos(raw.len() as u32 + 1));
+            assert!(parser
+                .parse_expr(raw, Syntax::Es(Default::default()), span)
+                .is_ok());
+        }
+    }
 }
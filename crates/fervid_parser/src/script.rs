@@ -1,12 +1,17 @@
 use fervid_core::{SfcScriptBlock, SfcScriptLang};
 use swc_core::{
-    common::Span,
+    common::{
+        comments::{CommentKind, SingleThreadedComments},
+        Span,
+    },
     ecma::ast::{Expr, Module, Pat},
 };
-use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax, TsSyntax};
+use swc_ecma_parser::{
+    error::SyntaxError, lexer::Lexer, EsSyntax, Parser, StringInput, Syntax, TsSyntax,
+};
 use swc_html_ast::{Child, Element};
 
-use crate::{error::ParseErrorKind, ParseError, SfcParser};
+use crate::{error::ParseErrorKind, sfc::raw_attributes, ParseError, SfcParser};
 
 impl SfcParser<'_, '_, '_> {
     /// Parses the `<script>` and `<script setup>`, both in EcmaScript and TypeScript
@@ -76,6 +81,7 @@ impl SfcParser<'_, '_, '_> {
                     }),
                     lang,
                     is_setup,
+                    attrs: raw_attributes(&element.attributes),
                     span: element.span,
                 }));
             }
@@ -86,7 +92,7 @@ impl SfcParser<'_, '_, '_> {
             return Ok(None);
         }
 
-        let module_content = self.parse_module(
+        let module_content = match self.parse_module(
             &script_content.data,
             if matches!(lang, SfcScriptLang::Typescript) {
                 Syntax::Typescript(TsSyntax::default())
@@ -94,12 +100,27 @@ impl SfcParser<'_, '_, '_> {
                 Syntax::Es(EsSyntax::default())
             },
             script_content.span,
-        )?;
+        ) {
+            Ok(module_content) => module_content,
+
+            // Keep an empty script in place of the broken one
+            Err(e) if self.keeps_placeholders() => {
+                self.report_error(e);
+                Module {
+                    span: script_content.span,
+                    body: Vec::new(),
+                    shebang: None,
+                }
+            }
+
+            Err(e) => return Err(e),
+        };
 
         Ok(Some(SfcScriptBlock {
             content: Box::new(module_content),
             lang,
             is_setup,
+            attrs: raw_attributes(&element.attributes),
             span: element.span,
         }))
     }
@@ -111,6 +132,8 @@ impl SfcParser<'_, '_, '_> {
         syntax: Syntax,
         span: Span,
     ) -> Result<Module, ParseError> {
+        check_html_like_comments(raw, syntax, span)?;
+
         let lexer = Lexer::new(
             syntax,
             // EsVersion defaults to es5
@@ -135,6 +158,8 @@ impl SfcParser<'_, '_, '_> {
         syntax: Syntax,
         span: Span,
     ) -> Result<Box<Expr>, ParseError> {
+        check_html_like_comments(raw, syntax, span)?;
+
         let lexer = Lexer::new(
             syntax,
             // EsVersion defaults to es5
@@ -154,6 +179,8 @@ impl SfcParser<'_, '_, '_> {
     }
 
     pub fn parse_pat(&mut self, raw: &str, syntax: Syntax, span: Span) -> Result<Pat, ParseError> {
+        check_html_like_comments(raw, syntax, span)?;
+
         let lexer = Lexer::new(
             syntax,
             // EsVersion defaults to es5
@@ -172,3 +199,46 @@ impl SfcParser<'_, '_, '_> {
         parse_result.map_err(From::from)
     }
 }
+
+/// Rejects HTML-like comments (`<!--`, or `-->` at the start of a line).
+/// They are not allowed in module code, and SWC parser panics in debug builds
+/// when such a comment is followed by a regular expression.
+///
+/// The comments are taken from the SWC lexer, which records them as line comments
+/// not starting with `//`. Lexing is skipped when the source cannot contain them.
+fn check_html_like_comments(raw: &str, syntax: Syntax, span: Span) -> Result<(), ParseError> {
+    if !raw.contains("<!--") && !raw.contains("-->") {
+        return Ok(());
+    }
+
+    let comments = SingleThreadedComments::default();
+    let lexer = Lexer::new(
+        syntax,
+        Default::default(),
+        StringInput::new(raw, span.lo, span.hi),
+        Some(&comments),
+    );
+    lexer.for_each(drop);
+
+    let (leading, trailing) = comments.take_all();
+    let leading = leading.borrow();
+    let trailing = trailing.borrow();
+
+    let html_like_comment = leading
+        .values()
+        .chain(trailing.values())
+        .flatten()
+        .filter(|comment| comment.kind == CommentKind::Line)
+        .find(|comment| {
+            let offset = (comment.span.lo - span.lo).0 as usize;
+            !raw.get(offset..).is_some_and(|rest| rest.starts_with("//"))
+        });
+
+    match html_like_comment {
+        Some(comment) => Err(ParseError {
+            kind: ParseErrorKind::EcmaSyntaxError(Box::new(SyntaxError::LegacyCommentInModule)),
+            span: comment.span,
+        }),
+        None => Ok(()),
+    }
+}
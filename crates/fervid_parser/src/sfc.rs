@@ -1,34 +1,28 @@
-use fervid_core::{fervid_atom, SfcDescriptor};
+use fervid_core::{fervid_atom, AttributeOrBinding, SfcDescriptor, SfcTemplateBlock};
 use swc_core::common::{BytePos, Span, Spanned, DUMMY_SP};
 use swc_ecma_parser::StringInput;
-use swc_html_ast::{Child, DocumentFragment, DocumentMode, Element, Namespace};
+use swc_html_ast::{Attribute, Child, DocumentFragment, DocumentMode, Element, Namespace};
 use swc_html_parser::{
+    error::ErrorKind as HtmlErrorKind,
     lexer::Lexer,
     parser::{Parser, ParserConfig},
 };
 
 use crate::{
     error::{ParseError, ParseErrorKind},
-    SfcParser,
+    ParseMode, SfcParser,
 };
 
 type SwcHtmlParserError = swc_html_parser::error::Error;
 
 impl SfcParser<'_, '_, '_> {
     /// Parses `self.input` as an SFC, producing an `SfcDescriptor`.
-    /// When `Err(ParseError)` is returned, that means unrecoverable error was discovered.
+    /// When `Err(ParseError)` is returned, that means unrecoverable error was discovered,
+    /// or an error was found in [`ParseMode::HardFailOnError`] or [`ParseMode::SoftReportAll`] modes.
     pub fn parse_sfc(&mut self) -> Result<SfcDescriptor, ParseError> {
-        let parsed_html = self.parse_html_document_fragment().map_err(|e| {
-            let kind = e.into_inner().1;
+        self.errors_start = self.errors.len();
 
-            ParseError {
-                kind: ParseErrorKind::InvalidHtml(Box::new(kind)),
-                span: Span {
-                    lo: BytePos(1),
-                    hi: BytePos(self.input.len() as u32),
-                },
-            }
-        })?;
+        let parsed_html = self.parse_root_fragment()?;
 
         let mut sfc_descriptor = SfcDescriptor::default();
 
@@ -42,6 +36,10 @@ impl SfcParser<'_, '_, '_> {
         }
 
         for root_node in parsed_html.children.into_iter() {
+            if let Some(fatal_error) = self.take_fatal_error(root_node.span_lo()) {
+                return Err(fatal_error);
+            }
+
             // Only root elements are supported
             let Child::Element(root_element) = root_node else {
                 continue;
@@ -57,13 +55,7 @@ impl SfcParser<'_, '_, '_> {
                     continue;
                 }
 
-                let template_result = self.parse_template_to_ir(root_element);
-                if template_result.is_none() {
-                    // TODO Error
-                    continue;
-                };
-
-                sfc_descriptor.template = template_result;
+                sfc_descriptor.template = Some(self.parse_template_to_ir(root_element));
             } else if tag_name.eq("script") {
                 let sfc_script_block = match self.parse_sfc_script_element(root_element) {
                     Ok(Some(v)) => v,
@@ -111,16 +103,112 @@ impl SfcParser<'_, '_, '_> {
             });
         }
 
+        if let Some(fatal_error) = self.take_fatal_error(BytePos(u32::MAX)) {
+            return Err(fatal_error);
+        }
+        if matches!(self.mode, ParseMode::SoftReportAll) && self.errors.len() > self.errors_start {
+            return Err(self.errors.remove(self.errors_start));
+        }
+
         Ok(sfc_descriptor)
     }
 
+    /// Parses `self.input` as the contents of a `<template>`, i.e. without the SFC blocks around it.
+    /// This is used for the templates which are not a part of an SFC,
+    /// e.g. `template: '<div>{{ msg }}</div>'` in a component options object.
+    /// Error handling is the same as in [`SfcParser::parse_sfc`].
+    pub fn parse_template(&mut self) -> Result<SfcTemplateBlock, ParseError> {
+        self.errors_start = self.errors.len();
+
+        let parsed_html = self.parse_root_fragment()?;
+
+        // Wrap the nodes into a synthetic `<template>` to reuse the SFC logic
+        let root_element = Element {
+            span: parsed_html.span,
+            tag_name: fervid_atom!("template"),
+            namespace: Namespace::HTML,
+            attributes: vec![],
+            children: parsed_html.children,
+            content: None,
+            is_self_closing: false,
+        };
+
+        let template_block = self.parse_template_to_ir(root_element);
+
+        if let Some(fatal_error) = self.take_fatal_error(BytePos(u32::MAX)) {
+            return Err(fatal_error);
+        }
+        if matches!(self.mode, ParseMode::SoftReportAll) && self.errors.len() > self.errors_start {
+            return Err(self.errors.remove(self.errors_start));
+        }
+
+        Ok(template_block)
+    }
+
+    /// Parses only the `<template>` block of the SFC in `self.input`, e.g. to parse it again
+    /// with the `delimiters` of the component, which are only known from its `<script>`.
+    /// The other blocks are skipped, and so are the errors outside of the `<template>`.
+    /// Error handling is the same as in [`SfcParser::parse_sfc`].
+    pub fn parse_sfc_template(&mut self) -> Result<Option<SfcTemplateBlock>, ParseError> {
+        self.errors_start = self.errors.len();
+
+        let parsed_html = self.parse_root_fragment()?;
+
+        let template_element =
+            parsed_html
+                .children
+                .into_iter()
+                .find_map(|root_node| match root_node {
+                    Child::Element(root_element) if root_element.tag_name.eq("template") => {
+                        Some(root_element)
+                    }
+                    _ => None,
+                });
+
+        // The errors of the other blocks were already reported when parsing the SFC
+        let template_span = template_element.as_ref().map(|element| element.span);
+        let errors_start = self.errors_start;
+        let mut idx = 0;
+        self.errors.retain(|e| {
+            idx += 1;
+            idx <= errors_start
+                || template_span.is_some_and(|template_span| template_span.contains(e.span))
+        });
+
+        let template_block = template_element.map(|element| self.parse_template_to_ir(element));
+
+        if let Some(fatal_error) = self.take_fatal_error(BytePos(u32::MAX)) {
+            return Err(fatal_error);
+        }
+        if matches!(self.mode, ParseMode::SoftReportAll) && self.errors.len() > self.errors_start {
+            return Err(self.errors.remove(self.errors_start));
+        }
+
+        Ok(template_block)
+    }
+
+    /// Parses `self.input` as a document fragment, an error here is unrecoverable
+    fn parse_root_fragment(&mut self) -> Result<DocumentFragment, ParseError> {
+        self.parse_html_document_fragment().map_err(|e| {
+            let kind = e.into_inner().1;
+
+            ParseError {
+                kind: ParseErrorKind::InvalidHtml(Box::new(kind)),
+                span: Span {
+                    lo: BytePos(1),
+                    hi: BytePos((self.input.len() + 1) as u32),
+                },
+            }
+        })
+    }
+
     /// Adapted from `swc_html_parser`
     #[inline]
     pub fn parse_html_document_fragment(&mut self) -> Result<DocumentFragment, SwcHtmlParserError> {
         let lexer = Lexer::new(StringInput::new(
             self.input,
             BytePos(1),
-            BytePos(self.input.len() as u32),
+            BytePos((self.input.len() + 1) as u32),
         ));
 
         let parser_config = ParserConfig {
@@ -148,6 +236,16 @@ impl SfcParser<'_, '_, '_> {
         self.errors.reserve(html_parse_errors.len());
         for html_parse_error in html_parse_errors {
             let e = html_parse_error.into_inner();
+
+            // Self-closing tags are valid in Vue templates, e.g. `<MyComp />` or `<div />`.
+            // The parser already handles them with `allow_self_closing`, but SWC still reports them
+            if matches!(
+                e.1,
+                HtmlErrorKind::NonVoidHtmlElementStartTagWithTrailingSolidus
+            ) {
+                continue;
+            }
+
             self.errors.push(ParseError {
                 kind: ParseErrorKind::InvalidHtml(Box::new(e.1)),
                 span: e.0,
@@ -209,4 +307,49 @@ impl SfcParser<'_, '_, '_> {
     pub fn report_error(&mut self, error: ParseError) {
         self.errors.push(error);
     }
+
+    /// Whether parsing must stop, i.e. an error was reported in [`ParseMode::HardFailOnError`]
+    #[inline]
+    pub fn should_abort(&self) -> bool {
+        matches!(self.mode, ParseMode::HardFailOnError) && self.errors.len() > self.errors_start
+    }
+
+    /// Whether the offending nodes should be kept as placeholders instead of being skipped
+    #[inline]
+    pub fn keeps_placeholders(&self) -> bool {
+        matches!(self.mode, ParseMode::SoftRecoverUnsafe)
+    }
+
+    /// When parsing must stop, takes the first (by position) error reported during the current parsing
+    /// and drops the rest, because they were discovered after the parser should have stopped.
+    /// Only errors located before `before` are considered, as the errors after it
+    /// may still be discarded (e.g. HTML errors inside a `<template lang="pug">`).
+    fn take_fatal_error(&mut self, before: BytePos) -> Option<ParseError> {
+        if !self.should_abort() {
+            return None;
+        }
+
+        let (fatal_error_idx, _) = self.errors[self.errors_start..]
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.span.lo < before)
+            .min_by_key(|(_, e)| e.span.lo)?;
+
+        let fatal_error = self.errors.remove(self.errors_start + fatal_error_idx);
+        self.errors.truncate(self.errors_start);
+        Some(fatal_error)
+    }
+}
+
+/// Keeps the attributes of an SFC block as written.
+/// Attributes without a value (e.g. `scoped`) get an empty one
+pub(crate) fn raw_attributes(attributes: &[Attribute]) -> Vec<AttributeOrBinding> {
+    attributes
+        .iter()
+        .map(|attr| AttributeOrBinding::RegularAttribute {
+            name: attr.name.to_owned(),
+            value: attr.value.to_owned().unwrap_or_else(|| fervid_atom!("")),
+            span: attr.span,
+        })
+        .collect()
 }
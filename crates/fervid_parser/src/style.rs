@@ -1,7 +1,7 @@
 use fervid_core::{fervid_atom, SfcStyleBlock};
 use swc_html_ast::{Child, Element};
 
-use crate::{error::ParseErrorKind, ParseError, SfcParser};
+use crate::{error::ParseErrorKind, sfc::raw_attributes, ParseError, SfcParser};
 
 impl SfcParser<'_, '_, '_> {
     pub fn parse_sfc_style_element(&mut self, mut element: Element) -> Option<SfcStyleBlock> {
@@ -10,6 +10,7 @@ impl SfcParser<'_, '_, '_> {
         let mut is_scoped = false;
         let mut is_module = false;
 
+        let attrs = raw_attributes(&element.attributes);
         for attr in element.attributes.into_iter() {
             if attr.name.eq("lang") {
                 let Some(attr_val) = attr.value else {
@@ -43,7 +44,9 @@ impl SfcParser<'_, '_, '_> {
                     content: fervid_atom!(""),
                     is_scoped,
                     is_module,
+                    attrs,
                     span: element.span,
+                    source_map: None,
                 });
             }
         };
@@ -58,7 +61,9 @@ impl SfcParser<'_, '_, '_> {
             content: style_content.data,
             is_scoped,
             is_module,
+            attrs,
             span: style_content.span,
+            source_map: None,
         })
     }
 }
@@ -5,16 +5,20 @@ use fervid_core::{
 use swc_core::common::{BytePos, Span};
 use swc_ecma_parser::{Syntax, TsSyntax};
 use swc_html_ast::{Child, Element, Text};
+use swc_html_parser::error::ErrorKind as HtmlErrorKind;
 
-use crate::SfcParser;
+use crate::{
+    error::{ParseError, ParseErrorKind},
+    sfc::raw_attributes,
+    SfcParser,
+};
 
 impl SfcParser<'_, '_, '_> {
-    pub fn parse_template_to_ir(&mut self, root_element: Element) -> Option<SfcTemplateBlock> {
-        // TODO Errors in template
-
+    pub fn parse_template_to_ir(&mut self, root_element: Element) -> SfcTemplateBlock {
         let lang_atom = fervid_atom!("lang");
         let html_atom = || fervid_atom!("html");
 
+        let attrs = raw_attributes(&root_element.attributes);
         let lang = root_element
             .attributes
             .into_iter()
@@ -47,11 +51,12 @@ impl SfcParser<'_, '_, '_> {
                 vec![]
             };
 
-            return Some(SfcTemplateBlock {
+            return SfcTemplateBlock {
                 lang,
                 roots,
+                attrs,
                 span: root_element.span,
-            });
+            };
         }
 
         // <template> technically has a `content`
@@ -60,14 +65,19 @@ impl SfcParser<'_, '_, '_> {
             .map(|c| c.children)
             .unwrap_or_else(|| root_element.children);
 
-        Some(SfcTemplateBlock {
+        SfcTemplateBlock {
             lang,
             roots: self.process_element_children(children),
+            attrs,
             span: root_element.span,
-        })
+        }
     }
 
-    fn process_element(&mut self, element: Element) -> Node {
+    fn process_element(&mut self, mut element: Element) -> Node {
+        if !self.keeps_placeholders() {
+            self.remove_malformed_attributes(&mut element);
+        }
+
         let children: Vec<Child> = element
             .content
             .map(|c| c.children)
@@ -128,8 +138,15 @@ impl SfcParser<'_, '_, '_> {
         let mut out = Vec::with_capacity(children.len());
 
         for child in children {
+            if self.should_abort() {
+                break;
+            }
+
             match child {
-                Child::DocumentType(_) => unimplemented!("Doctype is unsupported"),
+                Child::DocumentType(doctype) => self.report_error(ParseError {
+                    kind: ParseErrorKind::UnexpectedDoctype,
+                    span: doctype.span,
+                }),
                 Child::Element(element) => out.push(self.process_element(element)),
                 Child::Text(text) => self.process_text(text, &mut out),
                 Child::Comment(comment) => out.push(Node::Comment(comment.data, comment.span)),
@@ -167,7 +184,13 @@ impl SfcParser<'_, '_, '_> {
             let Some(interpolation_end_idx) =
                 raw[interpolation_start_idx..].find(interpolation_end_pat)
             else {
-                continue;
+                // Unclosed interpolation is kept as text
+                let offset = span.lo.0 + match_idx as u32;
+                self.report_error(ParseError {
+                    kind: ParseErrorKind::MissingInterpolationEnd,
+                    span: Span::new(BytePos(offset), span.hi),
+                });
+                break;
             };
 
             // Offset, because we did offset while `find`ing previously
@@ -207,7 +230,19 @@ impl SfcParser<'_, '_, '_> {
                     patch_flag: false,
                     span: interpolation_span,
                 })),
-                Err(expr_err) => self.report_error(expr_err),
+                Err(expr_err) => {
+                    self.report_error(expr_err);
+
+                    // Keep the whole `{{ bad }}` as a placeholder text
+                    if self.keeps_placeholders() {
+                        let offset = span.lo.0 + match_idx as u32;
+                        let placeholder = &raw[match_idx..text_start_idx];
+                        out.push(Node::Text(
+                            FervidAtom::from(placeholder),
+                            Span::new(BytePos(offset), BytePos(offset + placeholder.len() as u32)),
+                        ));
+                    }
+                }
             }
         }
 
@@ -274,6 +309,68 @@ impl SfcParser<'_, '_, '_> {
         //     out.push(Node::Text(FervidAtom::from(remaining), new_span));
         // }
     }
+
+    /// Removes the attributes in which the HTML parser reported an error,
+    /// e.g. a duplicate attribute or a missing whitespace before an attribute.
+    /// The errors are already reported, and the element itself is kept
+    fn remove_malformed_attributes(&self, element: &mut Element) {
+        // Elements implied by the HTML parser do not have a starting tag
+        if element.span.is_dummy() || element.span.lo.0 == 0 || element.attributes.is_empty() {
+            return;
+        }
+
+        let lo = element.span.lo;
+        let search_from = element
+            .attributes
+            .iter()
+            .map(|attr| attr.span.hi.0)
+            .max()
+            .unwrap_or(lo.0 + element.tag_name.len() as u32);
+
+        // `-1` is needed because SWC spans start from 1, and `+2` includes the `>`
+        let search_idx = search_from.saturating_sub(1) as usize;
+        let hi = self
+            .input
+            .get(search_idx..)
+            .and_then(|rest| rest.find('>'))
+            .map_or(element.span.hi, |idx| {
+                BytePos((search_idx + idx + 2) as u32)
+            });
+        let starting_tag_span = Span::new(lo, hi);
+
+        let error_positions: Vec<BytePos> = self.errors[self.errors_start..]
+            .iter()
+            .filter(|e| {
+                let ParseErrorKind::InvalidHtml(ref kind) = e.kind else {
+                    return false;
+                };
+
+                // Self-closing tags and other quirks are fine in Vue templates,
+                // only the errors which make the attributes unreliable are considered
+                matches!(
+                    **kind,
+                    HtmlErrorKind::DuplicateAttribute
+                        | HtmlErrorKind::EofInTag
+                        | HtmlErrorKind::MissingAttributeValue
+                        | HtmlErrorKind::MissingWhitespaceBetweenAttributes
+                        | HtmlErrorKind::UnexpectedCharacterInAttributeName
+                        | HtmlErrorKind::UnexpectedCharacterInUnquotedAttributeValue
+                        | HtmlErrorKind::UnexpectedEqualsSignBeforeAttributeName
+                        | HtmlErrorKind::UnexpectedSolidusInTag
+                ) && starting_tag_span.lo <= e.span.lo
+                    && e.span.lo < starting_tag_span.hi
+            })
+            .map(|e| e.span.lo)
+            .collect();
+
+        // An error points inside the attribute it is about,
+        // e.g. to the second `a` in `a="1" a="2"`, or to `b` in `a="1"b="2"`
+        element.attributes.retain(|attr| {
+            !error_positions
+                .iter()
+                .any(|pos| attr.span.lo <= *pos && *pos < attr.span.hi)
+        });
+    }
 }
 
 #[cfg(test)]
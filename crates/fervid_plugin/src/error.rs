@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use fervid_core::error::{ErrorCode, Severity, SeverityLevel};
+
+#[derive(Debug)]
+pub enum PluginError {
+    /// The library could not be loaded, or it does not export the plugin entry
+    Load { path: PathBuf, message: String },
+    /// The plugin was built with a different `fervid_plugin_api`, `fervid_core` or `swc_core`
+    Incompatible {
+        path: PathBuf,
+        expected: String,
+        found: String,
+    },
+    /// A hook of the plugin panicked
+    Panicked { plugin: String, hook: &'static str },
+    /// The IR could not be passed to or received from a hook of the plugin
+    InvalidIr {
+        plugin: String,
+        hook: &'static str,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginError::Load { path, message } => {
+                write!(f, "Failed to load plugin {}: {}", path.display(), message)
+            }
+            PluginError::Incompatible {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Plugin {} is incompatible, expected API {}, found {}. Rebuild the plugin with the same fervid version.",
+                path.display(),
+                expected,
+                found
+            ),
+            PluginError::Panicked { plugin, hook } => {
+                write!(f, "Plugin `{}` panicked in `{}`", plugin, hook)
+            }
+            PluginError::InvalidIr {
+                plugin,
+                hook,
+                message,
+            } => write!(
+                f,
+                "Plugin `{}` received or returned an invalid IR in `{}`: {}",
+                plugin, hook, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+impl Severity for PluginError {
+    fn get_severity(&self) -> SeverityLevel {
+        SeverityLevel::UnrecoverableError
+    }
+}
+
+impl ErrorCode for PluginError {
+    fn error_code(&self) -> &'static str {
+        match self {
+            PluginError::Load { .. } => "PLUGIN_LOAD",
+            PluginError::Incompatible { .. } => "PLUGIN_INCOMPATIBLE",
+            PluginError::Panicked { .. } => "PLUGIN_PANICKED",
+            PluginError::InvalidIr { .. } => "PLUGIN_INVALID_IR",
+        }
+    }
+}
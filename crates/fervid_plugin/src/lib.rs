@@ -0,0 +1,238 @@
+//! Loads the `fervid` plugins from dynamic libraries (`.so`, `.dll` and `.dylib`).
+//! For authoring a plugin, see [`fervid_plugin_api`].
+
+#[macro_use]
+extern crate lazy_static;
+
+mod error;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use fervid_core::SfcDescriptor;
+use fervid_plugin_api::{
+    from_ir, to_ir, HookFn, HookStatus, PluginVTable, RawBuffer, RawStr, TransformedSfc,
+    IR_VERSION, PLUGIN_API_VERSION,
+};
+use fervid_transform::TransformSfcResult;
+use fxhash::FxHashMap as HashMap;
+use serde::{de::DeserializeOwned, Serialize};
+use swc_core::{common::util::take::Take, ecma::ast::Module};
+
+pub use error::PluginError;
+
+lazy_static! {
+    /// Plugins are loaded once per process and are never unloaded
+    static ref LOADED_PLUGINS: Mutex<HashMap<PathBuf, Arc<DynamicPlugin>>> =
+        Mutex::new(HashMap::default());
+}
+
+/// A plugin loaded from a dynamic library
+pub struct DynamicPlugin {
+    vtable: PluginVTable,
+    path: PathBuf,
+    name: String,
+    /// Must outlive the `vtable`, as it contains the code
+    #[cfg(any(unix, windows))]
+    _library: libloading::Library,
+}
+
+// SAFETY: `fervid_plugin_api::Plugin` requires `Send + Sync`
+unsafe impl Send for DynamicPlugin {}
+unsafe impl Sync for DynamicPlugin {}
+
+/// Loads a plugin from the library at `path`, or returns the one which was already loaded.
+///
+/// Note that loading a library runs its initialization code,
+/// so only the plugins from the trusted sources must be used.
+pub fn load_plugin(path: &Path) -> Result<Arc<DynamicPlugin>, PluginError> {
+    let mut loaded_plugins = LOADED_PLUGINS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(plugin) = loaded_plugins.get(path) {
+        return Ok(plugin.clone());
+    }
+
+    // SAFETY: the caller trusts the library, and its ABI is checked by `DynamicPlugin::load`
+    let plugin = Arc::new(unsafe { DynamicPlugin::load(path)? });
+    loaded_plugins.insert(path.to_path_buf(), plugin.clone());
+
+    Ok(plugin)
+}
+
+impl DynamicPlugin {
+    /// Loads a plugin from the library at `path`.
+    ///
+    /// # Safety
+    /// The library runs arbitrary code when loaded.
+    /// It must export a function declared by [`fervid_plugin_api::declare_plugin!`].
+    #[cfg(any(unix, windows))]
+    pub unsafe fn load(path: &Path) -> Result<DynamicPlugin, PluginError> {
+        use fervid_plugin_api::{PluginEntry, PLUGIN_ENTRY_SYMBOL};
+
+        let load_error = |error: libloading::Error| PluginError::Load {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        };
+
+        let library = libloading::Library::new(path).map_err(load_error)?;
+        let entry = library
+            .get::<PluginEntry>(PLUGIN_ENTRY_SYMBOL)
+            .map_err(load_error)?;
+        let vtable = entry();
+
+        let incompatible = |library: libloading::Library, found: String| {
+            // The plugin is leaked, because its `drop` cannot be trusted either
+            std::mem::forget(library);
+
+            Err(PluginError::Incompatible {
+                path: path.to_path_buf(),
+                expected: format!("{} ({})", PLUGIN_API_VERSION, *IR_VERSION),
+                found,
+            })
+        };
+
+        // Everything else in the vtable, including `ir_version`, can only be trusted after this check
+        if vtable.api_version != PLUGIN_API_VERSION {
+            return incompatible(library, vtable.api_version.to_string());
+        }
+
+        let ir_version = vtable.ir_version.as_str();
+        if ir_version != *IR_VERSION {
+            return incompatible(library, format!("{} ({})", vtable.api_version, ir_version));
+        }
+
+        let name = (vtable.name)(vtable.instance).as_str().to_string();
+
+        Ok(DynamicPlugin {
+            vtable,
+            path: path.to_path_buf(),
+            name,
+            _library: library,
+        })
+    }
+
+    /// Loads a plugin from the library at `path`.
+    ///
+    /// # Safety
+    /// Dynamic libraries are not supported on this target, so this always errors.
+    #[cfg(not(any(unix, windows)))]
+    pub unsafe fn load(path: &Path) -> Result<DynamicPlugin, PluginError> {
+        Err(PluginError::Load {
+            path: path.to_path_buf(),
+            message: "Dynamic libraries are not supported on this target".to_string(),
+        })
+    }
+
+    /// Name of the plugin, as reported by the plugin itself
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Path of the library the plugin was loaded from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Calls [`fervid_plugin_api::Plugin::on_parse`]
+    pub fn on_parse(&self, sfc: &mut SfcDescriptor) -> Result<(), PluginError> {
+        self.call_hook(self.vtable.on_parse, "on_parse", sfc)
+    }
+
+    /// Calls [`fervid_plugin_api::Plugin::on_transform`]
+    pub fn on_transform(
+        &self,
+        transform_result: &mut TransformSfcResult,
+    ) -> Result<(), PluginError> {
+        // The fields are moved out and back in, only `bindings_helper` and `deps` stay on the host
+        let mut transformed_sfc = TransformedSfc {
+            template_block: transform_result.template_block.take(),
+            module: transform_result.module.take(),
+            exported_obj: transform_result.exported_obj.take(),
+            setup_fn: transform_result.setup_fn.take(),
+            style_blocks: std::mem::take(&mut transform_result.style_blocks),
+            custom_blocks: std::mem::take(&mut transform_result.custom_blocks),
+        };
+
+        let result = self.call_hook(
+            self.vtable.on_transform,
+            "on_transform",
+            &mut transformed_sfc,
+        );
+
+        transform_result.template_block = transformed_sfc.template_block;
+        transform_result.module = transformed_sfc.module;
+        transform_result.exported_obj = transformed_sfc.exported_obj;
+        transform_result.setup_fn = transformed_sfc.setup_fn;
+        transform_result.style_blocks = transformed_sfc.style_blocks;
+        transform_result.custom_blocks = transformed_sfc.custom_blocks;
+
+        result
+    }
+
+    /// Calls [`fervid_plugin_api::Plugin::on_module`]
+    pub fn on_module(&self, module: &mut Module) -> Result<(), PluginError> {
+        self.call_hook(self.vtable.on_module, "on_module", module)
+    }
+
+    /// Passes the serialized `value` to the hook and replaces it with the hook output.
+    /// The `value` is left as is when the hook fails
+    fn call_hook<T: Serialize + DeserializeOwned>(
+        &self,
+        hook_fn: HookFn,
+        hook: &'static str,
+        value: &mut T,
+    ) -> Result<(), PluginError> {
+        let invalid_ir = |message: String| PluginError::InvalidIr {
+            plugin: self.name.to_owned(),
+            hook,
+            message,
+        };
+
+        let input = to_ir(value).map_err(|error| invalid_ir(error.to_string()))?;
+        let mut output = RawBuffer::empty();
+
+        // SAFETY: the vtable is checked to be compatible when loading
+        let status = unsafe { hook_fn(self.vtable.instance, RawStr::new(&input), &mut output) };
+        match status {
+            HookStatus::OK => {}
+            HookStatus::PANICKED => {
+                return Err(PluginError::Panicked {
+                    plugin: self.name.to_owned(),
+                    hook,
+                })
+            }
+            _ => {
+                return Err(invalid_ir(
+                    "the plugin could not process the IR".to_string(),
+                ))
+            }
+        }
+
+        // SAFETY: the buffer is written by the hook on success and is freed by the plugin right after
+        let result = from_ir::<T>(unsafe { output.as_str() });
+        unsafe { (self.vtable.free_buffer)(output) };
+
+        *value = result.map_err(|error| invalid_ir(error.to_string()))?;
+        Ok(())
+    }
+}
+
+impl Drop for DynamicPlugin {
+    fn drop(&mut self) {
+        // SAFETY: the instance is owned by the vtable and is not used after this
+        unsafe { (self.vtable.drop)(self.vtable.instance) }
+    }
+}
+
+impl std::fmt::Debug for DynamicPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicPlugin")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .finish()
+    }
+}
@@ -0,0 +1,37 @@
+use fervid_core::{SfcCustomBlock, SfcStyleBlock, SfcTemplateBlock};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use swc_core::ecma::ast::{Function, Module, ObjectLit};
+
+/// The part of the transformed SFC passed to [`crate::Plugin::on_transform`]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransformedSfc {
+    /// Transformed template block
+    pub template_block: Option<SfcTemplateBlock>,
+    /// Module obtained by processing `<script>` and `<script setup>`
+    pub module: Box<Module>,
+    /// Object exported from the `module`, but detached from it
+    pub exported_obj: ObjectLit,
+    /// Setup function (not linked to default export yet)
+    pub setup_fn: Option<Box<Function>>,
+    /// Transformed style blocks
+    pub style_blocks: Vec<SfcStyleBlock>,
+    /// Custom blocks
+    pub custom_blocks: Vec<SfcCustomBlock>,
+}
+
+/// Serializes the IR before passing it to the other side of the plugin boundary
+pub fn to_ir<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    serde_json::to_string(value)
+}
+
+/// Deserializes the IR received from the other side of the plugin boundary
+pub fn from_ir<T: DeserializeOwned>(ir: &str) -> serde_json::Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_str(ir);
+    // The IR is as deep as the template and the script are,
+    // and it was already serialized from the same types
+    deserializer.disable_recursion_limit();
+
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
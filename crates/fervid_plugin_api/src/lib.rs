@@ -0,0 +1,97 @@
+//! The interface between `fervid` and the plugins loaded from dynamic libraries (`.so`, `.dll` and `.dylib`).
+//!
+//! A plugin is a `cdylib` crate which implements [`Plugin`] and declares it using [`declare_plugin!`]:
+//!
+//! ```
+//! use fervid_plugin_api::{declare_plugin, Plugin};
+//! use fervid_core::SfcDescriptor;
+//!
+//! struct DropDocs;
+//!
+//! impl Plugin for DropDocs {
+//!     fn name(&self) -> &str {
+//!         "drop-docs"
+//!     }
+//!
+//!     fn on_parse(&self, sfc: &mut SfcDescriptor) {
+//!         sfc.custom_blocks
+//!             .retain(|block| block.starting_tag.tag_name != "docs");
+//!     }
+//! }
+//!
+//! declare_plugin!(DropDocs);
+//! # fn main() {}
+//! ```
+//!
+//! ## Stability
+//! The host and a plugin only share the C ABI of [`PluginVTable`]: no Rust type crosses the library boundary.
+//! The IR is serialized to JSON (see [`to_ir`] and [`from_ir`]) before it is passed to a hook,
+//! and the hook returns the modified IR as JSON in a buffer which is freed by the plugin itself.
+//! This way a plugin can be built with any compiler, panic strategy and allocator.
+//!
+//! The schema of the IR is defined by `fervid_core` and `swc_core`,
+//! which is checked when the plugin is loaded (see [`IR_VERSION`]).
+//!
+//! A panic in a plugin hook is caught and reported as an error only with `panic = "unwind"`.
+//! With `panic = "abort"` (e.g. in a release profile), it aborts the host process.
+
+#[macro_use]
+extern crate lazy_static;
+
+mod ir;
+mod vtable;
+
+use fervid_core::SfcDescriptor;
+use swc_core::ecma::ast::Module;
+
+pub use ir::{from_ir, to_ir, TransformedSfc};
+pub use vtable::{HookFn, HookStatus, PluginEntry, PluginVTable, RawBuffer, RawStr};
+
+/// Version of the plugin ABI, i.e. the layout of [`PluginVTable`] and the types of the hooks
+pub const PLUGIN_API_VERSION: u32 = 2;
+
+lazy_static! {
+    /// Plugin is only compatible with the host when both serialize the IR in the same way,
+    /// i.e. they are built with the same versions of `fervid_core` and `swc_core`.
+    ///
+    /// E.g. `fervid_core 0.2.0; swc_core 0.106.4`
+    pub static ref IR_VERSION: String = format!(
+        "fervid_core {}; swc_core {}",
+        fervid_core::VERSION,
+        swc_core::SWC_CORE_VERSION
+    );
+}
+
+/// Name of the function which every plugin library exports, see [`PluginEntry`]
+pub const PLUGIN_ENTRY_SYMBOL: &[u8] = b"fervid_plugin_entry\0";
+
+/// A plugin which hooks into the stages of the SFC compilation.
+/// All the hooks are optional and are called in this order:
+/// 1. [`Plugin::on_parse`] after the SFC is parsed;
+/// 2. [`Plugin::on_transform`] after the SFC is transformed;
+/// 3. [`Plugin::on_module`] before the final module is stringified.
+pub trait Plugin: Send + Sync {
+    /// Name of the plugin, e.g. for the errors
+    fn name(&self) -> &str;
+
+    /// Called with the `SfcDescriptor` produced by the parser
+    fn on_parse(&self, _sfc: &mut SfcDescriptor) {}
+
+    /// Called with the transformed template and script IR
+    fn on_transform(&self, _transformed_sfc: &mut TransformedSfc) {}
+
+    /// Called with the generated module, right before it is converted to code
+    fn on_module(&self, _module: &mut Module) {}
+}
+
+/// Declares the plugin entry of a `cdylib` crate.
+/// Accepts an expression which creates the plugin, e.g. `declare_plugin!(MyPlugin::new())`.
+#[macro_export]
+macro_rules! declare_plugin {
+    ($plugin: expr) => {
+        #[no_mangle]
+        pub extern "C" fn fervid_plugin_entry() -> $crate::PluginVTable {
+            $crate::PluginVTable::new($plugin)
+        }
+    };
+}
@@ -0,0 +1,299 @@
+use std::{
+    ffi::c_void,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{from_ir, to_ir, Plugin, IR_VERSION, PLUGIN_API_VERSION};
+
+/// Signature of the function exported by a plugin library under [`crate::PLUGIN_ENTRY_SYMBOL`]
+pub type PluginEntry = unsafe extern "C" fn() -> PluginVTable;
+
+/// Signature of the plugin hooks.
+/// A hook receives the IR serialized by the host in `input`,
+/// and writes the modified IR to `output` when it returns [`HookStatus::OK`]
+pub type HookFn = unsafe extern "C" fn(
+    instance: *const c_void,
+    input: RawStr,
+    output: *mut RawBuffer,
+) -> HookStatus;
+
+/// A borrowed string which can be passed between the host and a plugin
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RawStr {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl RawStr {
+    pub fn new(value: &str) -> Self {
+        RawStr {
+            ptr: value.as_ptr(),
+            len: value.len(),
+        }
+    }
+
+    /// # Safety
+    /// The string must have been created from a `&str` which is still alive
+    pub unsafe fn as_str<'a>(&self) -> &'a str {
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.ptr, self.len))
+    }
+}
+
+/// A string allocated by a plugin.
+/// The host never frees it itself, but passes it back to [`PluginVTable::free_buffer`]
+#[repr(C)]
+pub struct RawBuffer {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl RawBuffer {
+    pub fn empty() -> Self {
+        RawBuffer {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+            capacity: 0,
+        }
+    }
+
+    pub fn from_string(value: String) -> Self {
+        let mut bytes = std::mem::ManuallyDrop::new(value.into_bytes());
+        RawBuffer {
+            ptr: bytes.as_mut_ptr(),
+            len: bytes.len(),
+            capacity: bytes.capacity(),
+        }
+    }
+
+    /// # Safety
+    /// The buffer must have been created by [`RawBuffer::from_string`] and not freed yet
+    pub unsafe fn as_str<'a>(&self) -> &'a str {
+        if self.ptr.is_null() {
+            return "";
+        }
+
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.ptr, self.len))
+    }
+
+    /// # Safety
+    /// The buffer must have been created by [`RawBuffer::from_string`] in the same library
+    pub unsafe fn into_string(self) -> String {
+        if self.ptr.is_null() {
+            return String::new();
+        }
+
+        String::from_utf8_unchecked(Vec::from_raw_parts(self.ptr, self.len, self.capacity))
+    }
+}
+
+/// Result of a plugin hook
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HookStatus(pub u32);
+
+impl HookStatus {
+    /// The hook succeeded and wrote its output
+    pub const OK: HookStatus = HookStatus(0);
+    /// The hook panicked (only with `panic = "unwind"`, see the crate docs)
+    pub const PANICKED: HookStatus = HookStatus(1);
+    /// The plugin could not deserialize the input or serialize the output
+    pub const INVALID_IR: HookStatus = HookStatus(2);
+}
+
+/// A type-erased [`Plugin`] created by a plugin library.
+///
+/// The `instance` is owned by the vtable and is freed by calling `drop`.
+#[repr(C)]
+pub struct PluginVTable {
+    /// Must be equal to [`PLUGIN_API_VERSION`] of the host, checked before anything else is used
+    pub api_version: u32,
+    /// Must be equal to [`IR_VERSION`] of the host, only read when `api_version` matches
+    pub ir_version: RawStr,
+    pub instance: *mut c_void,
+    pub name: unsafe extern "C" fn(instance: *const c_void) -> RawStr,
+    /// Takes and returns a serialized `SfcDescriptor`
+    pub on_parse: HookFn,
+    /// Takes and returns a serialized [`crate::TransformedSfc`]
+    pub on_transform: HookFn,
+    /// Takes and returns a serialized `Module`
+    pub on_module: HookFn,
+    /// Frees the output of a hook
+    pub free_buffer: unsafe extern "C" fn(buffer: RawBuffer),
+    pub drop: unsafe extern "C" fn(instance: *mut c_void),
+}
+
+impl PluginVTable {
+    pub fn new<P: Plugin + 'static>(plugin: P) -> Self {
+        unsafe extern "C" fn name<P: Plugin>(instance: *const c_void) -> RawStr {
+            RawStr::new((*instance.cast::<P>()).name())
+        }
+
+        unsafe extern "C" fn on_parse<P: Plugin>(
+            instance: *const c_void,
+            input: RawStr,
+            output: *mut RawBuffer,
+        ) -> HookStatus {
+            let plugin = &*instance.cast::<P>();
+            call_hook(input, output, |sfc| plugin.on_parse(sfc))
+        }
+
+        unsafe extern "C" fn on_transform<P: Plugin>(
+            instance: *const c_void,
+            input: RawStr,
+            output: *mut RawBuffer,
+        ) -> HookStatus {
+            let plugin = &*instance.cast::<P>();
+            call_hook(input, output, |transformed_sfc| {
+                plugin.on_transform(transformed_sfc)
+            })
+        }
+
+        unsafe extern "C" fn on_module<P: Plugin>(
+            instance: *const c_void,
+            input: RawStr,
+            output: *mut RawBuffer,
+        ) -> HookStatus {
+            let plugin = &*instance.cast::<P>();
+            call_hook(input, output, |module| plugin.on_module(module))
+        }
+
+        unsafe extern "C" fn free_buffer(buffer: RawBuffer) {
+            std::mem::drop(buffer.into_string());
+        }
+
+        unsafe extern "C" fn drop<P: Plugin>(instance: *mut c_void) {
+            let plugin = Box::from_raw(instance.cast::<P>());
+            let _ = catch_unwind(AssertUnwindSafe(|| std::mem::drop(plugin)));
+        }
+
+        PluginVTable {
+            api_version: PLUGIN_API_VERSION,
+            ir_version: RawStr::new(IR_VERSION.as_str()),
+            instance: Box::into_raw(Box::new(plugin)).cast(),
+            name: name::<P>,
+            on_parse: on_parse::<P>,
+            on_transform: on_transform::<P>,
+            on_module: on_module::<P>,
+            free_buffer,
+            drop: drop::<P>,
+        }
+    }
+}
+
+/// Deserializes the input, calls the hook and serializes its output.
+/// Panics do not cross the library boundary.
+unsafe fn call_hook<T, F>(input: RawStr, output: *mut RawBuffer, hook: F) -> HookStatus
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce(&mut T),
+{
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let Ok(mut value) = from_ir::<T>(input.as_str()) else {
+            return HookStatus::INVALID_IR;
+        };
+
+        hook(&mut value);
+
+        let Ok(ir) = to_ir(&value) else {
+            return HookStatus::INVALID_IR;
+        };
+        *output = RawBuffer::from_string(ir);
+        HookStatus::OK
+    }));
+
+    result.unwrap_or(HookStatus::PANICKED)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use fervid_core::{SfcCustomBlock, SfcDescriptor, StartingTag};
+    use swc_core::{common::DUMMY_SP, ecma::ast::Module};
+
+    use super::*;
+
+    struct TestPlugin {
+        calls: AtomicUsize,
+    }
+
+    impl Plugin for TestPlugin {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn on_parse(&self, sfc: &mut SfcDescriptor) {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            sfc.custom_blocks.clear();
+        }
+
+        fn on_module(&self, _module: &mut Module) {
+            panic!("on_module panicked")
+        }
+    }
+
+    #[test]
+    fn it_calls_plugin_through_vtable() {
+        let vtable = PluginVTable::new(TestPlugin {
+            calls: AtomicUsize::new(0),
+        });
+        assert_eq!(PLUGIN_API_VERSION, vtable.api_version);
+
+        unsafe {
+            assert_eq!(*IR_VERSION, vtable.ir_version.as_str());
+            assert_eq!("test", (vtable.name)(vtable.instance).as_str());
+
+            let mut sfc = SfcDescriptor::default();
+            sfc.custom_blocks.push(SfcCustomBlock {
+                starting_tag: StartingTag {
+                    tag_name: "docs".into(),
+                    attributes: vec![],
+                    directives: None,
+                },
+                content: "".into(),
+                span: DUMMY_SP,
+            });
+            let input = to_ir(&sfc).unwrap();
+            let mut output = RawBuffer::empty();
+            assert_eq!(
+                HookStatus::OK,
+                (vtable.on_parse)(vtable.instance, RawStr::new(&input), &mut output)
+            );
+            let sfc: SfcDescriptor = from_ir(output.as_str()).unwrap();
+            (vtable.free_buffer)(output);
+            assert!(sfc.custom_blocks.is_empty());
+            assert_eq!(
+                1,
+                (*vtable.instance.cast::<TestPlugin>())
+                    .calls
+                    .load(Ordering::Relaxed)
+            );
+
+            // Invalid input is reported
+            let mut output = RawBuffer::empty();
+            assert_eq!(
+                HookStatus::INVALID_IR,
+                (vtable.on_parse)(vtable.instance, RawStr::new("{"), &mut output)
+            );
+
+            // Panics do not cross the library boundary
+            let module = Module {
+                span: DUMMY_SP,
+                body: vec![],
+                shebang: None,
+            };
+            let input = to_ir(&module).unwrap();
+            let mut output = RawBuffer::empty();
+            assert_eq!(
+                HookStatus::PANICKED,
+                (vtable.on_module)(vtable.instance, RawStr::new(&input), &mut output)
+            );
+
+            (vtable.drop)(vtable.instance);
+        }
+    }
+}
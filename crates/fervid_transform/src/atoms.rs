@@ -17,6 +17,7 @@ lazy_static! {
     pub static ref EXPOSE: FervidAtom = fervid_atom!("expose");
     pub static ref NAME: FervidAtom = fervid_atom!("name");
     pub static ref DIRECTIVES: FervidAtom = fervid_atom!("directives");
+    pub static ref DELIMITERS: FervidAtom = fervid_atom!("delimiters");
 
     // Composition API atoms
     // pub static ref COMPUTED: FervidAtom = fervid_atom!("computed");
@@ -1,10 +1,13 @@
+use fervid_core::error::{ErrorCode, Severity, SeverityLevel};
 use fervid_css::CssError;
 use swc_core::common::{Span, Spanned};
 
 #[derive(Debug)]
 pub enum TransformError {
     CssError(CssError),
-    ScriptError(ScriptError)
+    ScriptError(ScriptError),
+    TemplateError(TemplateError),
+    PluginError(PluginError),
 }
 
 #[derive(Debug)]
@@ -13,6 +16,56 @@ pub struct ScriptError {
     pub kind: ScriptErrorKind
 }
 
+#[derive(Debug)]
+pub struct TemplateError {
+    pub span: Span,
+    pub kind: TemplateErrorKind,
+}
+
+/// A diagnostic emitted by a [`crate::template::TemplatePlugin`]
+#[derive(Debug)]
+pub struct PluginError {
+    pub span: Span,
+    /// Name of the plugin, i.e. [`crate::template::TemplatePlugin::name`]
+    pub plugin: String,
+    /// Code of the diagnostic, e.g. `ICON_NOT_FOUND`
+    pub code: &'static str,
+    pub message: String,
+    pub severity: SeverityLevel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateErrorKind {
+    /// `v-if`, `v-else-if`, `v-else` or `v-for` on a `<template v-slot>`,
+    /// which needs `createSlots` to be generated
+    DynamicSlotsUnsupported,
+    /// A binding of type `PropsAliased` was used in a non-inline template,
+    /// but there is no information on the original prop name
+    PropsAliasedUnsupported,
+    /// `v-else` or `v-else-if` without a preceding `v-if` or `v-else-if`
+    VElseNoAdjacentIf,
+    /// `key` on a child of `<template v-for>` instead of the `<template>` itself
+    VForTemplateKeyPlacement,
+    /// `v-model` value is not something which can be assigned to,
+    /// e.g. `v-model="foo()"`
+    VModelMalformedExpression,
+    /// `v-model` on a binding which cannot be reassigned,
+    /// e.g. `SetupConst` or `SetupReactiveConst`
+    VModelOnConst,
+    /// `v-model` on a prop, which is readonly
+    VModelOnProps,
+    /// `v-model` on a variable declared by `v-for` or `v-slot`
+    VModelOnScopeVariable,
+    /// Two or more static slots with the same name on one component
+    VSlotDuplicateSlotNames,
+    /// Children outside of `<template v-slot>` when there is an explicit default slot
+    VSlotExtraneousDefaultSlotChildren,
+    /// `v-slot` on an element, or on a `<template>` which is not a direct child of a component
+    VSlotMisplaced,
+    /// `v-slot` on both the component and a nested `<template>`
+    VSlotMixedSlotUsage,
+}
+
 #[derive(Debug)]
 pub enum ScriptErrorKind {
     /// A compiler macro was imported, but it didn't need to
@@ -92,11 +145,305 @@ impl From<ScriptError> for TransformError {
     }
 }
 
+impl From<TemplateError> for TransformError {
+    fn from(value: TemplateError) -> Self {
+        TransformError::TemplateError(value)
+    }
+}
+
+impl From<PluginError> for TransformError {
+    fn from(value: PluginError) -> Self {
+        TransformError::PluginError(value)
+    }
+}
+
 impl Spanned for TransformError {
     fn span(&self) -> Span {
         match self {
             TransformError::CssError(e) => e.span,
             TransformError::ScriptError(e) => e.span,
+            TransformError::TemplateError(e) => e.span,
+            TransformError::PluginError(e) => e.span,
+        }
+    }
+}
+
+impl std::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::CssError(e) => e.fmt(f),
+            TransformError::ScriptError(e) => e.fmt(f),
+            TransformError::TemplateError(e) => e.fmt(f),
+            TransformError::PluginError(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Severity for TransformError {
+    fn get_severity(&self) -> SeverityLevel {
+        match self {
+            TransformError::CssError(e) => e.get_severity(),
+            TransformError::ScriptError(e) => e.get_severity(),
+            TransformError::TemplateError(e) => e.get_severity(),
+            TransformError::PluginError(e) => e.get_severity(),
+        }
+    }
+}
+
+impl ErrorCode for TransformError {
+    fn error_code(&self) -> &'static str {
+        match self {
+            TransformError::CssError(e) => e.error_code(),
+            TransformError::ScriptError(e) => e.error_code(),
+            TransformError::TemplateError(e) => e.error_code(),
+            TransformError::PluginError(e) => e.error_code(),
+        }
+    }
+}
+
+impl std::fmt::Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.plugin, self.message)
+    }
+}
+
+impl Severity for PluginError {
+    fn get_severity(&self) -> SeverityLevel {
+        self.severity
+    }
+}
+
+impl ErrorCode for PluginError {
+    fn error_code(&self) -> &'static str {
+        self.code
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl Severity for TemplateError {
+    fn get_severity(&self) -> SeverityLevel {
+        SeverityLevel::RecoverableError
+    }
+}
+
+impl ErrorCode for TemplateError {
+    fn error_code(&self) -> &'static str {
+        self.kind.error_code()
+    }
+}
+
+impl std::fmt::Display for TemplateErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            TemplateErrorKind::DynamicSlotsUnsupported => {
+                "Conditional and looped slots (`v-if`, `v-else-if`, `v-else` and `v-for` on `<template v-slot>`) are not supported yet."
+            }
+            TemplateErrorKind::PropsAliasedUnsupported => {
+                "Aliased props can only be used when the template is inlined into `setup`."
+            }
+            TemplateErrorKind::VElseNoAdjacentIf => {
+                "v-else/v-else-if has no adjacent v-if or v-else-if."
+            }
+            TemplateErrorKind::VForTemplateKeyPlacement => {
+                "<template v-for> key should be placed on the <template> tag."
+            }
+            TemplateErrorKind::VModelMalformedExpression => {
+                "v-model value must be a valid JavaScript member expression."
+            }
+            TemplateErrorKind::VModelOnConst => {
+                "v-model cannot be used on a const binding because it is not writable."
+            }
+            TemplateErrorKind::VModelOnProps => {
+                "v-model cannot be used on a prop, because local prop bindings are not writable.\nUse a v-bind binding combined with a v-on listener that emits update:x event instead."
+            }
+            TemplateErrorKind::VModelOnScopeVariable => {
+                "v-model cannot be used on v-for or v-slot scope variables because they are not writable."
+            }
+            TemplateErrorKind::VSlotDuplicateSlotNames => "Duplicate slot names found.",
+            TemplateErrorKind::VSlotExtraneousDefaultSlotChildren => {
+                "Extraneous children found when component already has explicitly named default slot. These children will be ignored."
+            }
+            TemplateErrorKind::VSlotMisplaced => {
+                "v-slot can only be used on components or <template> tags."
+            }
+            TemplateErrorKind::VSlotMixedSlotUsage => {
+                "Mixed v-slot usage on both the component and nested <template>. When there are multiple named slots, all slots should use <template> syntax to avoid scope ambiguity."
+            }
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl ErrorCode for TemplateErrorKind {
+    fn error_code(&self) -> &'static str {
+        match self {
+            TemplateErrorKind::DynamicSlotsUnsupported => "X_DYNAMIC_SLOTS_UNSUPPORTED",
+            TemplateErrorKind::PropsAliasedUnsupported => "X_PROPS_ALIASED_UNSUPPORTED",
+            TemplateErrorKind::VElseNoAdjacentIf => "X_V_ELSE_NO_ADJACENT_IF",
+            TemplateErrorKind::VForTemplateKeyPlacement => "X_V_FOR_TEMPLATE_KEY_PLACEMENT",
+            TemplateErrorKind::VModelMalformedExpression => "X_V_MODEL_MALFORMED_EXPRESSION",
+            TemplateErrorKind::VModelOnConst => "X_V_MODEL_ON_CONST",
+            TemplateErrorKind::VModelOnProps => "X_V_MODEL_ON_PROPS",
+            TemplateErrorKind::VModelOnScopeVariable => "X_V_MODEL_ON_SCOPE_VARIABLE",
+            TemplateErrorKind::VSlotDuplicateSlotNames => "X_V_SLOT_DUPLICATE_SLOT_NAMES",
+            TemplateErrorKind::VSlotExtraneousDefaultSlotChildren => {
+                "X_V_SLOT_EXTRANEOUS_DEFAULT_SLOT_CHILDREN"
+            }
+            TemplateErrorKind::VSlotMisplaced => "X_V_SLOT_MISPLACED",
+            TemplateErrorKind::VSlotMixedSlotUsage => "X_V_SLOT_MIXED_SLOT_USAGE",
+        }
+    }
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl Severity for ScriptError {
+    fn get_severity(&self) -> SeverityLevel {
+        match self.kind {
+            ScriptErrorKind::CompilerMacroImport => SeverityLevel::Warning,
+            _ => SeverityLevel::RecoverableError,
+        }
+    }
+}
+
+impl ErrorCode for ScriptError {
+    fn error_code(&self) -> &'static str {
+        self.kind.error_code()
+    }
+}
+
+impl std::fmt::Display for ScriptErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ScriptErrorKind::CompilerMacroImport => {
+                "Compiler macros are globally available and no longer need to be imported."
+            }
+            ScriptErrorKind::DefineEmitsMalformed => {
+                "Malformed defineEmits() type argument. Expected a type literal, an interface or a function type with at least one parameter."
+            }
+            ScriptErrorKind::DefineEmitsTypeAndNonTypeArguments => {
+                "defineEmits() cannot accept both type and non-type arguments at the same time. Use one or the other."
+            }
+            ScriptErrorKind::DefineEmitsMixedCallAndPropertySyntax => {
+                "defineEmits() type cannot mixed call signature and property syntax."
+            }
+            ScriptErrorKind::DefinePropsTypeAndNonTypeArguments => {
+                "defineProps() cannot accept both type and non-type arguments at the same time. Use one or the other."
+            }
+            ScriptErrorKind::DefineOptionsTypeArguments => {
+                "defineOptions() cannot accept type arguments."
+            }
+            ScriptErrorKind::DefineOptionsProps => {
+                "defineOptions() cannot be used to declare props. Use defineProps() instead."
+            }
+            ScriptErrorKind::DefineOptionsEmits => {
+                "defineOptions() cannot be used to declare emits. Use defineEmits() instead."
+            }
+            ScriptErrorKind::DefineOptionsExpose => {
+                "defineOptions() cannot be used to declare expose. Use defineExpose() instead."
+            }
+            ScriptErrorKind::DefineOptionsSlots => {
+                "defineOptions() cannot be used to declare slots. Use defineSlots() instead."
+            }
+            ScriptErrorKind::DefinePropsDestructureForbidden => {
+                "Props destructure is explicitly prohibited via config."
+            }
+            ScriptErrorKind::DefineSlotsArguments => "defineSlots() cannot accept arguments.",
+            ScriptErrorKind::DuplicateDefineEmits => "Duplicate defineEmits() call.",
+            ScriptErrorKind::DuplicateDefineModelName => "Duplicate defineModel() model name.",
+            ScriptErrorKind::DuplicateDefineProps => "Duplicate defineProps() call.",
+            ScriptErrorKind::DuplicateDefineOptions => "Duplicate defineOptions() call.",
+            ScriptErrorKind::DuplicateDefineSlots => "Duplicate defineSlots() call.",
+            ScriptErrorKind::DuplicateImport => "Different imports are using the same local name.",
+            ScriptErrorKind::ResolveTypeElementType => {
+                "Failed to resolve element type of an array type."
+            }
+            ScriptErrorKind::ResolveTypeExtendsBaseType => "Failed to resolve extends base type.",
+            ScriptErrorKind::ResolveTypeMissingTypeParam => "Missing type parameter.",
+            ScriptErrorKind::ResolveTypeMissingTypeParams => "Missing type parameters.",
+            ScriptErrorKind::ResolveTypeUnresolvable => "Unresolvable type.",
+            ScriptErrorKind::ResolveTypeUnresolvableIndexType => {
+                "Failed to resolve index type into finite keys."
+            }
+            ScriptErrorKind::ResolveTypeUnsupported => "Unsupported type in SFC macro.",
+            ScriptErrorKind::ResolveTypeUnsupportedIndexType => {
+                "Unsupported type when resolving index type."
+            }
+            ScriptErrorKind::ResolveTypeUnsupportedComputedKey => {
+                "Unsupported computed key in type referenced by a macro."
+            }
+            ScriptErrorKind::SetupExport => "<script setup> cannot contain ES module exports.",
+            ScriptErrorKind::WithDefaultsNeedsTypeOnlyDefineProps => {
+                "withDefaults() can only be used with type-based defineProps() declaration."
+            }
+            ScriptErrorKind::WithDefaultsWithoutDefineProps => {
+                "withDefaults() first argument must be a defineProps() call."
+            }
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl ErrorCode for ScriptErrorKind {
+    fn error_code(&self) -> &'static str {
+        match self {
+            ScriptErrorKind::CompilerMacroImport => "COMPILER_MACRO_IMPORT",
+            ScriptErrorKind::DefineEmitsMalformed => "DEFINE_EMITS_MALFORMED",
+            ScriptErrorKind::DefineEmitsTypeAndNonTypeArguments => {
+                "DEFINE_EMITS_TYPE_AND_NON_TYPE_ARGUMENTS"
+            }
+            ScriptErrorKind::DefineEmitsMixedCallAndPropertySyntax => {
+                "DEFINE_EMITS_MIXED_CALL_AND_PROPERTY_SYNTAX"
+            }
+            ScriptErrorKind::DefinePropsTypeAndNonTypeArguments => {
+                "DEFINE_PROPS_TYPE_AND_NON_TYPE_ARGUMENTS"
+            }
+            ScriptErrorKind::DefineOptionsTypeArguments => "DEFINE_OPTIONS_TYPE_ARGUMENTS",
+            ScriptErrorKind::DefineOptionsProps => "DEFINE_OPTIONS_PROPS",
+            ScriptErrorKind::DefineOptionsEmits => "DEFINE_OPTIONS_EMITS",
+            ScriptErrorKind::DefineOptionsExpose => "DEFINE_OPTIONS_EXPOSE",
+            ScriptErrorKind::DefineOptionsSlots => "DEFINE_OPTIONS_SLOTS",
+            ScriptErrorKind::DefinePropsDestructureForbidden => {
+                "DEFINE_PROPS_DESTRUCTURE_FORBIDDEN"
+            }
+            ScriptErrorKind::DefineSlotsArguments => "DEFINE_SLOTS_ARGUMENTS",
+            ScriptErrorKind::DuplicateDefineEmits => "DUPLICATE_DEFINE_EMITS",
+            ScriptErrorKind::DuplicateDefineModelName => "DUPLICATE_DEFINE_MODEL_NAME",
+            ScriptErrorKind::DuplicateDefineProps => "DUPLICATE_DEFINE_PROPS",
+            ScriptErrorKind::DuplicateDefineOptions => "DUPLICATE_DEFINE_OPTIONS",
+            ScriptErrorKind::DuplicateDefineSlots => "DUPLICATE_DEFINE_SLOTS",
+            ScriptErrorKind::DuplicateImport => "DUPLICATE_IMPORT",
+            ScriptErrorKind::ResolveTypeElementType => "RESOLVE_TYPE_ELEMENT_TYPE",
+            ScriptErrorKind::ResolveTypeExtendsBaseType => "RESOLVE_TYPE_EXTENDS_BASE_TYPE",
+            ScriptErrorKind::ResolveTypeMissingTypeParam => "RESOLVE_TYPE_MISSING_TYPE_PARAM",
+            ScriptErrorKind::ResolveTypeMissingTypeParams => "RESOLVE_TYPE_MISSING_TYPE_PARAMS",
+            ScriptErrorKind::ResolveTypeUnresolvable => "RESOLVE_TYPE_UNRESOLVABLE",
+            ScriptErrorKind::ResolveTypeUnresolvableIndexType => {
+                "RESOLVE_TYPE_UNRESOLVABLE_INDEX_TYPE"
+            }
+            ScriptErrorKind::ResolveTypeUnsupported => "RESOLVE_TYPE_UNSUPPORTED",
+            ScriptErrorKind::ResolveTypeUnsupportedIndexType => {
+                "RESOLVE_TYPE_UNSUPPORTED_INDEX_TYPE"
+            }
+            ScriptErrorKind::ResolveTypeUnsupportedComputedKey => {
+                "RESOLVE_TYPE_UNSUPPORTED_COMPUTED_KEY"
+            }
+            ScriptErrorKind::SetupExport => "SETUP_EXPORT",
+            ScriptErrorKind::WithDefaultsNeedsTypeOnlyDefineProps => {
+                "WITH_DEFAULTS_NEEDS_TYPE_ONLY_DEFINE_PROPS"
+            }
+            ScriptErrorKind::WithDefaultsWithoutDefineProps => "WITH_DEFAULTS_WITHOUT_DEFINE_PROPS",
         }
     }
 }
@@ -23,6 +23,9 @@ mod test_utils;
 
 pub use structs::*;
 
+/// Version of this crate, e.g. for checking the compatibility of the plugins
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Applies all the necessary transformations to the SFC.
 ///
 /// The transformations can be fine-tuned by using individual `transform_` functions.
@@ -45,7 +48,8 @@ pub fn transform_sfc<'o>(
     // Transform the template if it is present
     let mut template_block = None;
     if let Some(mut template) = sfc_descriptor.template {
-        transform_and_record_template(&mut template, &mut ctx.bindings_helper);
+        transform_and_record_template(&mut template, &mut ctx.bindings_helper, options.whitespace);
+        errors.append(&mut ctx.bindings_helper.errors);
         if !template.roots.is_empty() {
             template_block = Some(template);
         }
@@ -54,7 +58,12 @@ pub fn transform_sfc<'o>(
     // Transform scoped CSS
     let mut style_blocks = sfc_descriptor.styles;
     let scope = create_style_scope(&options.scope_id);
-    let had_scoped_blocks = transform_style_blocks(&mut style_blocks, &scope, errors);
+    let had_scoped_blocks = transform_style_blocks(
+        &mut style_blocks,
+        &scope,
+        options.style_source_map.as_ref(),
+        errors,
+    );
     if had_scoped_blocks {
         attach_scope_id(&mut transform_result, &scope);
     }
@@ -71,6 +80,7 @@ pub fn transform_sfc<'o>(
         template_block,
         style_blocks,
         custom_blocks: sfc_descriptor.custom_blocks,
+        deps: ctx.deps,
     }
 }
 
@@ -82,6 +92,13 @@ impl TransformSfcContext {
         // Create the bindings helper
         let mut bindings_helper = BindingsHelper::default();
         bindings_helper.is_prod = options.is_prod;
+        bindings_helper
+            .is_custom_element_tag
+            .clone_from(&options.is_custom_element_tag);
+        bindings_helper.comments = options.comments;
+        bindings_helper
+            .template_plugins
+            .clone_from(&options.template_plugins);
 
         // TS if any of scripts is TS.
         // Unlike the official compiler, we don't care if languages are mixed, because nothing changes.
@@ -108,6 +125,7 @@ impl TransformSfcContext {
             bindings_helper,
             deps: Default::default(),
             scopes: vec![],
+            file_scopes: Default::default(),
         }
     }
 
@@ -32,8 +32,11 @@ pub fn infer_name(exported_obj: &mut ObjectLit, filename: &str) {
         return;
     }
 
-    // Remove `.vue` from the end of the filename
-    let name_without_ext = filename.strip_suffix(".vue").unwrap_or(filename);
+    // Same as the official compiler, the name is the file name without the directories and the extension
+    let file_name = filename.rsplit(['/', '\\']).next().unwrap_or(filename);
+    let name_without_ext = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(file_stem, _)| file_stem);
 
     // Add `__name` to the exported object
     exported_obj
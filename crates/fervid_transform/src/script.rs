@@ -1,7 +1,7 @@
 //! Responsible for `<script>` and `<script setup>` transformations and analysis.
 
 use fervid_core::SfcScriptBlock;
-use resolve_type::record_types;
+use resolve_type::{record_type_imports, record_types};
 use swc_core::{
     common::DUMMY_SP,
     ecma::ast::{Function, Module, ObjectLit},
@@ -17,6 +17,8 @@ use self::{
     setup::{merge_sfc_helper, transform_and_record_script_setup},
 };
 
+pub use self::options_api::{analyze_options_object, find_default_export_delimiters};
+
 pub mod common;
 mod imports;
 mod options_api;
@@ -77,6 +79,12 @@ pub fn transform_and_record_scripts(
         let scope = ctx.root_scope();
         let mut scope = (*scope).borrow_mut();
         scope.imports = ctx.bindings_helper.user_imports.clone();
+        if let Some(ref script_options) = script_options {
+            record_type_imports(&script_options.content, &mut scope.imports, false);
+        }
+        if let Some(ref script_setup) = script_setup {
+            record_type_imports(&script_setup.content, &mut scope.imports, true);
+        }
 
         record_types(
             ctx,
@@ -207,6 +215,7 @@ mod tests {
             content: ts!(script_content),
             lang: fervid_core::SfcScriptLang::Typescript,
             is_setup: false,
+            attrs: vec![],
             span: Span {
                 lo: swc_core::common::BytePos(1),
                 hi: swc_core::common::BytePos(script_content.len() as u32 + 1),
@@ -216,6 +225,7 @@ mod tests {
             content: ts!(script_setup_content),
             lang: fervid_core::SfcScriptLang::Typescript,
             is_setup: true,
+            attrs: vec![],
             span: Span {
                 lo: swc_core::common::BytePos(script_content.len() as u32 + 2),
                 hi: swc_core::common::BytePos(script_setup_content.len() as u32 + 1),
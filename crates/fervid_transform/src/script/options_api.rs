@@ -1,12 +1,13 @@
+use fervid_core::FervidAtom;
 use swc_core::{
     common::DUMMY_SP,
     ecma::ast::{
-        Callee, Expr, ExprOrSpread, Module, ModuleDecl, ModuleItem, ObjectLit, PropOrSpread,
-        SpreadElement,
+        CallExpr, Callee, Expr, ExprOrSpread, Module, ModuleDecl, ModuleItem, ObjectLit,
+        PropOrSpread, SpreadElement,
     },
 };
 
-use crate::{error::TransformError, BindingsHelper};
+use crate::{error::TransformError, BindingsHelper, OptionsApiBindings};
 
 mod analyzer;
 mod components;
@@ -79,6 +80,12 @@ pub fn transform_and_record_script_options_api(
     }
 }
 
+/// Analyzes a component options object which is not necessarily a default export,
+/// e.g. `defineComponent({ /* ... */ })` or a component registered in `components`.
+pub fn analyze_options_object(options_obj: &ObjectLit, out: &mut OptionsApiBindings) {
+    analyzer::analyze_default_export(options_obj, out);
+}
+
 /// Finds and takes ownership of the `export default` expression
 fn find_default_export_obj(module: &mut Module) -> Option<ObjectLit> {
     let default_export_index = module
@@ -143,26 +150,7 @@ fn unroll_default_export_expr(mut expr: Expr) -> Expr {
                 };
             }
 
-            // We only support `defineComponent` with 1 argument which isn't a spread
-            if call_expr.args.len() != 1 {
-                bail!();
-            }
-
-            let Callee::Expr(ref callee) = call_expr.callee else {
-                bail!();
-            };
-
-            let Expr::Ident(callee_ident) = callee.as_ref() else {
-                bail!();
-            };
-
-            // Todo compare against the imported symbol
-            if &callee_ident.sym != "defineComponent" {
-                bail!();
-            }
-
-            let is_first_arg_ok = matches!(call_expr.args[0], ExprOrSpread { spread: None, .. });
-            if !is_first_arg_ok {
+            if !is_define_component_call(call_expr) {
                 bail!();
             }
 
@@ -177,6 +165,53 @@ fn unroll_default_export_expr(mut expr: Expr) -> Expr {
     }
 }
 
+/// Checks for `defineComponent` with 1 argument which isn't a spread,
+/// the only form of it which is supported
+fn is_define_component_call(call_expr: &CallExpr) -> bool {
+    if call_expr.args.len() != 1 {
+        return false;
+    }
+
+    let Callee::Expr(ref callee) = call_expr.callee else {
+        return false;
+    };
+
+    let Expr::Ident(callee_ident) = callee.as_ref() else {
+        return false;
+    };
+
+    // Todo compare against the imported symbol
+    &callee_ident.sym == "defineComponent"
+        && matches!(call_expr.args[0], ExprOrSpread { spread: None, .. })
+}
+
+/// Reads the `delimiters` option of the `export default` without transforming the module.
+/// The template needs them before the script is transformed, because they change how it is parsed.
+pub fn find_default_export_delimiters(module: &Module) -> Option<(FervidAtom, FervidAtom)> {
+    let default_export_expr = module
+        .body
+        .iter()
+        .find_map(|module_item| match module_item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(expr)) => Some(&expr.expr),
+            _ => None,
+        })?;
+
+    let options_obj = match default_export_expr.as_ref() {
+        Expr::Object(obj_lit) => obj_lit,
+        Expr::Call(call_expr) if is_define_component_call(call_expr) => {
+            let Expr::Object(ref obj_lit) = *call_expr.args[0].expr else {
+                return None;
+            };
+            obj_lit
+        }
+        _ => return None,
+    };
+
+    let mut options_api_bindings = OptionsApiBindings::default();
+    analyzer::analyze_default_export(options_obj, &mut options_api_bindings);
+    options_api_bindings.delimiters
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -343,6 +378,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_sees_delimiters() {
+        let test_delimiters = OptionsApiBindings {
+            delimiters: Some((FervidAtom::from("${"), FervidAtom::from("}"))),
+            ..Default::default()
+        };
+
+        test_js_and_ts!(
+            r"export default { delimiters: ['${', '}'] }",
+            test_delimiters
+        );
+        test_js_and_ts!(
+            r"export default defineComponent({ delimiters: [`${'$'}{`, '}'] })",
+            OptionsApiBindings::default()
+        );
+        test_js_and_ts!(
+            r#"export default { "delimiters": ["${", `}`] }"#,
+            test_delimiters
+        );
+
+        // Malformed delimiters are ignored
+        test_js_and_ts!(
+            r"export default { delimiters: ['${'] }",
+            OptionsApiBindings::default()
+        );
+        test_js_and_ts!(
+            r"export default { delimiters: ['${', '}', ']]'] }",
+            OptionsApiBindings::default()
+        );
+        test_js_and_ts!(
+            r"export default { delimiters: ['${', ...end] }",
+            OptionsApiBindings::default()
+        );
+    }
+
     #[test]
     fn it_analyzes_components() {
         test_js_and_ts!(
@@ -14,7 +14,7 @@ use crate::{
             categorize_class, categorize_expr, categorize_fn_decl, enrich_binding_types,
             extract_variables_from_pat,
         },
-        utils::get_string_tpl,
+        utils::{get_string_expr, get_string_tpl},
     },
     structs::VueResolvedImports,
     OptionsApiBindings, SetupBinding,
@@ -195,6 +195,26 @@ fn handle_options_array(
         collect_emits_bindings_array(array_lit, script_legacy_vars)
     } else if *field == *EXPOSE {
         collect_expose_bindings_array(array_lit, script_legacy_vars)
+    } else if *field == *DELIMITERS {
+        collect_delimiters_array(array_lit, script_legacy_vars)
+    }
+}
+
+/// Collects `delimiters` defined as `[string, string]`, e.g. `['${', '}']`.
+/// Anything else is ignored and the default delimiters are used.
+///
+/// https://vuejs.org/api/options-rendering.html#delimiters
+fn collect_delimiters_array(array_lit: &ArrayLit, script_legacy_vars: &mut OptionsApiBindings) {
+    let [Some(start), Some(end)] = array_lit.elems.as_slice() else {
+        return;
+    };
+
+    if start.spread.is_some() || end.spread.is_some() {
+        return;
+    }
+
+    if let (Some(start), Some(end)) = (get_string_expr(&start.expr), get_string_expr(&end.expr)) {
+        script_legacy_vars.delimiters = Some((start, end));
     }
 }
 
@@ -3,6 +3,7 @@
 use std::{
     cell::{Ref, RefCell},
     ops::Deref,
+    path::{Component, Path, PathBuf},
     rc::Rc,
 };
 
@@ -13,11 +14,12 @@ use itertools::Itertools;
 use phf::{phf_set, Set};
 use strum_macros::{AsRefStr, EnumString, IntoStaticStr};
 use swc_core::{
-    common::{pass::Either, Span, Spanned, DUMMY_SP},
+    common::{pass::Either, BytePos, Span, Spanned, DUMMY_SP},
     ecma::ast::{
-        BinExpr, BinaryOp, Class, ClassDecl, Decl, DefaultDecl, ExportDecl, ExportSpecifier, Expr, FnDecl, FnExpr, Function, Ident, Lit, Module, ModuleDecl, ModuleExportName, ModuleItem, Pat, Stmt, Tpl, TsCallSignatureDecl, TsEntityName, TsEnumDecl, TsExprWithTypeArgs, TsFnOrConstructorType, TsFnParam, TsFnType, TsIndexedAccessType, TsInterfaceDecl, TsIntersectionType, TsKeywordType, TsKeywordTypeKind, TsLit, TsLitType, TsMappedType, TsMethodSignature, TsModuleDecl, TsModuleName, TsNamespaceBody, TsNamespaceDecl, TsPropertySignature, TsQualifiedName, TsTplLitType, TsType, TsTypeAnn, TsTypeElement, TsTypeLit, TsTypeOperatorOp, TsTypeQueryExpr, TsTypeRef, TsUnionOrIntersectionType, TsUnionType
+        BinExpr, BinaryOp, Class, ClassDecl, Decl, DefaultDecl, ExportDecl, EsVersion, ExportSpecifier, Expr, FnDecl, FnExpr, Function, Ident, ImportSpecifier, Lit, Module, ModuleDecl, ModuleExportName, ModuleItem, Pat, Stmt, Tpl, TsCallSignatureDecl, TsEntityName, TsEnumDecl, TsExprWithTypeArgs, TsFnOrConstructorType, TsFnParam, TsFnType, TsIndexedAccessType, TsInterfaceDecl, TsIntersectionType, TsKeywordType, TsKeywordTypeKind, TsLit, TsLitType, TsMappedType, TsMethodSignature, TsModuleDecl, TsModuleName, TsNamespaceBody, TsNamespaceDecl, TsPropertySignature, TsQualifiedName, TsTplLitType, TsType, TsTypeAnn, TsTypeElement, TsTypeLit, TsTypeOperatorOp, TsTypeQueryExpr, TsTypeRef, TsUnionOrIntersectionType, TsUnionType
     },
 };
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax, TsSyntax};
 
 use crate::{
     error::{ScriptError, ScriptErrorKind},
@@ -909,17 +911,174 @@ fn resolve_global_scope(_ctx: &mut TypeResolveContext) -> Result<Option<Vec<Type
 }
 
 fn resolve_type_from_import<'t>(
-    _ctx: &mut TypeResolveContext,
-    _ts_type: ReferenceTypes<'t>,
-    _name: &str,
-    _scope: &TypeScope,
+    ctx: &mut TypeResolveContext,
+    ts_type: ReferenceTypes<'t>,
+    name: &FervidAtom,
+    scope: &TypeScope,
 ) -> Option<ScopeTypeNode> {
     // const { source, imported } = scope.imports[name]
     // const sourceScope = importSourceToScope(ctx, node, scope, source)
     // return resolveTypeReference(ctx, node, sourceScope, imported, true)
+    let ImportBinding {
+        ref source,
+        ref imported,
+        ..
+    } = scope.imports.get(name)?;
 
-    // TODO: Implement when FS access is ready
-    None
+    let source_scope = import_source_to_scope(ctx, source, scope)?;
+    let source_scope = &*source_scope.borrow();
+
+    inner_resolve_type_reference(
+        ctx,
+        ts_type,
+        source_scope,
+        std::slice::from_ref(imported),
+        true,
+    )
+}
+
+/// Loads the scope of a file imported by a relative path, e.g. `import type { Props } from './types'`.
+/// The loaded file becomes a dependency of the compilation, see [`TransformSfcContext::deps`].
+///
+/// Bare imports (e.g. from packages or aliases) are not supported yet.
+fn import_source_to_scope(
+    ctx: &mut TypeResolveContext,
+    source: &str,
+    scope: &TypeScope,
+) -> Option<TypeScopeContainer> {
+    if !source.starts_with("./") && !source.starts_with("../") {
+        return None;
+    }
+
+    let base_dir = Path::new(&scope.filename).parent().unwrap_or(Path::new(""));
+    let resolved = resolve_ext(&normalize_path(&base_dir.join(source)))?;
+    let filename = resolved.to_string_lossy().into_owned();
+
+    if let Some(file_scope) = ctx.file_scopes.get(&filename) {
+        return Some(file_scope.clone());
+    }
+
+    let content = std::fs::read_to_string(&resolved).ok()?;
+    ctx.deps.insert(filename.to_owned());
+
+    let ts_syntax = TsSyntax {
+        tsx: filename.ends_with(".tsx"),
+        dts: filename.ends_with(".d.ts"),
+        ..Default::default()
+    };
+    let lexer = Lexer::new(
+        Syntax::Typescript(ts_syntax),
+        EsVersion::EsNext,
+        StringInput::new(&content, BytePos(1), BytePos(1 + content.len() as u32)),
+        None,
+    );
+    let module = Parser::new_from(lexer).parse_typescript_module().ok()?;
+
+    // Registered before recording the types to support circular imports
+    let scope_container = Rc::new(RefCell::new(TypeScope::new(
+        ctx.scopes.len(),
+        filename.to_owned(),
+    )));
+    ctx.scopes.push(scope_container.clone());
+    ctx.file_scopes.insert(filename, scope_container.clone());
+
+    let span = module.span;
+    let mut virtual_sfc_block = SfcScriptBlock {
+        content: Box::new(module),
+        lang: fervid_core::SfcScriptLang::Typescript,
+        is_setup: false,
+        attrs: vec![],
+        span,
+    };
+
+    {
+        let scope = &mut *scope_container.borrow_mut();
+        record_type_imports(&virtual_sfc_block.content, &mut scope.imports, false);
+        record_types(ctx, None, Some(&mut virtual_sfc_block), scope, false);
+    }
+
+    Some(scope_container)
+}
+
+/// Records the imports of a module, including the type-only ones, so that the imported types can be resolved.
+/// Already recorded imports are kept as is
+pub(crate) fn record_type_imports(
+    module: &Module,
+    imports: &mut HashMap<FervidAtom, ImportBinding>,
+    is_from_setup: bool,
+) {
+    for module_item in module.body.iter() {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = module_item else {
+            continue;
+        };
+
+        for specifier in import_decl.specifiers.iter() {
+            let (local, imported) = match specifier {
+                ImportSpecifier::Named(named) => {
+                    let imported = match named.imported {
+                        Some(ModuleExportName::Ident(ref ident)) => ident.sym.to_owned(),
+                        Some(ModuleExportName::Str(ref s)) => s.value.to_owned(),
+                        None => named.local.sym.to_owned(),
+                    };
+                    (named.local.sym.to_owned(), imported)
+                }
+                ImportSpecifier::Default(default) => {
+                    (default.local.sym.to_owned(), fervid_atom!("default"))
+                }
+                ImportSpecifier::Namespace(namespace) => {
+                    (namespace.local.sym.to_owned(), fervid_atom!("*"))
+                }
+            };
+
+            imports
+                .entry(local.to_owned())
+                .or_insert_with(|| ImportBinding {
+                    source: import_decl.src.value.to_owned(),
+                    imported,
+                    local,
+                    is_from_setup,
+                });
+        }
+    }
+}
+
+/// Resolves the extension of an imported type file the same way TypeScript does
+fn resolve_ext(path: &Path) -> Option<PathBuf> {
+    let path_str = path.to_string_lossy();
+
+    // `import from './types.js'` refers to `./types.ts`
+    let without_js = path_str
+        .strip_suffix(".js")
+        .or_else(|| path_str.strip_suffix(".mjs"))
+        .unwrap_or(&path_str);
+
+    [
+        path_str.to_string(),
+        format!("{without_js}.ts"),
+        format!("{without_js}.tsx"),
+        format!("{without_js}.d.ts"),
+        format!("{path_str}/index.ts"),
+        format!("{path_str}/index.tsx"),
+        format!("{path_str}/index.d.ts"),
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .find(|candidate| candidate.is_file())
+}
+
+/// Removes `.` and `..` from the path without touching the file system
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
 }
 
 fn resolve_template_keys(
@@ -1354,7 +1513,7 @@ fn inner_resolve_type_reference<'t>(
 
     if let Some(name_single) = name_single {
         if let Some(_) = scope.imports.get(name_single) {
-            return resolve_type_from_import(ctx, ts_type, &name_single, scope);
+            return resolve_type_from_import(ctx, ts_type, name_single, scope);
         };
 
         let lookup_source = match ts_type {
@@ -1481,6 +1640,7 @@ fn module_decl_to_scope(
             }),
             lang: fervid_core::SfcScriptLang::Typescript,
             is_setup: true,
+            attrs: vec![],
             span,
         };
 
@@ -1712,6 +1872,14 @@ pub fn record_types(
         declare.owner_scope = scope.id;
     }
 
+    for node in exported_types.values_mut() {
+        node.owner_scope = scope.id;
+    }
+
+    for declare in exported_declares.values_mut() {
+        declare.owner_scope = scope.id;
+    }
+
 
     // TODO Support declares `_ownerScope`
     // for (const key of Object.keys(declares)) {
@@ -3979,7 +4147,70 @@ mod tests {
         );
     }
 
-    // TODO Imported types
+    #[test]
+    fn imported_types_from_file() {
+        let dir = std::env::temp_dir().join(format!("fervid-resolve-type-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(
+            dir.join("types.ts"),
+            "import type { Size } from './nested'
+            export interface Props { foo: string; size?: Size }
+            type Local = { bar: number }
+            export { Local as Aliased }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("nested/index.ts"),
+            "export type Size = 'sm' | 'lg'",
+        )
+        .unwrap();
+        let filename = dir.join("Test.vue");
+
+        let resolved = try_resolve_in_file(
+            "
+            import type { Props, Aliased } from './types.js'
+            defineProps<Props & Aliased>()",
+            filename.to_str().unwrap(),
+        )
+        .expect("Should resolve");
+
+        assert_eq!(resolved.props.len(), 3);
+        assert_eq!(
+            resolved.props.get(&fervid_atom!("foo")),
+            Some(&FlagSet::from(Types::String))
+        );
+        assert_eq!(
+            resolved.props.get(&fervid_atom!("size")),
+            Some(&FlagSet::from(Types::String))
+        );
+        assert_eq!(
+            resolved.props.get(&fervid_atom!("bar")),
+            Some(&FlagSet::from(Types::Number))
+        );
+
+        let mut deps: Vec<_> = resolved.deps.into_iter().collect();
+        deps.sort();
+        assert_eq!(
+            deps,
+            vec![
+                dir.join("nested/index.ts").to_string_lossy().into_owned(),
+                dir.join("types.ts").to_string_lossy().into_owned(),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn unresolvable_bare_import() {
+        let result = try_resolve(
+            "
+            import type { Props } from 'some-package'
+            defineProps<Props>()",
+        );
+
+        assert!(result.is_err_and(|e| matches!(e.kind, ScriptErrorKind::ResolveTypeUnresolvable)));
+    }
 
     #[test]
     fn failed_type_reference() {
@@ -4119,7 +4350,6 @@ mod tests {
     struct ResolveResult {
         props: FxHashMap<FervidAtom, TypesSet>,
         calls: Vec<Either<TsFnType, TsCallSignatureDecl>>,
-        #[allow(dead_code)]
         deps: FxHashSet<String>,
         raw_props: HashMap<FervidAtom, ResolvedProp>,
     }
@@ -4129,6 +4359,10 @@ mod tests {
     }
 
     fn try_resolve(code: &str) -> ResolutionResult<ResolveResult> {
+        try_resolve_in_file(code, "./Test.vue")
+    }
+
+    fn try_resolve_in_file(code: &str, filename: &str) -> ResolutionResult<ResolveResult> {
         let (script_setup_content, _) =
             parse_typescript_module(code, 0, TsSyntax::default()).expect("Should parse");
 
@@ -4140,6 +4374,7 @@ mod tests {
                 content: Box::new(script_setup_content),
                 lang: fervid_core::SfcScriptLang::Typescript,
                 is_setup: true,
+                attrs: vec![],
                 span,
             }),
             styles: vec![],
@@ -4149,10 +4384,9 @@ mod tests {
             &sfc_descriptor,
             &crate::TransformSfcOptions {
                 is_prod: true,
-                is_ce: false,
-                props_destructure: crate::PropsDestructureConfig::default(),
                 scope_id: "test",
-                filename: "./Test.vue",
+                filename,
+                ..Default::default()
             },
         );
 
@@ -4171,6 +4405,9 @@ mod tests {
         if ctx.bindings_helper.is_ts {
             let mut scope = (*scope).borrow_mut();
             scope.imports = ctx.bindings_helper.user_imports.clone();
+            if let Some(ref script_setup) = sfc_descriptor.script_setup {
+                record_type_imports(&script_setup.content, &mut scope.imports, true);
+            }
 
             record_types(
                 &mut ctx,
@@ -20,7 +20,7 @@ use crate::{
         utils::is_static,
     },
     structs::SfcExportedObjectHelper,
-    SetupBinding, TransformSfcContext,
+    ConstValue, SetupBinding, TransformSfcContext,
 };
 
 mod await_detection;
@@ -271,6 +271,17 @@ fn transform_decl_stmt(
                                 is_const,
                                 is_ident,
                             );
+
+                            // Remember the value for inlining it into the template
+                            if let (BindingTypes::LiteralConst, Some(binding), Some(value)) = (
+                                rhs_type,
+                                collected_bindings.first(),
+                                ConstValue::evaluate(init_expr),
+                            ) {
+                                ctx.bindings_helper
+                                    .literal_consts
+                                    .insert(binding.0.to_owned(), value);
+                            }
                         }
                         _ => {}
                     }
@@ -452,6 +463,7 @@ mod tests {
             content: Box::new(parsed),
             lang: fervid_core::SfcScriptLang::Es,
             is_setup: true,
+            attrs: vec![],
             span: DUMMY_SP,
         })
     }
@@ -465,6 +477,7 @@ mod tests {
             content: Box::new(parsed),
             lang: fervid_core::SfcScriptLang::Typescript,
             is_setup: true,
+            attrs: vec![],
             span: DUMMY_SP,
         })
     }
@@ -771,6 +784,7 @@ mod tests {
                     content: Box::new(parsed),
                     lang: fervid_core::SfcScriptLang::Typescript,
                     is_setup: true,
+                    attrs: vec![],
                     span: DUMMY_SP,
                 };
 
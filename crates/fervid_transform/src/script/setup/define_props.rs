@@ -124,6 +124,8 @@ pub fn process_with_defaults(
 
 /// Extracts runtime and types from `defineProps` call
 fn extract_from_define_props(define_props_call: &CallExpr, out: &mut DefineProps) {
+    out.span = define_props_call.span;
+
     // Runtime
     if let Some(first_argument) = &define_props_call.args.get(0) {
         out.runtime_decl = Some(first_argument.expr.to_owned());
@@ -1,17 +1,21 @@
 //! Exports data structs used by the crate
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, fmt::Debug, rc::Rc, sync::Arc};
 
 use fervid_core::{
     BindingTypes, ComponentBinding, CustomDirectiveBinding, FervidAtom, SfcCustomBlock,
     SfcStyleBlock, SfcTemplateBlock, TemplateGenerationMode, VueImportsSet,
 };
+use fervid_css::CssSourceMapConfig;
 use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use regex::RegexSet;
 use smallvec::SmallVec;
 use swc_core::ecma::ast::{
     Decl, Expr, ExprOrSpread, Function, Id, Module, ObjectLit, PropOrSpread, Str, TsType,
 };
 
+use crate::{error::TransformError, template::TemplatePlugin};
+
 /// Context object. Currently very minimal but may grow over time.
 pub struct TransformSfcContext {
     pub filename: String,
@@ -21,8 +25,11 @@ pub struct TransformSfcContext {
     /// For Custom Elements
     pub is_ce: bool,
     pub bindings_helper: BindingsHelper,
+    /// Files the compilation depended on, e.g. the ones the types were resolved from
     pub deps: HashSet<String>,
     pub(crate) scopes: Vec<TypeScopeContainer>,
+    /// Scopes of the imported type files by their paths, so that each file is only loaded once
+    pub(crate) file_scopes: HashMap<String, TypeScopeContainer>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -30,7 +37,58 @@ pub enum PropsDestructureConfig {
     #[default]
     False,
     True,
-    Error
+    Error,
+}
+
+/// Controls how whitespace in the template text is handled.
+/// Text inside `<pre>` and `<textarea>` is always kept as is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WhitespaceMode {
+    /// Whitespace-only text between elements and comments is removed when it contains a newline,
+    /// otherwise condensed into a single space.
+    /// Consecutive whitespace characters in the other text are condensed into a single space.
+    #[default]
+    Condense,
+    /// Whitespace-only text between elements and comments is condensed into a single space,
+    /// the other text is kept as is.
+    Preserve,
+}
+
+/// Predicate on a tag name, e.g. to recognize custom elements such as `<ion-button>`
+#[derive(Clone)]
+pub struct TagPredicate(Arc<dyn Fn(&str) -> bool + Send + Sync>);
+
+impl TagPredicate {
+    pub fn new(predicate: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        TagPredicate(Arc::new(predicate))
+    }
+
+    /// Matches the tags which start with any of the `prefixes` (e.g. `ion-`)
+    /// or match any of the regular expressions in `patterns` (e.g. `^model-`)
+    pub fn from_prefixes_and_patterns(
+        prefixes: Vec<String>,
+        patterns: &[String],
+    ) -> Result<Self, regex::Error> {
+        let patterns = RegexSet::new(patterns)?;
+
+        Ok(TagPredicate::new(move |tag_name| {
+            prefixes
+                .iter()
+                .any(|prefix| tag_name.starts_with(prefix.as_str()))
+                || patterns.is_match(tag_name)
+        }))
+    }
+
+    #[inline]
+    pub fn matches(&self, tag_name: &str) -> bool {
+        (self.0)(tag_name)
+    }
+}
+
+impl Debug for TagPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TagPredicate")
+    }
 }
 
 /// A helper which encompasses all the logic related to bindings,
@@ -46,10 +104,18 @@ pub struct BindingsHelper {
     pub is_prod: bool,
     /// Is Typescript or Javascript used
     pub is_ts: bool,
+    /// Tags which are native custom elements and must not be resolved as components.
+    /// Same as `isCustomElement` in the official compiler
+    pub is_custom_element_tag: Option<TagPredicate>,
+    /// Whether to keep the `<!-- comments -->` of the `<template>`.
+    /// When not set, comments are kept in DEV and removed in PROD
+    pub comments: Option<bool>,
     /// Scopes of the `<template>` for in-template variable resolutions
     pub template_scopes: Vec<TemplateScope>,
     /// Bindings in `<script setup>`
     pub setup_bindings: Vec<SetupBinding>,
+    /// Values of the `LiteralConst` bindings in `<script setup>`, inlined into the template in PROD
+    pub literal_consts: HashMap<FervidAtom, ConstValue>,
     /// Bindings in `<script>`
     pub options_api_bindings: Option<Box<OptionsApiBindings>>,
     /// The mode with which `<template>` variables are resolved.
@@ -65,6 +131,70 @@ pub struct BindingsHelper {
     pub vue_imports: VueImportsSet,
     /// User imports from `vue` package
     pub vue_resolved_imports: Box<VueResolvedImports>,
+    /// Imports added by the template plugins
+    pub template_imports: Vec<TemplateImport>,
+    /// User-defined transforms of the `<template>` elements
+    pub template_plugins: Vec<Arc<dyn TemplatePlugin>>,
+    /// Errors found while transforming or generating the `<template>`
+    pub errors: Vec<TransformError>,
+}
+
+impl BindingsHelper {
+    /// Whether the template comments should be kept and generated
+    pub fn keeps_comments(&self) -> bool {
+        self.comments.unwrap_or(!self.is_prod)
+    }
+
+    /// Whether `variable` is declared in the `<template>` by a `v-for` or a `v-slot`,
+    /// looking from `template_scope` up to the root scope
+    pub fn is_template_local(&self, template_scope: u32, variable: &str) -> bool {
+        let mut current_scope_index = template_scope;
+
+        while let Some(current_scope) = self.template_scopes.get(current_scope_index as usize) {
+            if current_scope.variables.iter().any(|it| it == variable) {
+                return true;
+            }
+
+            // Check if we reached the root scope, it will have itself as a parent
+            if current_scope.parent == current_scope_index {
+                break;
+            }
+
+            // Go to parent
+            current_scope_index = current_scope.parent;
+        }
+
+        false
+    }
+
+    /// Adds an import to the generated module and makes its `local` accessible from the `<template>`.
+    /// An import with the same `local` is only added once.
+    pub fn add_template_import(&mut self, import: TemplateImport) {
+        if self
+            .template_imports
+            .iter()
+            .any(|existing| existing.local == import.local)
+        {
+            return;
+        }
+
+        // Imports are module-level and can be referenced as-is,
+        // both from the inline template and from the `render` function
+        self.used_bindings
+            .insert(import.local.to_owned(), BindingTypes::JsGlobal);
+        self.template_imports.push(import);
+    }
+}
+
+/// An import added to the module when compiling the `<template>`, e.g. by a [`TemplatePlugin`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateImport {
+    /// `foo` in `import foo from 'bar'` or `import { baz as foo } from 'bar'`
+    pub local: FervidAtom,
+    /// `baz` in `import { baz as foo } from 'bar'`, `None` for a default import
+    pub imported: Option<FervidAtom>,
+    /// `bar` in `import foo from 'bar'`
+    pub source: FervidAtom,
 }
 
 #[derive(Debug, Clone)]
@@ -112,14 +242,26 @@ pub struct OptionsApiBindings {
     pub expose: Vec<FervidAtom>,
     pub name: Option<FervidAtom>,
     pub directives: Vec<FervidAtom>,
+    /// Custom interpolation delimiters, e.g. `['${', '}']`
+    pub delimiters: Option<(FervidAtom, FervidAtom)>,
     /// `SetupBinding` is used to distinguish between `.vue` and other imports
     pub imports: Vec<SetupBinding>,
 }
 
 /// Identifier plus a binding type
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SetupBinding(pub FervidAtom, pub BindingTypes);
 
+/// A primitive value known at compile time, e.g. the value of a `LiteralConst` binding
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Null,
+    Undefined,
+}
+
 #[derive(Debug, Clone)]
 pub struct ImportBinding {
     /// Where it was imported from
@@ -189,12 +331,23 @@ pub struct TransformScriptsResult {
     pub setup_fn: Option<Box<Function>>,
 }
 
+#[derive(Default)]
 pub struct TransformSfcOptions<'s> {
     pub is_prod: bool,
     pub is_ce: bool,
     pub props_destructure: PropsDestructureConfig,
     pub scope_id: &'s str,
     pub filename: &'s str,
+    /// How the whitespace in `<template>` is handled
+    pub whitespace: WhitespaceMode,
+    /// Which unknown tags are native custom elements rather than components
+    pub is_custom_element_tag: Option<TagPredicate>,
+    /// Keep the `<template>` comments regardless of `is_prod`
+    pub comments: Option<bool>,
+    /// Generate source maps for the transformed `<style>` blocks
+    pub style_source_map: Option<CssSourceMapConfig<'s>>,
+    /// User-defined transforms of the `<template>` elements, run in the given order
+    pub template_plugins: Vec<Arc<dyn TemplatePlugin>>,
 }
 
 pub struct TransformSfcResult {
@@ -212,6 +365,8 @@ pub struct TransformSfcResult {
     pub style_blocks: Vec<SfcStyleBlock>,
     /// Custom blocks
     pub custom_blocks: Vec<SfcCustomBlock>,
+    /// Files the compilation depended on, see [`TransformSfcContext::deps`]
+    pub deps: HashSet<String>,
 }
 
 #[cfg(test)]
@@ -225,6 +380,7 @@ impl TransformSfcContext {
             props_destructure: PropsDestructureConfig::default(),
             deps: HashSet::default(),
             scopes: vec![],
+            file_scopes: HashMap::default(),
         }
     }
 }
@@ -38,6 +38,7 @@ pub fn create_style_scope(file_hash: &str) -> String {
 pub fn transform_style_blocks(
     style_blocks: &mut Vec<SfcStyleBlock>,
     scope: &str,
+    source_map: Option<&CssSourceMapConfig>,
     errors: &mut Vec<TransformError>,
 ) -> bool {
     // Check work
@@ -58,11 +59,15 @@ pub fn transform_style_blocks(
                 style_block.span,
                 Some(&scope),
                 &mut css_errors,
-                TransformCssConfig::default(),
+                TransformCssConfig {
+                    source_map: source_map.copied(),
+                    ..Default::default()
+                },
             );
 
             if let Some(transformed) = result {
-                style_block.content = transformed.into();
+                style_block.content = transformed.code.into();
+                style_block.source_map = transformed.source_map;
             }
         }
     }
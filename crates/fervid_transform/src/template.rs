@@ -2,10 +2,15 @@
 
 mod ast_transform;
 mod collect_vars;
+mod const_fold;
 mod expr_transform;
 mod js_builtins;
+mod plugin;
 mod resolutions;
 mod v_on;
 mod utils;
+mod validate;
 
 pub use ast_transform::transform_and_record_template;
+pub use plugin::{ElementTransform, TemplatePlugin};
+pub use validate::validate_template;
@@ -10,37 +10,69 @@ use swc_core::{
     ecma::ast::{Bool, Expr, Lit},
 };
 
-use crate::{BindingsHelper, TemplateScope};
-
-use super::{collect_vars::collect_variables, expr_transform::BindingsHelperTransform};
+use crate::{BindingsHelper, ConstValue, TagPredicate, TemplateScope, WhitespaceMode};
+
+use super::{
+    collect_vars::collect_variables,
+    const_fold::{fold_class_or_style, fold_constant_node, fold_static_attributes},
+    expr_transform::BindingsHelperTransform,
+    plugin::run_template_plugins,
+    validate::validate_children,
+};
 
 pub struct TemplateVisitor<'s> {
     pub bindings_helper: &'s mut BindingsHelper,
     pub current_scope: u32,
     pub v_for_scope: bool,
+    pub whitespace: WhitespaceMode,
+    /// Whether the visitor is inside `<pre>` or `<textarea>`
+    pub is_pre: bool,
 }
 
 /// Transforms the AST template by using information from [`BindingsHelper`].
 ///
 /// The transformations tackled:
-/// - Optimizing the tree by removing white-space nodes;
+/// - Running the [`BindingsHelper::template_plugins`] on the elements;
+/// - Validating the template and reporting the errors to [`BindingsHelper::errors`];
+/// - Optimizing the tree by removing and condensing white-space according to [`WhitespaceMode`];
 /// - Folding the conditional nodes (`v-if`, etc.) into a single `ConditionalNode`;
-/// - Transforming Js expressions by resolving variables inside them.
+/// - Transforming Js expressions by resolving variables inside them;
+/// - Folding the constant expressions in PROD.
 pub fn transform_and_record_template(
     template: &mut SfcTemplateBlock,
     bindings_helper: &mut BindingsHelper,
+    whitespace: WhitespaceMode,
 ) {
-    // Optimize conditional sequences within template root
-    optimize_children(&mut template.roots, ElementKind::Element);
+    let mut template_visitor = TemplateVisitor {
+        bindings_helper,
+        current_scope: 0,
+        v_for_scope: false,
+        whitespace,
+        is_pre: false,
+    };
+
+    // Transform and validate the roots, then optimize conditional sequences within them
+    template_visitor.prepare_children(&mut template.roots, None);
+    template_visitor.optimize_children(&mut template.roots, ElementKind::Element, false);
 
     // Merge more than 1 child into a separate `<template>` element so that Fragment gets generated.
     // #11: Do this only when all children are `TextNode`s.
-    if template.roots.len() > 1
+    let needs_fragment = template.roots.len() > 1
         && !template
             .roots
             .iter()
-            .all(|r| matches!(r, Node::Text(_, _) | Node::Interpolation(_)))
-    {
+            .all(|r| matches!(r, Node::Text(_, _) | Node::Interpolation(_)));
+
+    for node in template.roots.iter_mut() {
+        node.visit_mut_with(&mut template_visitor);
+
+        if template_visitor.bindings_helper.is_prod {
+            fold_constant_node(node);
+        }
+    }
+
+    // The roots are visited as they are, the `<template>` itself needs no transformation
+    if needs_fragment {
         let all_roots = std::mem::replace(&mut template.roots, Vec::with_capacity(1));
         let new_root = Node::Element(ElementNode {
             kind: ElementKind::Element,
@@ -56,201 +88,312 @@ pub fn transform_and_record_template(
         });
         template.roots.push(new_root);
     }
-
-    let mut template_visitor = TemplateVisitor {
-        bindings_helper,
-        current_scope: 0,
-        v_for_scope: false,
-    };
-
-    for node in template.roots.iter_mut() {
-        node.visit_mut_with(&mut template_visitor);
-    }
 }
 
-/// Optimizes the children by removing whitespace in between `ElementNode`s,
-/// as well as folding `v-if`/`v-else-if`/`v-else` sequences into a `ConditionalNodeSequence`
-fn optimize_children(children: &mut Vec<Node>, element_kind: ElementKind) {
-    let children_len = children.len();
-
-    // Discard children mask, limited to 128 children. 0 means to preserve the node, 1 to discard
-    let mut discard_mask: u128 = 0;
-
-    // Filter out whitespace text nodes at the beginning and end of ElementNode
-    match children.first() {
-        Some(Node::Text(v, _)) if v.trim().is_empty() => {
-            discard_mask |= 1 << 0;
-        }
-        _ => {}
+impl TemplateVisitor<'_> {
+    /// Runs the template plugins on the children with the current scope, and then validates the children.
+    /// This is done before the children are optimized, i.e. while they are in their original shape.
+    /// `parent` is `None` for the template roots
+    fn prepare_children(&mut self, children: &mut Vec<Node>, parent: Option<&StartingTag>) {
+        // User-defined transforms go first, as they may change the elements completely
+        run_template_plugins(children, self.current_scope, self.bindings_helper);
+
+        validate_children(
+            children,
+            parent,
+            self.bindings_helper.is_custom_element_tag.as_ref(),
+            &mut self.bindings_helper.errors,
+        );
     }
-    match children.last() {
-        Some(Node::Text(v, _)) if v.trim().is_empty() => {
-            discard_mask |= 1 << (children_len - 1);
+
+    /// Optimizes the children by removing whitespace in between `ElementNode`s
+    /// and comments (unless they are kept, see [`BindingsHelper::keeps_comments`]),
+    /// as well as folding `v-if`/`v-else-if`/`v-else` sequences into a `ConditionalNodeSequence`
+    fn optimize_children(
+        &mut self,
+        children: &mut Vec<Node>,
+        element_kind: ElementKind,
+        is_pre: bool,
+    ) {
+        optimize_whitespace(children, self.whitespace, is_pre);
+
+        if !self.bindings_helper.keeps_comments() {
+            children.retain(|child| !matches!(child, Node::Comment(_, _)));
         }
-        _ => {}
-    }
 
-    // For removing the middle whitespace text nodes, we need sliding windows of three nodes
-    for (index, window) in children.windows(3).enumerate() {
-        match window {
-            [Node::Element(_) | Node::Comment(_, _), Node::Text(middle, _), Node::Element(_) | Node::Comment(_, _)]
-                if middle.trim().is_empty() =>
-            {
-                discard_mask |= 1 << (index + 1);
-            }
-            _ => {}
+        // For components, reorder children so that named slots come first
+        if matches!(element_kind, ElementKind::Component) && children.len() > 0 {
+            children.sort_by(|a, b| {
+                let a_is_from_default = is_from_default_slot(a);
+                let b_is_from_default = is_from_default_slot(b);
+
+                a_is_from_default.cmp(&b_is_from_default)
+            });
         }
-    }
 
-    // Retain based on discard_mask. If a discard bit at `index` is set to 1, the node will be dropped
-    let mut index = 0;
-    children.retain(|_| {
-        let should_retain = discard_mask & (1 << index) == 0;
-        index += 1;
-        should_retain
-    });
+        // Merge multiple v-if/else-if/else nodes into a ConditionalNodeSequence
+        if !children.is_empty() {
+            let mut seq: Option<ConditionalNodeSequence> = None;
+            let mut new_children = Vec::with_capacity(children.len());
 
-    // For components, reorder children so that named slots come first
-    if matches!(element_kind, ElementKind::Component) && children.len() > 0 {
-        children.sort_by(|a, b| {
-            let a_is_from_default = is_from_default_slot(a);
-            let b_is_from_default = is_from_default_slot(b);
+            /// Finishes the sequence. Pass `child` to also push the current child
+            macro_rules! finish_seq {
+                () => {
+                    if let Some(seq) = seq.take() {
+                        new_children.push(Node::ConditionalSeq(seq))
+                    }
+                };
+                ($child: expr) => {
+                    finish_seq!();
+                    new_children.push($child);
+                };
+            }
 
-            a_is_from_default.cmp(&b_is_from_default)
-        });
-    }
+            // To move out of &ElementNode to ElementNode and avoid "partially moved variable" error
+            macro_rules! deref_element {
+                ($child: ident) => {{
+                    let Node::Element(mut child_element) = $child else {
+                        unreachable!()
+                    };
 
-    // Merge multiple v-if/else-if/else nodes into a ConditionalNodeSequence
-    if !children.is_empty() {
-        let mut seq: Option<ConditionalNodeSequence> = None;
-        let mut new_children = Vec::with_capacity(children.len());
+                    // The `<template>` is replaced by its only child and is never visited,
+                    // so the child is prepared here instead
+                    if has_child_to_unwrap(&child_element) {
+                        self.prepare_children(
+                            &mut child_element.children,
+                            Some(&child_element.starting_tag),
+                        );
+                    }
 
-        /// Finishes the sequence. Pass `child` to also push the current child
-        macro_rules! finish_seq {
-            () => {
-                if let Some(seq) = seq.take() {
-                    new_children.push(Node::ConditionalSeq(seq))
-                }
-            };
-            ($child: expr) => {
-                finish_seq!();
-                new_children.push($child);
-            };
-        }
+                    optimize_v_if_plus_v_for(child_element)
+                }};
+            }
 
-        // To move out of &ElementNode to ElementNode and avoid "partially moved variable" error
-        macro_rules! deref_element {
-            ($child: ident) => {{
-                let Node::Element(child_element) = $child else {
-                    unreachable!()
+            let mut children_iter = std::mem::take(children).into_iter().peekable();
+            while let Some(mut child) = children_iter.next() {
+                // Only process `ElementNode`s.
+                // Otherwise, when we have an `if` node, ignore `Comment`s and finish sequence.
+                // Whitespace is also ignored when the sequence continues after it.
+                let Node::Element(child_element) = &mut child else {
+                    let is_ignored = match child {
+                        Node::Comment(_, _) => true,
+                        Node::Text(ref text, _) => {
+                            is_all_whitespace(text)
+                                && children_iter.peek().is_some_and(is_else_branch)
+                        }
+                        _ => false,
+                    };
+                    if is_ignored && seq.is_some() {
+                        continue;
+                    } else {
+                        finish_seq!(child);
+                        continue;
+                    }
                 };
 
-                optimize_v_if_plus_v_for(child_element)
-            }};
-        }
-
-        for mut child in children.drain(..) {
-            // Only process `ElementNode`s.
-            // Otherwise, when we have an `if` node, ignore `Comment`s and finish sequence.
-            let Node::Element(child_element) = &mut child else {
-                if let (Node::Comment(_, _), Some(_)) = (&child, seq.as_ref()) {
-                    continue;
-                } else {
+                let Some(ref mut directives) = child_element.starting_tag.directives else {
                     finish_seq!(child);
                     continue;
+                };
+
+                // Check if we have a `v-if`.
+                // The already existing sequence should end, and the new sequence should start.
+                if let Some(v_if) = directives.v_if.take() {
+                    finish_seq!();
+                    seq = Some(ConditionalNodeSequence {
+                        if_node: Box::new(Conditional {
+                            condition: *v_if,
+                            node: deref_element!(child),
+                        }),
+                        else_if_nodes: vec![],
+                        else_node: None,
+                    });
+                    continue;
                 }
-            };
 
-            let Some(ref mut directives) = child_element.starting_tag.directives else {
-                finish_seq!(child);
-                continue;
-            };
+                // Check for `v-else-if`
+                if let Some(v_else_if) = directives.v_else_if.take() {
+                    let Some(ref mut seq) = seq else {
+                        // This must be a warning, v-else-if without v-if
+                        finish_seq!(child);
+                        continue;
+                    };
 
-            // Check if we have a `v-if`.
-            // The already existing sequence should end, and the new sequence should start.
-            if let Some(v_if) = directives.v_if.take() {
-                finish_seq!();
-                seq = Some(ConditionalNodeSequence {
-                    if_node: Box::new(Conditional {
-                        condition: *v_if,
+                    seq.else_if_nodes.push(Conditional {
+                        condition: *v_else_if,
                         node: deref_element!(child),
-                    }),
-                    else_if_nodes: vec![],
-                    else_node: None,
-                });
-                continue;
-            }
+                    });
+                    continue;
+                }
 
-            // Check for `v-else-if`
-            if let Some(v_else_if) = directives.v_else_if.take() {
-                let Some(ref mut seq) = seq else {
-                    // This must be a warning, v-else-if without v-if
-                    finish_seq!(child);
+                // Check for `v-else`
+                if let Some(_) = directives.v_else {
+                    let Some(ref mut cond_seq) = seq else {
+                        // This must be a warning, v-else without v-if
+                        finish_seq!(child);
+                        continue;
+                    };
+
+                    cond_seq.else_node = Some(Box::new(deref_element!(child)));
+
+                    // `else` node always finishes the sequence
+                    finish_seq!();
                     continue;
-                };
+                }
 
-                seq.else_if_nodes.push(Conditional {
-                    condition: *v_else_if,
-                    node: deref_element!(child),
-                });
-                continue;
+                // No directives, just push the child
+                finish_seq!(child);
             }
 
-            // Check for `v-else`
-            if let Some(_) = directives.v_else {
-                let Some(ref mut cond_seq) = seq else {
-                    // This must be a warning, v-else without v-if
-                    finish_seq!(child);
-                    continue;
-                };
+            finish_seq!();
 
-                cond_seq.else_node = Some(Box::new(deref_element!(child)));
+            *children = new_children;
+        }
+    }
+}
 
-                // `else` node always finishes the sequence
-                finish_seq!();
-                continue;
+/// Removes and condenses whitespace the same way as the official compiler does.
+/// Whitespace inside `<pre>` and `<textarea>` is kept, only Windows newlines are normalized.
+/// https://github.com/vuejs/core/blob/a41c5f1f4367a9f41bcdb8c4e02f54b2378e577d/packages/compiler-core/src/parser.ts#L855
+fn optimize_whitespace(children: &mut Vec<Node>, whitespace: WhitespaceMode, is_pre: bool) {
+    if is_pre {
+        for child in children.iter_mut() {
+            if let Node::Text(text, _) = child {
+                if text.contains('\r') {
+                    *text = FervidAtom::from(text.replace("\r\n", "\n"));
+                }
             }
+        }
+        return;
+    }
+
+    let should_condense = matches!(whitespace, WhitespaceMode::Condense);
+    let is_element = |node: Option<&Node>| matches!(node, Some(Node::Element(_)));
+    let is_comment = |node: Option<&Node>| matches!(node, Some(Node::Comment(_, _)));
+
+    // Which whitespace-only nodes are to be removed.
+    // This is decided upfront, because the neighbours must be checked before any removal.
+    let mut should_remove = Vec::with_capacity(children.len());
+    for (index, child) in children.iter().enumerate() {
+        let Node::Text(text, _) = child else {
+            should_remove.push(false);
+            continue;
+        };
 
-            // No directives, just push the child
-            finish_seq!(child);
+        if !is_all_whitespace(text) {
+            should_remove.push(false);
+            continue;
         }
 
-        finish_seq!();
+        // Remove if:
+        // - the whitespace is the first or last node, or:
+        // - (condense mode) the whitespace is between two comments, or:
+        // - (condense mode) the whitespace is between comment and element, or:
+        // - (condense mode) the whitespace is between two elements AND contains newline
+        let prev = index.checked_sub(1).and_then(|prev| children.get(prev));
+        let next = children.get(index + 1);
+        should_remove.push(
+            prev.is_none()
+                || next.is_none()
+                || (should_condense
+                    && ((is_comment(prev) && (is_comment(next) || is_element(next)))
+                        || (is_element(prev)
+                            && (is_comment(next)
+                                || (is_element(next) && text.contains(['\n', '\r'])))))),
+        );
+    }
+
+    let mut should_remove = should_remove.into_iter();
+    children.retain_mut(|child| {
+        if should_remove.next().unwrap_or_default() {
+            return false;
+        }
+
+        if let Node::Text(text, _) = child {
+            if is_all_whitespace(text) {
+                if text != " " {
+                    *text = fervid_atom!(" ");
+                }
+            } else if should_condense && has_whitespace_to_condense(text) {
+                *text = FervidAtom::from(condense_whitespace(text));
+            }
+        }
+
+        true
+    });
+}
+
+/// Only ASCII whitespace is considered, e.g. `&nbsp;` must not be touched
+#[inline]
+fn is_all_whitespace(text: &str) -> bool {
+    text.bytes().all(|b| b.is_ascii_whitespace())
+}
 
-        *children = new_children;
+/// Checks if there is a whitespace sequence which is not a single space
+fn has_whitespace_to_condense(text: &str) -> bool {
+    let mut prev_is_whitespace = false;
+    for b in text.bytes() {
+        let is_whitespace = b.is_ascii_whitespace();
+        if is_whitespace && (prev_is_whitespace || b != b' ') {
+            return true;
+        }
+        prev_is_whitespace = is_whitespace;
     }
+    false
 }
 
-// Optimize combined usage of conditional directives and `v-for`
-// https://github.com/vuejs/core/blob/438a74aad840183286fbdb488178510f37218a73/packages/compiler-core/src/transforms/vIf.ts#L260
-fn optimize_v_if_plus_v_for(mut parent: ElementNode) -> ElementNode {
-    // Check that work is needed
-    // This must be a `<template>` element with exactly one Element child
-    if parent.children.len() != 1 || parent.starting_tag.tag_name != "template" {
-        return parent;
+/// Replaces every ASCII whitespace sequence with a single space
+fn condense_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut prev_is_whitespace = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !prev_is_whitespace {
+                result.push(' ');
+            }
+            prev_is_whitespace = true;
+        } else {
+            result.push(c);
+            prev_is_whitespace = false;
+        }
     }
+    result
+}
 
-    let Some(Node::Element(child)) = parent.children.first_mut() else {
-        return parent;
+/// Checks if the node is an element with `v-else-if` or `v-else`
+fn is_else_branch(node: &Node) -> bool {
+    let Node::Element(element) = node else {
+        return false;
     };
 
-    // There must be at most one `v-for` for both parent and child
-    let parent_has_v_for = parent
-        .starting_tag
-        .directives
-        .as_ref()
-        .map_or(false, |d| d.v_for.is_some());
-    let child_has_v_for = child
+    element
         .starting_tag
         .directives
         .as_ref()
-        .map_or(false, |d| d.v_for.is_some());
-    if parent_has_v_for && child_has_v_for {
+        .is_some_and(|d| d.v_else_if.is_some() || d.v_else.is_some())
+}
+
+/// `<pre>` and `<textarea>` keep the whitespace of their content
+#[inline]
+fn is_pre_tag(tag_name: &str) -> bool {
+    matches!(tag_name, "pre" | "textarea")
+}
+
+// Optimize combined usage of conditional directives and `v-for`
+// https://github.com/vuejs/core/blob/438a74aad840183286fbdb488178510f37218a73/packages/compiler-core/src/transforms/vIf.ts#L260
+fn optimize_v_if_plus_v_for(mut parent: ElementNode) -> ElementNode {
+    // Check that work is needed
+    if !has_child_to_unwrap(&parent) {
         return parent;
     }
 
+    // Take the child and return it instead
+    let Some(Node::Element(mut child)) = parent.children.pop() else {
+        unreachable!()
+    };
+
     // Take parent's `v-for` and give it to the child
-    if parent_has_v_for {
+    if has_v_for(&parent) {
         let Some(mut parent_directives) = parent.starting_tag.directives.take() else {
             unreachable!()
         };
@@ -262,12 +405,31 @@ fn optimize_v_if_plus_v_for(mut parent: ElementNode) -> ElementNode {
         child_directives.v_for = parent_directives.v_for.take();
     }
 
-    // Take the child and return it instead
-    let Some(Node::Element(child)) = parent.children.pop() else {
-        unreachable!()
+    child
+}
+
+/// Whether [`optimize_v_if_plus_v_for`] replaces the element with its only child:
+/// this must be a `<template>` element with exactly one Element child,
+/// and there must be at most one `v-for` for both parent and child
+fn has_child_to_unwrap(parent: &ElementNode) -> bool {
+    if parent.children.len() != 1 || parent.starting_tag.tag_name != "template" {
+        return false;
+    }
+
+    let Some(Node::Element(child)) = parent.children.first() else {
+        return false;
     };
 
-    child
+    !(has_v_for(parent) && has_v_for(child))
+}
+
+#[inline]
+fn has_v_for(element: &ElementNode) -> bool {
+    element
+        .starting_tag
+        .directives
+        .as_ref()
+        .map_or(false, |d| d.v_for.is_some())
 }
 
 trait Visitor {
@@ -307,6 +469,7 @@ impl<'a> Visitor for TemplateVisitor<'_> {
         let mut has_vnode_hook = false;
         let mut ref_key = Option::<FervidAtom>::None;
         let mut should_use_block = false;
+        let mut has_constant_class_or_style = false;
 
         // Check if there is a scoping directive.
         // Find a `v-for` or `v-slot` directive when in ElementNode
@@ -440,6 +603,15 @@ impl<'a> Visitor for TemplateVisitor<'_> {
                         has_ref = true;
                     }
 
+                    // Constant `class` and `style` become regular attributes in PROD
+                    if self.bindings_helper.is_prod
+                        && !v_bind.is_prop
+                        && fold_class_or_style(argument, &v_bind.value).is_some()
+                    {
+                        has_constant_class_or_style = true;
+                        continue;
+                    }
+
                     // If we are FULL_PROPS already, do not add other props/class/style.
                     // Or if we do not need to add.
                     if !has_bindings || patch_hints.flags.contains(PatchFlags::FullProps) {
@@ -550,6 +722,9 @@ impl<'a> Visitor for TemplateVisitor<'_> {
                 _ => {}
             }
         }
+        if has_constant_class_or_style {
+            fold_static_attributes(&mut element_node.starting_tag.attributes);
+        }
 
         // Transform the directives
         if let Some(ref mut directives) = element_node.starting_tag.directives {
@@ -566,6 +741,17 @@ impl<'a> Visitor for TemplateVisitor<'_> {
             maybe_transform!(v_show);
             maybe_transform!(v_text);
 
+            // Constant truthy `v-show` has no effect in PROD
+            if self.bindings_helper.is_prod
+                && directives
+                    .v_show
+                    .as_ref()
+                    .and_then(|v_show| ConstValue::evaluate(v_show))
+                    .is_some_and(|value| value.is_truthy())
+            {
+                directives.v_show = None;
+            }
+
             for v_model in directives.v_model.iter_mut() {
                 self.bindings_helper
                     .transform_v_model(v_model, scope_to_use, patch_hints);
@@ -596,8 +782,11 @@ impl<'a> Visitor for TemplateVisitor<'_> {
             }
         }
 
-        // Merge conditional nodes and clean up whitespace
-        optimize_children(&mut element_node.children, element_kind);
+        // Transform and validate the children, then merge conditional nodes and clean up whitespace
+        let is_pre = self.is_pre || is_pre_tag(&element_node.starting_tag.tag_name);
+        self.prepare_children(&mut element_node.children, Some(&element_node.starting_tag));
+        self.optimize_children(&mut element_node.children, element_kind, is_pre);
+        let old_is_pre = std::mem::replace(&mut self.is_pre, is_pre);
 
         // Patch flag for HTML elements which only contain interpolation and text,
         // e.g. `<p>{{ msg }}</p>`.
@@ -610,6 +799,10 @@ impl<'a> Visitor for TemplateVisitor<'_> {
         for child in element_node.children.iter_mut() {
             child.visit_mut_with(self);
 
+            if self.bindings_helper.is_prod {
+                fold_constant_node(child);
+            }
+
             match child {
                 // When Elements are present, TEXT patch flag does not apply
                 Node::Element(_) | Node::ConditionalSeq(_) => {
@@ -624,6 +817,7 @@ impl<'a> Visitor for TemplateVisitor<'_> {
                 Node::Text(_, _) | Node::Comment(_, _) => {}
             }
         }
+        self.is_pre = old_is_pre;
 
         // Add `ref_for` and `ref_key`
         if has_ref && self.v_for_scope {
@@ -709,33 +903,48 @@ impl<'a> Visitor for TemplateVisitor<'_> {
 }
 
 impl TemplateVisitor<'_> {
-    // TODO Maybe do this in parser instead, because it sometimes needs this info
     fn recognize_element_kind(&self, starting_tag: &StartingTag) -> ElementKind {
-        let tag_name = &starting_tag.tag_name;
-
-        // First, check for a built-in
-        if let Some(builtin_type) = VUE_BUILTINS.get(&tag_name) {
-            // Special case for `<component>`. If it does not have `is`, this is not a built-in
-            if tag_name.eq("component") {
-                let has_is = starting_tag
-                    .attributes
-                    .iter()
-                    .any(|attr| check_attribute_name(attr, "is"));
-
-                if !has_is {
-                    return ElementKind::Component;
-                }
-            }
+        recognize_element_kind(
+            starting_tag,
+            self.bindings_helper.is_custom_element_tag.as_ref(),
+        )
+    }
+}
 
-            return ElementKind::Builtin(*builtin_type);
-        }
+// TODO Maybe do this in parser instead, because it sometimes needs this info
+pub(super) fn recognize_element_kind(
+    starting_tag: &StartingTag,
+    is_custom_element_tag: Option<&TagPredicate>,
+) -> ElementKind {
+    let tag_name = &starting_tag.tag_name;
 
-        // Then check if this is an HTML tag
-        if is_html_tag(&starting_tag.tag_name) {
-            ElementKind::Element
-        } else {
-            ElementKind::Component
+    // Custom elements are never resolved as components or built-ins
+    if is_custom_element_tag.is_some_and(|predicate| predicate.matches(tag_name)) {
+        return ElementKind::Element;
+    }
+
+    // First, check for a built-in
+    if let Some(builtin_type) = VUE_BUILTINS.get(&tag_name) {
+        // Special case for `<component>`. If it does not have `is`, this is not a built-in
+        if tag_name.eq("component") {
+            let has_is = starting_tag
+                .attributes
+                .iter()
+                .any(|attr| check_attribute_name(attr, "is"));
+
+            if !has_is {
+                return ElementKind::Component;
+            }
         }
+
+        return ElementKind::Builtin(*builtin_type);
+    }
+
+    // Then check if this is an HTML tag
+    if is_html_tag(&starting_tag.tag_name) {
+        ElementKind::Element
+    } else {
+        ElementKind::Component
     }
 }
 
@@ -755,7 +964,10 @@ mod tests {
     use fervid_core::{ElementKind, Node, PatchHints, VForDirective, VueDirectives};
     use swc_core::common::DUMMY_SP;
 
-    use crate::test_utils::{js, to_str};
+    use crate::{
+        error::{TemplateError, TemplateErrorKind, TransformError},
+        test_utils::{js, to_str},
+    };
 
     use super::*;
 
@@ -773,6 +985,8 @@ mod tests {
             bindings_helper: &mut bindings_helper,
             current_scope: 0,
             v_for_scope: false,
+            whitespace: Default::default(),
+            is_pre: false,
         };
         assert!(matches!(
             template_visitor.recognize_element_kind(&starting_tag),
@@ -780,6 +994,56 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn it_recognizes_custom_elements() {
+        let tag = |tag_name: &str| StartingTag {
+            tag_name: tag_name.into(),
+            attributes: vec![],
+            directives: None,
+        };
+
+        let predicate = TagPredicate::from_prefixes_and_patterns(
+            vec!["ion-".to_string()],
+            &["^model-".to_string(), "^Transition$".to_string()],
+        )
+        .unwrap();
+
+        assert!(matches!(
+            recognize_element_kind(&tag("ion-button"), Some(&predicate)),
+            ElementKind::Element
+        ));
+        assert!(matches!(
+            recognize_element_kind(&tag("model-viewer"), Some(&predicate)),
+            ElementKind::Element
+        ));
+        assert!(matches!(
+            recognize_element_kind(&tag("Transition"), Some(&predicate)),
+            ElementKind::Element
+        ));
+        assert!(matches!(
+            recognize_element_kind(&tag("my-model-viewer"), Some(&predicate)),
+            ElementKind::Component
+        ));
+        assert!(matches!(
+            recognize_element_kind(&tag("ion-button"), None),
+            ElementKind::Component
+        ));
+
+        // Closure API
+        let predicate = TagPredicate::new(|tag_name| tag_name.contains('-'));
+        assert!(matches!(
+            recognize_element_kind(&tag("my-button"), Some(&predicate)),
+            ElementKind::Element
+        ));
+        assert!(matches!(
+            recognize_element_kind(&tag("MyButton"), Some(&predicate)),
+            ElementKind::Component
+        ));
+
+        // Invalid pattern
+        assert!(TagPredicate::from_prefixes_and_patterns(vec![], &["(".to_string()]).is_err());
+    }
+
     #[test]
     fn it_folds_basic_seq() {
         // <template><div>
@@ -802,10 +1066,15 @@ mod tests {
                 patch_hints: Default::default(),
                 span: DUMMY_SP,
             })],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
 
         // Template roots: one div
         assert_eq!(1, sfc_template.roots.len());
@@ -841,10 +1110,15 @@ mod tests {
         let mut sfc_template = SfcTemplateBlock {
             lang: "html".into(),
             roots: vec![if_node(), else_if_node(), else_node()],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
 
         // Template roots: one conditional sequence
         assert_eq!(1, sfc_template.roots.len());
@@ -872,10 +1146,15 @@ mod tests {
         let mut sfc_template = SfcTemplateBlock {
             lang: "html".into(),
             roots: vec![if_node(), if_node()],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
 
         // Template roots: two conditional sequences inside one root
         assert_eq!(1, sfc_template.roots.len());
@@ -906,10 +1185,15 @@ mod tests {
         let mut sfc_template = SfcTemplateBlock {
             lang: "html".into(),
             roots: vec![if_node(), else_if_node(), if_node(), else_if_node()],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
 
         // Template roots: two conditional sequences inside one root
         assert_eq!(1, sfc_template.roots.len());
@@ -929,6 +1213,49 @@ mod tests {
         check_else_if_node(&seq.else_if_nodes[0]);
     }
 
+    #[test]
+    fn it_folds_seq_separated_by_whitespace() {
+        // <template>
+        //   <h1 v-if="true">if</h1> <h2 v-else-if="foo">else-if</h2> <h3 v-else>else</h3> <h1 v-if="true">if</h1>
+        // </template>
+        let space = || Node::Text(" ".into(), DUMMY_SP);
+        let mut sfc_template = SfcTemplateBlock {
+            lang: "html".into(),
+            roots: vec![
+                if_node(),
+                space(),
+                else_if_node(),
+                space(),
+                else_node(),
+                space(),
+                if_node(),
+            ],
+            attrs: vec![],
+            span: DUMMY_SP,
+        };
+
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            WhitespaceMode::Preserve,
+        );
+
+        // Whitespace before the `v-else-if` and `v-else` is dropped, but not before the `v-if`
+        let Node::Element(ref root) = sfc_template.roots[0] else {
+            panic!("root is not an element")
+        };
+        let [Node::ConditionalSeq(ref seq), Node::Text(ref text, _), Node::ConditionalSeq(ref seq2)] =
+            root.children.as_slice()
+        else {
+            panic!("Expected sequence, text and sequence")
+        };
+        check_if_node(&seq.if_node);
+        check_else_if_node(&seq.else_if_nodes[0]);
+        check_else_node(seq.else_node.as_ref());
+        assert_eq!(" ", text);
+        check_if_node(&seq2.if_node);
+    }
+
     #[test]
     fn it_leaves_bad_nodes() {
         // <template>
@@ -938,10 +1265,21 @@ mod tests {
         let mut sfc_template = SfcTemplateBlock {
             lang: "html".into(),
             roots: vec![else_if_node(), else_node()],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        let mut bindings_helper = BindingsHelper::default();
+        transform_and_record_template(&mut sfc_template, &mut bindings_helper, Default::default());
+
+        // `v-else-if` without `v-if` is reported, `v-else` follows it and is fine
+        assert!(matches!(
+            bindings_helper.errors.as_slice(),
+            [TransformError::TemplateError(TemplateError {
+                kind: TemplateErrorKind::VElseNoAdjacentIf,
+                ..
+            })]
+        ));
 
         // Template root children: still two
         assert_eq!(1, sfc_template.roots.len());
@@ -969,9 +1307,14 @@ mod tests {
                     span: DUMMY_SP,
                 }),
             ],
+            attrs: vec![],
             span: DUMMY_SP,
         };
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
         assert_eq!(2, sfc_template.roots.len());
 
         // Should get merged
@@ -1002,9 +1345,14 @@ mod tests {
                     span: DUMMY_SP,
                 }),
             ],
+            attrs: vec![],
             span: DUMMY_SP,
         };
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
         assert_eq!(1, sfc_template.roots.len());
     }
 
@@ -1043,10 +1391,15 @@ mod tests {
                 patch_hints: Default::default(),
                 span: DUMMY_SP,
             })],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
 
         // Template roots: one div
         assert_eq!(1, sfc_template.roots.len());
@@ -1099,10 +1452,15 @@ mod tests {
         let mut sfc_template = SfcTemplateBlock {
             lang: "html".into(),
             roots: vec![no_directives1, no_directives2],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
-        transform_and_record_template(&mut sfc_template, &mut Default::default());
+        transform_and_record_template(
+            &mut sfc_template,
+            &mut Default::default(),
+            Default::default(),
+        );
 
         // Template root: both children nodes are still present
         assert_eq!(1, sfc_template.roots.len());
@@ -1160,6 +1518,7 @@ mod tests {
         let sfc_tmpl = SfcTemplateBlock {
             lang: "html".into(),
             roots: vec![],
+            attrs: vec![],
             span: DUMMY_SP,
         };
 
@@ -1179,7 +1538,11 @@ mod tests {
                 sfc_template.roots.push(Node::Element(div.clone()));
             }
             sfc_template.roots.push(Node::Element(template));
-            transform_and_record_template(&mut sfc_template, &mut Default::default());
+            transform_and_record_template(
+                &mut sfc_template,
+                &mut Default::default(),
+                Default::default(),
+            );
 
             let Some(Node::ConditionalSeq(cond)) = sfc_template.roots.pop() else {
                 panic!("root is not a conditional seq")
@@ -1339,6 +1702,163 @@ mod tests {
         };
     }
 
+    #[test]
+    fn it_handles_whitespace() {
+        let element = |tag_name: &str, children: Vec<Node>| {
+            Node::Element(ElementNode {
+                kind: ElementKind::Element,
+                starting_tag: StartingTag {
+                    tag_name: tag_name.into(),
+                    attributes: vec![],
+                    directives: None,
+                },
+                children,
+                template_scope: 0,
+                patch_hints: Default::default(),
+                span: DUMMY_SP,
+            })
+        };
+        let text = |text: &str| Node::Text(text.into(), DUMMY_SP);
+        let comment = || Node::Comment("c".into(), DUMMY_SP);
+
+        // <div>
+        //   <span>a</span> <span>b</span>
+        //   <!--c-->  <!--c-->
+        //   foo    bar\u{a0}\u{a0}baz
+        //   <pre>
+        //  a  <b>  b  </b>
+        //   </pre>
+        //   <textarea>\r\n  x  </textarea>
+        // </div>
+        let transform = |whitespace: WhitespaceMode| {
+            let mut sfc_template = SfcTemplateBlock {
+                lang: "html".into(),
+                roots: vec![element(
+                    "div",
+                    vec![
+                        text("\n  "),
+                        element("span", vec![text("a")]),
+                        text(" "),
+                        element("span", vec![text("b")]),
+                        text("\n  "),
+                        comment(),
+                        text("  "),
+                        comment(),
+                        text("\n  foo    bar\u{a0}\u{a0}baz\n  "),
+                        element(
+                            "pre",
+                            vec![
+                                text(" a  "),
+                                element("b", vec![text("  b  ")]),
+                                text("\n  "),
+                            ],
+                        ),
+                        text("\n  "),
+                        element("textarea", vec![text("\r\n  x  ")]),
+                        text("\n"),
+                    ],
+                )],
+                attrs: vec![],
+                span: DUMMY_SP,
+            };
+            transform_and_record_template(&mut sfc_template, &mut Default::default(), whitespace);
+
+            let Some(Node::Element(div)) = sfc_template.roots.pop() else {
+                panic!("Root is not an element")
+            };
+            div.children
+        };
+
+        // Simplified representation of the children for comparison
+        fn repr(nodes: &[Node]) -> String {
+            let mut out = String::new();
+            for node in nodes {
+                match node {
+                    Node::Text(t, _) => out.push_str(&format!("{:?}", t.as_ref())),
+                    Node::Comment(_, _) => out.push_str("<!---->"),
+                    Node::Element(e) => out.push_str(&format!(
+                        "<{0}>{1}</{0}>",
+                        e.starting_tag.tag_name,
+                        repr(&e.children)
+                    )),
+                    _ => out.push('?'),
+                }
+            }
+            out
+        }
+
+        assert_eq!(
+            repr(&transform(WhitespaceMode::Condense)),
+            concat!(
+                r#"<span>"a"</span>" "<span>"b"</span><!----><!---->"#,
+                r#"" foo bar\u{a0}\u{a0}baz "<pre>" a  "<b>"  b  "</b>"\n  "</pre>"#,
+                r#"<textarea>"\n  x  "</textarea>"#
+            )
+        );
+
+        assert_eq!(
+            repr(&transform(WhitespaceMode::Preserve)),
+            concat!(
+                r#"<span>"a"</span>" "<span>"b"</span>" "<!---->" "<!---->"#,
+                r#""\n  foo    bar\u{a0}\u{a0}baz\n  "<pre>" a  "<b>"  b  "</b>"\n  "</pre>" ""#,
+                r#"<textarea>"\n  x  "</textarea>"#
+            )
+        );
+    }
+
+    #[test]
+    fn it_handles_comments() {
+        // <div><!--c-->a</div>
+        let transform = |is_prod: bool, comments: Option<bool>| {
+            let mut sfc_template = SfcTemplateBlock {
+                lang: "html".into(),
+                roots: vec![Node::Element(ElementNode {
+                    kind: ElementKind::Element,
+                    starting_tag: StartingTag {
+                        tag_name: "div".into(),
+                        attributes: vec![],
+                        directives: None,
+                    },
+                    children: vec![
+                        Node::Comment("c".into(), DUMMY_SP),
+                        Node::Text("a".into(), DUMMY_SP),
+                    ],
+                    template_scope: 0,
+                    patch_hints: Default::default(),
+                    span: DUMMY_SP,
+                })],
+                attrs: vec![],
+                span: DUMMY_SP,
+            };
+            let mut bindings_helper = BindingsHelper {
+                is_prod,
+                comments,
+                ..Default::default()
+            };
+            transform_and_record_template(
+                &mut sfc_template,
+                &mut bindings_helper,
+                WhitespaceMode::Condense,
+            );
+
+            let Some(Node::Element(div)) = sfc_template.roots.pop() else {
+                panic!("Root is not an element")
+            };
+            div.children
+                .iter()
+                .filter(|child| matches!(child, Node::Comment(_, _)))
+                .count()
+        };
+
+        // DEV keeps comments, PROD removes them
+        assert_eq!(transform(false, None), 1);
+        assert_eq!(transform(true, None), 0);
+
+        // Explicit option wins
+        assert_eq!(transform(true, Some(true)), 1);
+        assert_eq!(transform(false, Some(false)), 0);
+    }
+
     // text
     fn text_node() -> Node {
         Node::Text("text".into(), DUMMY_SP)
@@ -0,0 +1,749 @@
+//! Compile-time constant folding of the template, which is applied in PROD.
+//!
+//! The template expressions are expected to be already transformed,
+//! i.e. `LiteralConst` bindings are already inlined as literals.
+
+use fervid_core::{
+    fervid_atom, AttributeOrBinding, Conditional, ConditionalNodeSequence, ElementNode, FervidAtom,
+    IntoIdent, Node, StrOrExpr,
+};
+use swc_core::{
+    common::{Span, DUMMY_SP},
+    ecma::ast::{
+        BinExpr, BinaryOp, Bool, Expr, Lit, Null, Number, Prop, PropName, PropOrSpread, Str,
+        UnaryExpr, UnaryOp,
+    },
+};
+
+use crate::ConstValue;
+
+impl ConstValue {
+    /// Evaluates an expression consisting only of literals.
+    /// Returns `None` when the expression is not a constant or is not supported.
+    pub fn evaluate(expr: &Expr) -> Option<ConstValue> {
+        match expr {
+            Expr::Lit(Lit::Str(s)) => Some(ConstValue::Str(s.value.to_string())),
+            Expr::Lit(Lit::Num(n)) => Some(ConstValue::Num(n.value)),
+            Expr::Lit(Lit::Bool(b)) => Some(ConstValue::Bool(b.value)),
+            Expr::Lit(Lit::Null(_)) => Some(ConstValue::Null),
+            Expr::Ident(ident) => match ident.sym.as_ref() {
+                "undefined" => Some(ConstValue::Undefined),
+                "NaN" => Some(ConstValue::Num(f64::NAN)),
+                "Infinity" => Some(ConstValue::Num(f64::INFINITY)),
+                _ => None,
+            },
+            Expr::Paren(paren) => ConstValue::evaluate(&paren.expr),
+            Expr::Seq(seq) => {
+                let mut last = None;
+                for expr in seq.exprs.iter() {
+                    last = Some(ConstValue::evaluate(expr)?);
+                }
+                last
+            }
+            Expr::Tpl(tpl) => {
+                let mut out = String::new();
+                for (idx, quasi) in tpl.quasis.iter().enumerate() {
+                    out.push_str(quasi.cooked.as_ref()?);
+                    if let Some(expr) = tpl.exprs.get(idx) {
+                        out.push_str(&ConstValue::evaluate(expr)?.to_js_string()?);
+                    }
+                }
+                Some(ConstValue::Str(out))
+            }
+            Expr::Cond(cond) => {
+                if ConstValue::evaluate(&cond.test)?.is_truthy() {
+                    ConstValue::evaluate(&cond.cons)
+                } else {
+                    ConstValue::evaluate(&cond.alt)
+                }
+            }
+            Expr::Unary(unary) => evaluate_unary(unary),
+            Expr::Bin(bin) => evaluate_bin(bin),
+            _ => None,
+        }
+    }
+
+    /// Same as `!!value` in JS
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            ConstValue::Str(s) => !s.is_empty(),
+            ConstValue::Num(n) => *n != 0.0 && !n.is_nan(),
+            ConstValue::Bool(b) => *b,
+            ConstValue::Null | ConstValue::Undefined => false,
+        }
+    }
+
+    /// Same as `String(value)` in JS.
+    /// Returns `None` for the numbers which JS formats in the exponential notation.
+    pub fn to_js_string(&self) -> Option<String> {
+        match self {
+            ConstValue::Str(s) => Some(s.to_owned()),
+            ConstValue::Num(n) => number_to_js_string(*n),
+            ConstValue::Bool(b) => Some(b.to_string()),
+            ConstValue::Null => Some("null".to_owned()),
+            ConstValue::Undefined => Some("undefined".to_owned()),
+        }
+    }
+
+    /// Same as `toDisplayString` from Vue
+    pub fn to_display_string(&self) -> Option<String> {
+        match self {
+            ConstValue::Null | ConstValue::Undefined => Some(String::new()),
+            _ => self.to_js_string(),
+        }
+    }
+
+    /// Converts the value back to a Js literal expression
+    pub fn to_expr(&self, span: Span) -> Expr {
+        match self {
+            ConstValue::Str(s) => Expr::Lit(Lit::Str(Str {
+                span,
+                value: FervidAtom::from(s.as_str()),
+                raw: None,
+            })),
+            ConstValue::Num(n) if n.is_nan() => {
+                Expr::Ident(fervid_atom!("NaN").into_ident_spanned(span))
+            }
+            ConstValue::Num(n) if n.is_sign_negative() => Expr::Unary(UnaryExpr {
+                span,
+                op: UnaryOp::Minus,
+                arg: Box::new(ConstValue::Num(-n).to_expr(span)),
+            }),
+            ConstValue::Num(n) if n.is_infinite() => {
+                Expr::Ident(fervid_atom!("Infinity").into_ident_spanned(span))
+            }
+            ConstValue::Num(n) => Expr::Lit(Lit::Num(Number {
+                span,
+                value: *n,
+                raw: None,
+            })),
+            ConstValue::Bool(value) => Expr::Lit(Lit::Bool(Bool {
+                span,
+                value: *value,
+            })),
+            ConstValue::Null => Expr::Lit(Lit::Null(Null { span })),
+            ConstValue::Undefined => {
+                Expr::Ident(fervid_atom!("undefined").into_ident_spanned(span))
+            }
+        }
+    }
+
+    /// Same as `Number(value)` in JS.
+    /// Returns `None` for the strings which are not plain decimals.
+    fn to_number(&self) -> Option<f64> {
+        match self {
+            ConstValue::Str(s) => {
+                let s = s.trim();
+                if s.is_empty() {
+                    return Some(0.0);
+                }
+
+                let is_decimal = s
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'+' | b'-'));
+                is_decimal.then(|| s.parse::<f64>().unwrap_or(f64::NAN))
+            }
+            ConstValue::Num(n) => Some(*n),
+            ConstValue::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            ConstValue::Null => Some(0.0),
+            ConstValue::Undefined => Some(f64::NAN),
+        }
+    }
+
+    fn type_of(&self) -> &'static str {
+        match self {
+            ConstValue::Str(_) => "string",
+            ConstValue::Num(_) => "number",
+            ConstValue::Bool(_) => "boolean",
+            ConstValue::Null => "object",
+            ConstValue::Undefined => "undefined",
+        }
+    }
+}
+
+fn evaluate_unary(unary: &UnaryExpr) -> Option<ConstValue> {
+    let arg = ConstValue::evaluate(&unary.arg)?;
+
+    match unary.op {
+        UnaryOp::Bang => Some(ConstValue::Bool(!arg.is_truthy())),
+        UnaryOp::Minus => Some(ConstValue::Num(-arg.to_number()?)),
+        UnaryOp::Plus => Some(ConstValue::Num(arg.to_number()?)),
+        UnaryOp::TypeOf => Some(ConstValue::Str(arg.type_of().to_owned())),
+        UnaryOp::Void => Some(ConstValue::Undefined),
+        _ => None,
+    }
+}
+
+fn evaluate_bin(bin: &BinExpr) -> Option<ConstValue> {
+    let left = ConstValue::evaluate(&bin.left)?;
+
+    // Short-circuiting operators do not need the right side
+    match bin.op {
+        BinaryOp::LogicalAnd if !left.is_truthy() => return Some(left),
+        BinaryOp::LogicalOr if left.is_truthy() => return Some(left),
+        BinaryOp::NullishCoalescing
+            if !matches!(left, ConstValue::Null | ConstValue::Undefined) =>
+        {
+            return Some(left)
+        }
+        BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing => {
+            return ConstValue::evaluate(&bin.right)
+        }
+        _ => {}
+    }
+
+    let right = ConstValue::evaluate(&bin.right)?;
+
+    macro_rules! numeric {
+        ($op: expr) => {{
+            let op = $op;
+            Some(ConstValue::Num(op(left.to_number()?, right.to_number()?)))
+        }};
+    }
+
+    macro_rules! compare {
+        ($op: expr) => {{
+            let op = $op;
+            let ordering = match (&left, &right) {
+                // Strings are compared by UTF-16 code units
+                (ConstValue::Str(l), ConstValue::Str(r)) => {
+                    Some(l.encode_utf16().cmp(r.encode_utf16()))
+                }
+                _ => left.to_number()?.partial_cmp(&right.to_number()?),
+            };
+            Some(ConstValue::Bool(ordering.is_some_and(op)))
+        }};
+    }
+
+    match bin.op {
+        BinaryOp::Add => match (&left, &right) {
+            (ConstValue::Str(_), _) | (_, ConstValue::Str(_)) => {
+                let mut out = left.to_js_string()?;
+                out.push_str(&right.to_js_string()?);
+                Some(ConstValue::Str(out))
+            }
+            _ => numeric!(|l: f64, r: f64| l + r),
+        },
+        BinaryOp::Sub => numeric!(|l: f64, r: f64| l - r),
+        BinaryOp::Mul => numeric!(|l: f64, r: f64| l * r),
+        BinaryOp::Div => numeric!(|l: f64, r: f64| l / r),
+        BinaryOp::Mod => numeric!(|l: f64, r: f64| l % r),
+        BinaryOp::Exp => numeric!(|l: f64, r: f64| {
+            // `powf` differs from JS for these
+            if r.is_nan() || (l.abs() == 1.0 && r.is_infinite()) {
+                f64::NAN
+            } else {
+                l.powf(r)
+            }
+        }),
+        BinaryOp::EqEqEq => Some(ConstValue::Bool(strict_equals(&left, &right))),
+        BinaryOp::NotEqEq => Some(ConstValue::Bool(!strict_equals(&left, &right))),
+        BinaryOp::Lt => compare!(|o: std::cmp::Ordering| o.is_lt()),
+        BinaryOp::LtEq => compare!(|o: std::cmp::Ordering| o.is_le()),
+        BinaryOp::Gt => compare!(|o: std::cmp::Ordering| o.is_gt()),
+        BinaryOp::GtEq => compare!(|o: std::cmp::Ordering| o.is_ge()),
+        _ => None,
+    }
+}
+
+fn strict_equals(left: &ConstValue, right: &ConstValue) -> bool {
+    match (left, right) {
+        // `NaN !== NaN` and `0 === -0` are handled by `f64` itself
+        (ConstValue::Num(l), ConstValue::Num(r)) => l == r,
+        _ => left == right,
+    }
+}
+
+fn number_to_js_string(n: f64) -> Option<String> {
+    if n.is_nan() {
+        Some("NaN".to_owned())
+    } else if n.is_infinite() {
+        Some(if n > 0.0 { "Infinity" } else { "-Infinity" }.to_owned())
+    } else if n == 0.0 {
+        Some("0".to_owned())
+    } else if n.abs() >= 1e21 || n.abs() < 1e-6 {
+        None
+    } else {
+        Some(n.to_string())
+    }
+}
+
+/// Folds the constant interpolations into text
+/// and removes the dead branches of the conditional sequences.
+/// The node must be already transformed.
+pub fn fold_constant_node(node: &mut Node) {
+    match node {
+        Node::Interpolation(interpolation) => {
+            let Some(text) =
+                ConstValue::evaluate(&interpolation.value).and_then(|v| v.to_display_string())
+            else {
+                return;
+            };
+
+            *node = Node::Text(FervidAtom::from(text), interpolation.span);
+        }
+
+        Node::ConditionalSeq(seq) => {
+            let has_constant_condition = std::iter::once(&seq.if_node.condition)
+                .chain(seq.else_if_nodes.iter().map(|it| &it.condition))
+                .any(|condition| ConstValue::evaluate(condition).is_some());
+            if !has_constant_condition {
+                return;
+            }
+
+            let Node::ConditionalSeq(seq) =
+                std::mem::replace(node, Node::Text(fervid_atom!(""), DUMMY_SP))
+            else {
+                unreachable!()
+            };
+            *node = fold_conditional_seq(seq);
+        }
+
+        _ => {}
+    }
+}
+
+fn fold_conditional_seq(seq: ConditionalNodeSequence) -> Node {
+    let span = seq.if_node.node.span;
+    let ConditionalNodeSequence {
+        if_node,
+        else_if_nodes,
+        else_node,
+    } = seq;
+
+    // Conditions which are only known at runtime
+    let mut conditionals: Vec<Conditional> = Vec::with_capacity(1 + else_if_nodes.len());
+    // Node rendered when no runtime condition is met
+    let mut fallback: Option<ElementNode> = None;
+
+    for conditional in std::iter::once(*if_node).chain(else_if_nodes) {
+        match ConstValue::evaluate(&conditional.condition) {
+            // Always rendered, the following branches are dead
+            Some(value) if value.is_truthy() => {
+                fallback = Some(conditional.node);
+                break;
+            }
+            // Never rendered
+            Some(_) => {}
+            None => conditionals.push(conditional),
+        }
+    }
+    let fallback = fallback.or_else(|| else_node.map(|it| *it));
+
+    let mut conditionals = conditionals.into_iter();
+    match (conditionals.next(), fallback) {
+        (Some(if_node), else_node) => Node::ConditionalSeq(ConditionalNodeSequence {
+            if_node: Box::new(if_node),
+            else_if_nodes: conditionals.collect(),
+            else_node: else_node.map(Box::new),
+        }),
+
+        // `<template>` can only be generated as a fragment inside the sequence
+        (None, Some(node)) if node.starting_tag.tag_name == "template" => {
+            Node::ConditionalSeq(ConditionalNodeSequence {
+                if_node: Box::new(Conditional {
+                    condition: ConstValue::Bool(true).to_expr(span),
+                    node,
+                }),
+                else_if_nodes: vec![],
+                else_node: None,
+            })
+        }
+
+        (None, Some(node)) => Node::Element(node),
+
+        // Nothing is rendered, the official compiler generates a `v-if` comment as well
+        (None, None) => Node::Comment(fervid_atom!("v-if"), span),
+    }
+}
+
+/// Pre-evaluates a constant `:class` or `:style` value into a static attribute value
+pub fn fold_class_or_style(name: &str, value: &Expr) -> Option<String> {
+    match name {
+        "class" => normalize_class(value),
+        "style" => stringify_style(value),
+        _ => None,
+    }
+}
+
+/// Replaces the constant `:class` and `:style` bindings with the regular attributes,
+/// merging them with the already existing ones.
+pub fn fold_static_attributes(attributes: &mut Vec<AttributeOrBinding>) {
+    for (name, separator) in [("class", " "), ("style", ";")] {
+        let mut folded: Vec<(String, Span)> = Vec::new();
+        attributes.retain(|attr| {
+            let AttributeOrBinding::VBind(v_bind) = attr else {
+                return true;
+            };
+            if v_bind.is_prop
+                || !matches!(v_bind.argument, Some(StrOrExpr::Str(ref argument)) if argument == name)
+            {
+                return true;
+            }
+
+            match fold_class_or_style(name, &v_bind.value) {
+                Some(value) => {
+                    folded.push((value, v_bind.span));
+                    false
+                }
+                None => true,
+            }
+        });
+
+        if folded.is_empty() {
+            continue;
+        }
+
+        // Static value goes first, same as in `normalizeClass(["static", bound])`
+        let existing = attributes.iter_mut().find_map(|attr| match attr {
+            AttributeOrBinding::RegularAttribute {
+                name: attr_name,
+                value,
+                ..
+            } if attr_name == name => Some(value),
+            _ => None,
+        });
+        let mut parts: Vec<String> = existing
+            .as_ref()
+            .map(|value| vec![value.to_string()])
+            .unwrap_or_default();
+        parts.extend(folded.iter().map(|(value, _)| value.to_owned()));
+        parts.retain(|part| !part.trim().is_empty());
+        let merged = FervidAtom::from(parts.join(separator));
+
+        match existing {
+            Some(value) => *value = merged,
+            None => attributes.push(AttributeOrBinding::RegularAttribute {
+                name: FervidAtom::from(name),
+                value: merged,
+                span: folded[0].1,
+            }),
+        }
+    }
+}
+
+/// Same as `normalizeClass` from Vue
+fn normalize_class(expr: &Expr) -> Option<String> {
+    let mut classes: Vec<String> = Vec::new();
+
+    match expr {
+        Expr::Paren(paren) => return normalize_class(&paren.expr),
+
+        Expr::Array(array) => {
+            for elem in array.elems.iter().flatten() {
+                if elem.spread.is_some() {
+                    return None;
+                }
+                classes.push(normalize_class(&elem.expr)?);
+            }
+        }
+
+        Expr::Object(obj) => {
+            for prop in obj.props.iter() {
+                let PropOrSpread::Prop(prop) = prop else {
+                    return None;
+                };
+                let Prop::KeyValue(key_value) = prop.as_ref() else {
+                    return None;
+                };
+                let key = prop_name_to_string(&key_value.key)?;
+
+                // Duplicate keys would need the JS semantics of overwriting
+                if classes.contains(&key) {
+                    return None;
+                }
+                if ConstValue::evaluate(&key_value.value)?.is_truthy() {
+                    classes.push(key);
+                }
+            }
+        }
+
+        _ => {
+            if let ConstValue::Str(class) = ConstValue::evaluate(expr)? {
+                classes.push(class);
+            }
+        }
+    }
+
+    classes.retain(|class| !class.trim().is_empty());
+    Some(classes.join(" ").trim().to_owned())
+}
+
+/// Converts a constant style value into a `style` attribute string
+fn stringify_style(expr: &Expr) -> Option<String> {
+    let mut declarations: Vec<String> = Vec::new();
+
+    match expr {
+        Expr::Paren(paren) => return stringify_style(&paren.expr),
+
+        Expr::Array(array) => {
+            for elem in array.elems.iter().flatten() {
+                if elem.spread.is_some() {
+                    return None;
+                }
+                declarations.push(stringify_style(&elem.expr)?);
+            }
+        }
+
+        Expr::Object(obj) => {
+            for prop in obj.props.iter() {
+                let PropOrSpread::Prop(prop) = prop else {
+                    return None;
+                };
+                let Prop::KeyValue(key_value) = prop.as_ref() else {
+                    return None;
+                };
+                let key = prop_name_to_string(&key_value.key)?;
+                let value = match ConstValue::evaluate(&key_value.value)? {
+                    ConstValue::Str(value) => value,
+                    ConstValue::Num(value) if value.is_finite() => number_to_js_string(value)?,
+                    // Other values are ignored by the runtime
+                    _ => continue,
+                };
+
+                // Values like this would not survive the parsing of the `style` attribute
+                if value.contains(';') {
+                    return None;
+                }
+
+                let key = if key.starts_with("--") {
+                    key
+                } else {
+                    hyphenate(&key)
+                };
+                declarations.push(format!("{key}:{value}"));
+            }
+        }
+
+        _ => match ConstValue::evaluate(expr)? {
+            ConstValue::Str(style) => declarations.push(style),
+            ConstValue::Num(_) => return None,
+            _ => {}
+        },
+    }
+
+    declarations.retain(|declaration| !declaration.trim().is_empty());
+    Some(declarations.join(";"))
+}
+
+/// Gets the static object key. Numeric keys are not supported, as JS reorders them.
+fn prop_name_to_string(prop_name: &PropName) -> Option<String> {
+    let key = match prop_name {
+        PropName::Ident(ident) => ident.sym.to_string(),
+        PropName::Str(s) => s.value.to_string(),
+        _ => return None,
+    };
+
+    if !key.is_empty() && key.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(key)
+}
+
+/// `fontSize` -> `font-size`
+fn hyphenate(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    for (idx, c) in s.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if idx != 0 {
+                out.push('-');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::common::DUMMY_SP;
+
+    use super::*;
+    use crate::{
+        test_utils::{js, sfc::transform, to_str},
+        TransformSfcOptions,
+    };
+
+    /// Roots of a `<template>` transformed in PROD, where the constants are folded
+    fn fold(template: &str) -> Vec<Node> {
+        let (result, _) = transform(
+            &format!("<template>{}</template>", template),
+            TransformSfcOptions {
+                is_prod: true,
+                ..Default::default()
+            },
+        );
+        result.template_block.expect("Expected a template").roots
+    }
+
+    #[test]
+    fn it_evaluates_constants() {
+        macro_rules! test {
+            ($input: expr, $expected: expr) => {
+                assert_eq!(ConstValue::evaluate(&js($input)), $expected, "{}", $input)
+            };
+        }
+
+        test!("'foo' + 1", Some(ConstValue::Str("foo1".into())));
+        test!("1 + 2 * 3", Some(ConstValue::Num(7.0)));
+        test!("0.1 + 0.2", Some(ConstValue::Num(0.1 + 0.2)));
+        test!(
+            "`a${1 + 1}b${true}`",
+            Some(ConstValue::Str("a2btrue".into()))
+        );
+        test!("!''", Some(ConstValue::Bool(true)));
+        test!("-'3'", Some(ConstValue::Num(-3.0)));
+        test!("typeof null", Some(ConstValue::Str("object".into())));
+        test!("void 0", Some(ConstValue::Undefined));
+        test!("1 === 1.0", Some(ConstValue::Bool(true)));
+        test!("NaN !== NaN", Some(ConstValue::Bool(true)));
+        test!("'b' > 'a'", Some(ConstValue::Bool(true)));
+        test!("'10' < 9", Some(ConstValue::Bool(false)));
+        test!("null ?? 'x'", Some(ConstValue::Str("x".into())));
+        test!("0 || 'x'", Some(ConstValue::Str("x".into())));
+        test!("false && foo", Some(ConstValue::Bool(false)));
+        test!("true ? 'a' : 'b'", Some(ConstValue::Str("a".into())));
+        test!("(1, 2)", Some(ConstValue::Num(2.0)));
+
+        // Not constants or not supported
+        test!("foo", None);
+        test!("true && foo", None);
+        test!("1 == 1", None);
+        test!("-'abc'", None);
+        test!("[1]", None);
+        test!("`${1e21}`", None);
+    }
+
+    #[test]
+    fn it_formats_numbers() {
+        let test = |n: f64| ConstValue::Num(n).to_display_string();
+
+        assert_eq!(test(1.0).as_deref(), Some("1"));
+        assert_eq!(test(-0.0).as_deref(), Some("0"));
+        assert_eq!(test(0.5).as_deref(), Some("0.5"));
+        assert_eq!(test(1e20).as_deref(), Some("100000000000000000000"));
+        assert_eq!(test(f64::NAN).as_deref(), Some("NaN"));
+        assert_eq!(test(f64::NEG_INFINITY).as_deref(), Some("-Infinity"));
+        assert_eq!(test(1e21), None);
+        assert_eq!(ConstValue::Null.to_display_string().as_deref(), Some(""));
+
+        assert_eq!(to_str(&ConstValue::Num(-2.0).to_expr(DUMMY_SP)), "-2");
+        assert_eq!(to_str(&ConstValue::Num(f64::NAN).to_expr(DUMMY_SP)), "NaN");
+    }
+
+    #[test]
+    fn it_folds_class_and_style() {
+        let class = |input: &str| fold_class_or_style("class", &js(input));
+        let style = |input: &str| fold_class_or_style("style", &js(input));
+
+        assert_eq!(class("'a b'").as_deref(), Some("a b"));
+        assert_eq!(
+            class("['a', { b: true, c: 0, 'd-e': 'yes' }, ['f'], null]").as_deref(),
+            Some("a b d-e f")
+        );
+        assert_eq!(class("{ a: foo }"), None);
+        assert_eq!(class("{ [a]: true }"), None);
+        assert_eq!(class("{ 1: true }"), None);
+
+        assert_eq!(
+            style("{ color: 'red', fontSize: 12 + 'px', '--my-var': 1, width: null }").as_deref(),
+            Some("color:red;font-size:12px;--my-var:1")
+        );
+        assert_eq!(
+            style("['color: red', { display: 'none' }]").as_deref(),
+            Some("color: red;display:none")
+        );
+        assert_eq!(style("{ color: foo }"), None);
+        assert_eq!(style("{ color: 'red;' }"), None);
+    }
+
+    #[test]
+    fn it_folds_static_attributes() {
+        let roots = fold(
+            r#"<div class="static" :class="{ active: true }" :style="{ color: 'red' }" :title="'hello'"></div>"#,
+        );
+        let [Node::Element(div)] = roots.as_slice() else {
+            panic!("Expected an element")
+        };
+        let attributes = &div.starting_tag.attributes;
+
+        assert_eq!(attributes.len(), 3);
+        assert!(matches!(
+            &attributes[0],
+            AttributeOrBinding::RegularAttribute { name, value, .. }
+            if name == "class" && value == "static active"
+        ));
+        assert!(matches!(&attributes[1], AttributeOrBinding::VBind(_)));
+        assert!(matches!(
+            &attributes[2],
+            AttributeOrBinding::RegularAttribute { name, value, .. }
+            if name == "style" && value == "color:red"
+        ));
+    }
+
+    #[test]
+    fn it_folds_interpolations() {
+        let roots = fold("<div><p>{{ 'count: ' + 2 }}</p><p>{{ foo }}</p></div>");
+        let [Node::Element(div)] = roots.as_slice() else {
+            panic!("Expected an element")
+        };
+        let [Node::Element(constant), Node::Element(dynamic)] = div.children.as_slice() else {
+            panic!("Expected two elements")
+        };
+
+        assert!(matches!(
+            constant.children.as_slice(),
+            [Node::Text(text, _)] if text == "count: 2"
+        ));
+        assert!(matches!(
+            dynamic.children.as_slice(),
+            [Node::Interpolation(_)]
+        ));
+    }
+
+    #[test]
+    fn it_folds_conditional_sequences() {
+        let tag_name = |node: &Node| match node {
+            Node::Element(el) => el.starting_tag.tag_name.to_string(),
+            _ => panic!("Expected an element"),
+        };
+
+        // `v-if="true"` -> element
+        let roots = fold(r#"<h1 v-if="true"></h1><h2 v-else></h2>"#);
+        assert_eq!(tag_name(&roots[0]), "h1");
+
+        // `v-if="false"` -> `v-else`
+        let roots = fold(r#"<h1 v-if="false"></h1><h2 v-else></h2>"#);
+        assert_eq!(tag_name(&roots[0]), "h2");
+
+        // Nothing to render
+        let roots = fold(r#"<h1 v-if="0"></h1><h2 v-else-if="''"></h2>"#);
+        assert!(matches!(roots[0], Node::Comment(ref text, _) if text == "v-if"));
+
+        // Dead branches are removed, the first truthy one becomes `v-else`
+        let roots = fold(
+            r#"<h1 v-if="false"></h1><h2 v-else-if="foo"></h2><h3 v-else-if="1"></h3><h4 v-else-if="bar"></h4><h5 v-else></h5>"#,
+        );
+        let Node::ConditionalSeq(ref seq) = roots[0] else {
+            panic!("Expected a conditional sequence")
+        };
+        assert_eq!(to_str(&seq.if_node.condition), "_ctx.foo");
+        assert_eq!(seq.if_node.node.starting_tag.tag_name, "h2");
+        assert!(seq.else_if_nodes.is_empty());
+        assert!(matches!(seq.else_node, Some(ref el) if el.starting_tag.tag_name == "h3"));
+
+        // Unknown conditions are kept as they are
+        let roots = fold(r#"<h1 v-if="foo"></h1><h2 v-else-if="bar"></h2>"#);
+        assert!(matches!(roots[0], Node::ConditionalSeq(ref seq) if seq.else_if_nodes.len() == 1));
+
+        // `<template>` stays in the sequence to be generated as a fragment
+        let roots = fold(r#"<template v-if="true"><h1></h1><h2></h2></template>"#);
+        let Node::ConditionalSeq(ref seq) = roots[0] else {
+            panic!("Expected a conditional sequence")
+        };
+        assert_eq!(to_str(&seq.if_node.condition), "true");
+    }
+}
@@ -6,19 +6,22 @@ use swc_core::{
     common::DUMMY_SP,
     ecma::{
         ast::{
-            ArrayLit, ArrayPat, AssignExpr, AssignOp, AssignTarget, AssignTargetPat, BindingIdent,
-            BlockStmt, CallExpr, Callee, CondExpr, Decl, Expr, ExprOrSpread, Ident, IdentName,
-            KeyValuePatProp, KeyValueProp, Lit, MemberExpr, MemberProp, Null, ObjectLit, ObjectPat,
-            ObjectPatProp, Pat, Prop, PropName, PropOrSpread, SimpleAssignTarget, Stmt, UpdateExpr,
-            UpdateOp,
+            ArrayLit, ArrayPat, AssignExpr, AssignOp, AssignPat, AssignPatProp, AssignProp,
+            AssignTarget, AssignTargetPat, BindingIdent, BlockStmt, CallExpr, Callee, CondExpr,
+            Decl, Expr, ExprOrSpread, Ident, IdentName, KeyValuePatProp, KeyValueProp, Lit,
+            MemberExpr, MemberProp, Null, ObjectLit, ObjectPat, ObjectPatProp, ParenExpr, Pat,
+            Prop, PropName, PropOrSpread, RestPat, SimpleAssignTarget, SpreadElement, Stmt,
+            UpdateExpr, UpdateOp,
         },
         visit::{VisitMut, VisitMutWith},
     },
 };
 
 use crate::{
-    script::common::extract_variables_from_pat, template::js_builtins::JS_BUILTINS, BindingsHelper,
-    SetupBinding,
+    error::{TemplateError, TemplateErrorKind, TransformError},
+    script::common::extract_variables_from_pat,
+    template::js_builtins::JS_BUILTINS,
+    BindingsHelper, ConstValue, SetupBinding,
 };
 
 use super::utils::wrap_in_event_arrow;
@@ -61,6 +64,8 @@ pub enum IdentTransformStrategy {
     Prefix(FervidAtom),
     /// Generate `isRef(e) ? e.value++ : e++`
     IsRefCheckUpdate,
+    /// Replace with the value of a literal constant (PROD only)
+    Inline(ConstValue),
 }
 
 pub trait BindingsHelperTransform {
@@ -109,20 +114,53 @@ impl BindingsHelperTransform for BindingsHelper {
     ) {
         // 0. Ensure that `v-model` value is a valid AssignTarget
         let Some(assign_target) = convert_expr_to_assign_target(v_model.value.to_owned()) else {
-            // TODO Error
+            self.errors
+                .push(TransformError::TemplateError(TemplateError {
+                    span: v_model.span,
+                    kind: TemplateErrorKind::VModelMalformedExpression,
+                }));
             return;
         };
 
-        // 1. Create handler: wrap in `$event => value = $event`
-        let event_expr = Box::new(Expr::Ident(FervidAtom::from("$event").into_ident()));
-        let mut handler = wrap_in_event_arrow(wrap_in_assignment(
-            assign_target,
-            event_expr,
-            AssignOp::Assign,
-        ));
+        // 1. Ensure that the variable itself can be reassigned.
+        // Imports are maybe-refs like in the official compiler, e.g. a `ref` exported from a store
+        let mut is_const = false;
+        if let Expr::Ident(ref ident) = *v_model.value {
+            let error_kind = match self.get_var_binding_type(scope_to_use, &ident.sym) {
+                BindingTypes::SetupConst
+                | BindingTypes::SetupReactiveConst
+                | BindingTypes::LiteralConst
+                | BindingTypes::Component => {
+                    is_const = true;
+                    Some(TemplateErrorKind::VModelOnConst)
+                }
+                BindingTypes::Props | BindingTypes::PropsAliased => {
+                    Some(TemplateErrorKind::VModelOnProps)
+                }
+                BindingTypes::TemplateLocal => Some(TemplateErrorKind::VModelOnScopeVariable),
+                _ => None,
+            };
 
-        // 2. Transform handler
-        {
+            if let Some(kind) = error_kind {
+                self.errors
+                    .push(TransformError::TemplateError(TemplateError {
+                        span: v_model.span,
+                        kind,
+                    }));
+            }
+        }
+
+        // 2. Create handler: wrap in `$event => value = $event`.
+        // A const cannot be reassigned, the handler would throw at runtime
+        if !is_const {
+            let event_expr = Box::new(Expr::Ident(FervidAtom::from("$event").into_ident()));
+            let mut handler = wrap_in_event_arrow(wrap_in_assignment(
+                assign_target,
+                event_expr,
+                AssignOp::Assign,
+            ));
+
+            // 3. Transform handler
             let is_inline = matches!(
                 self.template_generation_mode,
                 TemplateGenerationMode::Inline
@@ -140,15 +178,15 @@ impl BindingsHelperTransform for BindingsHelper {
                 should_consume_update_expr: false,
             };
             handler.visit_mut_with(&mut visitor);
-        }
 
-        // 3. Assign handler
-        v_model.update_handler = Some(handler);
+            // 4. Assign handler
+            v_model.update_handler = Some(handler);
+        }
 
-        // 4. Transform value
+        // 5. Transform value
         self.transform_expr(&mut v_model.value, scope_to_use);
 
-        // 5. (Optional) Transform dynamic argument and set patch hints
+        // 6. (Optional) Transform dynamic argument and set patch hints
         match v_model.argument {
             Some(StrOrExpr::Expr(ref mut expr)) => {
                 self.transform_expr(expr, scope_to_use);
@@ -165,10 +203,6 @@ impl BindingsHelperTransform for BindingsHelper {
                 patch_hints.props.push(fervid_atom!("modelValue"));
             }
         }
-
-        // TODO Check that SetupConst or SetupReactiveConst are not used as a `v-model` value. Report hard error in this case.
-        // TODO Check in general in all cases
-        // DOCTEXT: Disallow `SetupConst` or `SetupReactiveConst` to be used as a `v-model` value or as an assignment target.
     }
 
     fn get_var_binding_type(&mut self, starting_scope: u32, variable: &FervidAtom) -> BindingTypes {
@@ -176,24 +210,9 @@ impl BindingsHelperTransform for BindingsHelper {
             return BindingTypes::JsGlobal;
         }
 
-        let mut current_scope_index = starting_scope;
-
         // Check template scope
-        while let Some(current_scope) = self.template_scopes.get(current_scope_index as usize) {
-            // Check variable existence in the current scope
-            let found = current_scope.variables.iter().find(|it| *it == variable);
-
-            if let Some(_) = found {
-                return BindingTypes::TemplateLocal;
-            }
-
-            // Check if we reached the root scope, it will have itself as a parent
-            if current_scope.parent == current_scope_index {
-                break;
-            }
-
-            // Go to parent
-            current_scope_index = current_scope.parent;
+        if self.is_template_local(starting_scope, variable) {
+            return BindingTypes::TemplateLocal;
         }
 
         // Check hash-map for convenience (we may have found the reference previously)
@@ -258,34 +277,29 @@ impl<'s> VisitMut for TransformVisitor<'s> {
 
                 // Check for special case: LHS is an ident of type `SetupLet`
                 // Also special case: LHS is `SetupMaybeRef` inside v-model
-                // This is only valid for Inline mode
-                let setup_let_ident = if self.is_inline {
-                    let ident = match assign_expr.left {
-                        AssignTarget::Simple(SimpleAssignTarget::Ident(ref id)) => Some(&id.id),
-                        _ => None,
-                    };
-
-                    ident.and_then(|ident| {
-                        let binding_type = self
-                            .bindings_helper
-                            .get_var_binding_type(self.current_scope, &ident.sym);
-
-                        if matches!(binding_type, BindingTypes::SetupLet)
-                            || self.is_v_model_transform
-                                && matches!(binding_type, BindingTypes::SetupMaybeRef)
-                        {
-                            Some((ident, binding_type))
-                        } else {
-                            None
-                        }
-                    })
-                } else {
-                    None
+                // This is only valid for Inline mode, except for the imports inside v-model:
+                // they are not on `$setup` and are used as is in both modes
+                let ident = match assign_expr.left {
+                    AssignTarget::Simple(SimpleAssignTarget::Ident(ref id)) => Some(&id.id),
+                    _ => None,
                 };
+                let setup_let_ident = ident.and_then(|ident| {
+                    let binding_type = self
+                        .bindings_helper
+                        .get_var_binding_type(self.current_scope, &ident.sym);
+
+                    let needs_is_ref_check = match binding_type {
+                        BindingTypes::SetupLet => self.is_inline,
+                        BindingTypes::SetupMaybeRef => self.is_inline && self.is_v_model_transform,
+                        BindingTypes::Imported => self.is_v_model_transform,
+                        _ => false,
+                    };
+                    needs_is_ref_check.then_some((ident, binding_type))
+                });
 
-                // Special case for `SetupLet` or `SetupMaybeRef`: generate `isRef` check
+                // Special case for `SetupLet`, `SetupMaybeRef` or `Imported`: generate `isRef` check
                 if let Some((ident, binding_type)) = setup_let_ident {
-                    // SetupMaybeRef is constant, reassignment is not possible
+                    // SetupMaybeRef and imports are constant, reassignment is not possible
                     let is_reassignable = matches!(binding_type, BindingTypes::SetupLet);
 
                     *expr = *generate_is_ref_check_assignment(
@@ -360,7 +374,7 @@ impl<'s> VisitMut for TransformVisitor<'s> {
                 }
                 return;
             }
-            
+
             // Call expression, type arguments should not be taken into account
             Expr::Call(call_expr) => {
                 call_expr.callee.visit_mut_with(self);
@@ -425,6 +439,10 @@ impl<'s> VisitMut for TransformVisitor<'s> {
                 return;
             }
 
+            IdentTransformStrategy::Inline(value) => {
+                *expr = value.to_expr(span);
+            }
+
             IdentTransformStrategy::IsRefCheckUpdate => {
                 let Some((update_op, update_prefix)) = self.update_expr_helper.take() else {
                     // TODO This should be unreachable, signify error
@@ -557,7 +575,8 @@ impl<'s> VisitMut for TransformVisitor<'s> {
                         }
 
                         IdentTransformStrategy::Unref
-                        | IdentTransformStrategy::IsRefCheckUpdate => {
+                        | IdentTransformStrategy::IsRefCheckUpdate
+                        | IdentTransformStrategy::Inline(_) => {
                             // TODO Error: this is not a valid transform strategy
                             // Error hint: this is a bug in `fervid`, please report it
                         }
@@ -630,7 +649,9 @@ impl<'s> VisitMut for TransformVisitor<'s> {
                         return;
                     }
 
-                    IdentTransformStrategy::Unref | IdentTransformStrategy::IsRefCheckUpdate => {
+                    IdentTransformStrategy::Unref
+                    | IdentTransformStrategy::IsRefCheckUpdate
+                    | IdentTransformStrategy::Inline(_) => {
                         // TODO Error: this is not a valid transform strategy
                         // (technically this is a syntax error, so should be impossible)
                     }
@@ -663,11 +684,13 @@ impl<'s> VisitMut for TransformVisitor<'s> {
                 ObjectPatProp::KeyValue(key_value) => {
                     key_value.value.visit_mut_with(self);
 
+                    // Keys are not variables, with the exception of computed ones: `{ [x]: y }`
                     match key_value.key {
-                        // TODO Finish the implementation
-                        PropName::Ident(_) => todo!(),
-                        PropName::Computed(_) => todo!(),
-                        PropName::Str(_) | PropName::Num(_) | PropName::BigInt(_) => {}
+                        PropName::Computed(ref mut computed) => computed.expr.visit_mut_with(self),
+                        PropName::Ident(_)
+                        | PropName::Str(_)
+                        | PropName::Num(_)
+                        | PropName::BigInt(_) => {}
                     }
                 }
 
@@ -730,6 +753,28 @@ impl TransformVisitor<'_> {
             return IdentTransformStrategy::LeaveUnchanged;
         }
 
+        // Literal constants are inlined in PROD unless they are (wrongly) assigned to
+        if matches!(binding_type, BindingTypes::LiteralConst)
+            && self.bindings_helper.is_prod
+            && !self.is_in_assign_target
+            && !self.is_in_destructure_assign
+            && self.update_expr_helper.is_none()
+        {
+            if let Some(value) = self.bindings_helper.literal_consts.get(symbol) {
+                return IdentTransformStrategy::Inline(value.to_owned());
+            }
+        }
+
+        // Aliased props need the original prop name, which is only known in the inline mode
+        if matches!(binding_type, BindingTypes::PropsAliased) && !self.is_inline {
+            self.bindings_helper
+                .errors
+                .push(TransformError::TemplateError(TemplateError {
+                    span: ident.span,
+                    kind: TemplateErrorKind::PropsAliasedUnsupported,
+                }));
+        }
+
         // Get the prefix which fits the scope (e.g. `_ctx.` for unknown scopes, `$setup.` for setup scope)
         if let Some(prefix) = get_prefix(&binding_type, self.is_inline) {
             self.has_js_bindings = true;
@@ -806,14 +851,15 @@ pub fn get_prefix(binding_type: &BindingTypes, is_inline: bool) -> Option<Fervid
         | BindingTypes::JsGlobal
         | BindingTypes::LiteralConst
         | BindingTypes::Component
-        | BindingTypes::Imported => None,
+        | BindingTypes::Imported
+        // The alias has no matching field on `$props`, this case is reported by the transform
+        | BindingTypes::PropsAliased => None,
         BindingTypes::SetupConst
         | BindingTypes::SetupLet
         | BindingTypes::SetupMaybeRef
         | BindingTypes::SetupReactiveConst
         | BindingTypes::SetupRef => Some(FervidAtom::from("$setup")),
         BindingTypes::Unresolved => Some(FervidAtom::from("_ctx")),
-        BindingTypes::PropsAliased => unimplemented!(),
     }
 }
 
@@ -939,23 +985,35 @@ fn generate_is_ref_check_update(
 /// Wraps `expr` to `expr = $event`
 #[inline]
 fn wrap_in_assignment(lhs: AssignTarget, rhs_expr: Box<Expr>, op: AssignOp) -> Box<Expr> {
-    Box::new(Expr::Assign(AssignExpr {
+    // `{ a } = $event` must be parenthesized, otherwise it is a block
+    let needs_paren = matches!(lhs, AssignTarget::Pat(AssignTargetPat::Object(_)));
+
+    let assignment = Box::new(Expr::Assign(AssignExpr {
         span: DUMMY_SP,
         op,
         left: lhs,
         right: rhs_expr,
-    }))
+    }));
+
+    if needs_paren {
+        Box::new(Expr::Paren(ParenExpr {
+            span: DUMMY_SP,
+            expr: assignment,
+        }))
+    } else {
+        assignment
+    }
 }
 
 fn convert_expr_to_assign_target(expr: Box<Expr>) -> Option<AssignTarget> {
     // Because AssignTarget is strongly typed, we have to map from `Expr` to `AssignTarget`
     match *expr {
         Expr::Array(arr) => Some(AssignTarget::Pat(AssignTargetPat::Array(
-            convert_arr_lit_to_pat(arr),
+            convert_arr_lit_to_pat(arr)?,
         ))),
 
         Expr::Object(obj) => Some(AssignTarget::Pat(AssignTargetPat::Object(
-            convert_obj_lit_to_pat(obj),
+            convert_obj_lit_to_pat(obj)?,
         ))),
 
         Expr::Ident(ident) => Some(AssignTarget::Simple(SimpleAssignTarget::Ident(
@@ -990,17 +1048,132 @@ fn convert_expr_to_assign_target(expr: Box<Expr>) -> Option<AssignTarget> {
     }
 }
 
-fn convert_arr_lit_to_pat(_arr_lit: ArrayLit) -> ArrayPat {
-    todo!()
+/// Converts `[a, b.c, ...d]` used as an assignment target to an `ArrayPat`
+fn convert_arr_lit_to_pat(arr_lit: ArrayLit) -> Option<ArrayPat> {
+    let mut elems = Vec::with_capacity(arr_lit.elems.len());
+
+    for elem in arr_lit.elems {
+        let pat = match elem {
+            // A hole, e.g. `[, a]`
+            None => None,
+
+            Some(ExprOrSpread {
+                spread: Some(dot3_token),
+                expr,
+            }) => Some(Pat::Rest(RestPat {
+                span: dot3_token,
+                dot3_token,
+                arg: Box::new(convert_expr_to_pat(expr)?),
+                type_ann: None,
+            })),
+
+            Some(ExprOrSpread { spread: None, expr }) => Some(convert_expr_to_pat(expr)?),
+        };
+
+        elems.push(pat);
+    }
+
+    Some(ArrayPat {
+        span: arr_lit.span,
+        elems,
+        optional: false,
+        type_ann: None,
+    })
+}
+
+/// Converts `{ a, b: c.d, e = 1, ...f }` used as an assignment target to an `ObjectPat`
+fn convert_obj_lit_to_pat(obj_lit: ObjectLit) -> Option<ObjectPat> {
+    let mut props = Vec::with_capacity(obj_lit.props.len());
+
+    for prop in obj_lit.props {
+        let pat_prop = match prop {
+            PropOrSpread::Spread(SpreadElement { dot3_token, expr }) => {
+                ObjectPatProp::Rest(RestPat {
+                    span: dot3_token,
+                    dot3_token,
+                    arg: Box::new(convert_expr_to_pat(expr)?),
+                    type_ann: None,
+                })
+            }
+
+            PropOrSpread::Prop(prop) => match *prop {
+                Prop::Shorthand(ident) => ObjectPatProp::Assign(AssignPatProp {
+                    span: ident.span,
+                    key: ident.into(),
+                    value: None,
+                }),
+
+                Prop::KeyValue(KeyValueProp { key, value }) => {
+                    ObjectPatProp::KeyValue(KeyValuePatProp {
+                        key,
+                        value: Box::new(convert_expr_to_pat(value)?),
+                    })
+                }
+
+                Prop::Assign(AssignProp { span, key, value }) => {
+                    ObjectPatProp::Assign(AssignPatProp {
+                        span,
+                        key: key.into(),
+                        value: Some(value),
+                    })
+                }
+
+                // Getters, setters and methods cannot be assigned to
+                Prop::Getter(_) | Prop::Setter(_) | Prop::Method(_) => return None,
+            },
+        };
+
+        props.push(pat_prop);
+    }
+
+    Some(ObjectPat {
+        span: obj_lit.span,
+        props,
+        optional: false,
+        type_ann: None,
+    })
 }
 
-fn convert_obj_lit_to_pat(_obj_lit: ObjectLit) -> ObjectPat {
-    todo!()
+/// Converts an element of an array or object literal used as an assignment target to a `Pat`
+fn convert_expr_to_pat(expr: Box<Expr>) -> Option<Pat> {
+    match *expr {
+        // Default value, e.g. `b = 1` in `[a, b = 1]`
+        Expr::Assign(AssignExpr {
+            span,
+            op: AssignOp::Assign,
+            left,
+            right,
+        }) => {
+            let left = match left {
+                AssignTarget::Simple(SimpleAssignTarget::Ident(binding_ident)) => {
+                    Pat::Ident(binding_ident)
+                }
+                AssignTarget::Simple(simple) => Pat::Expr(simple.into()),
+                AssignTarget::Pat(pat) => pat.into(),
+            };
+
+            Some(Pat::Assign(AssignPat {
+                span,
+                left: Box::new(left),
+                right,
+            }))
+        }
+
+        // Everything else must be a valid assignment target on its own
+        _ => match convert_expr_to_assign_target(expr)? {
+            AssignTarget::Simple(SimpleAssignTarget::Ident(binding_ident)) => {
+                Some(Pat::Ident(binding_ident))
+            }
+            AssignTarget::Simple(simple) => Some(Pat::Expr(simple.into())),
+            AssignTarget::Pat(pat) => Some(pat.into()),
+        },
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
+        error::{TemplateError, TemplateErrorKind, TransformError},
         template::{expr_transform::BindingsHelperTransform, js_builtins::JS_BUILTINS},
         test_utils::{parser::parse_javascript_expr, to_str},
         BindingsHelper, SetupBinding, TemplateScope,
@@ -1073,6 +1246,7 @@ mod tests {
         // const Const = 123
         // let Let = 123
         // const Reactive = reactive({})
+        // import { Imported } from './store'
         helper.setup_bindings.push(SetupBinding(
             FervidAtom::from("Ref"),
             BindingTypes::SetupRef,
@@ -1093,6 +1267,10 @@ mod tests {
             FervidAtom::from("Reactive"),
             BindingTypes::SetupReactiveConst,
         ));
+        helper.setup_bindings.push(SetupBinding(
+            FervidAtom::from("Imported"),
+            BindingTypes::Imported,
+        ));
 
         macro_rules! test {
             ($value: literal, $expected_value: literal, $expected_handler: literal) => {
@@ -1111,12 +1289,25 @@ mod tests {
                     $expected_handler
                 );
             };
+            ($value: literal, $expected_value: literal) => {
+                let mut v_model = VModelDirective {
+                    argument: None,
+                    value: js($value),
+                    update_handler: None,
+                    modifiers: vec![],
+                    span: DUMMY_SP,
+                };
+                let mut patch_hints = PatchHints::default();
+                helper.transform_v_model(&mut v_model, 0, &mut patch_hints);
+                assert_eq!(to_str(&v_model.value), $expected_value);
+                assert!(v_model.update_handler.is_none());
+            };
         }
 
         // Syntax is like that:
         // first element is `$expr` in `v-model="$expr"`;
         // second is the transformed value;
-        // third is the transformed update handler.
+        // third is the transformed update handler, none when the value cannot be reassigned.
 
         // DEV DIRECT
         test!("Ref", "$setup.Ref", "$event=>$setup.Ref=$event");
@@ -1124,16 +1315,20 @@ mod tests {
             "MaybeRef",
             "$setup.MaybeRef",
             "$event=>$setup.MaybeRef=$event"
-        ); // TODO must err?
-        test!("Const", "$setup.Const", "$event=>$setup.Const=$event"); // TODO must err
+        );
+        test!("Const", "$setup.Const");
         test!("Let", "$setup.Let", "$event=>$setup.Let=$event");
+        test!("Reactive", "$setup.Reactive");
         test!(
-            "Reactive",
-            "$setup.Reactive",
-            "$event=>$setup.Reactive=$event"
-        ); // TODO must err
+            "Imported",
+            "Imported",
+            "$event=>_isRef(Imported)?Imported.value=$event:null"
+        );
         test!("Unknown", "_ctx.Unknown", "$event=>_ctx.Unknown=$event");
 
+        // `Const` and `Reactive` cannot be reassigned
+        assert_v_model_on_const(&mut helper);
+
         // DEV INDIRECT
         test!("Ref.x", "$setup.Ref.x", "$event=>$setup.Ref.x=$event");
         test!(
@@ -1164,15 +1359,23 @@ mod tests {
             // This is the official spec, but it is inconsistent with the `v-on` transform
             "$event=>_isRef(MaybeRef)?MaybeRef.value=$event:null"
         );
-        test!("Const", "Const", "$event=>Const=$event"); // TODO must err
+        test!("Const", "Const");
         test!(
             "Let",
             "_unref(Let)",
             "$event=>_isRef(Let)?Let.value=$event:Let=$event"
         );
-        test!("Reactive", "Reactive", "$event=>Reactive=$event"); // TODO must err
+        test!("Reactive", "Reactive");
+        test!(
+            "Imported",
+            "_unref(Imported)",
+            "$event=>_isRef(Imported)?Imported.value=$event:null"
+        );
         test!("Unknown", "_ctx.Unknown", "$event=>_ctx.Unknown=$event");
 
+        // `Const` and `Reactive` cannot be reassigned
+        assert_v_model_on_const(&mut helper);
+
         // PROD INDIRECT
         test!("Ref.x", "Ref.value.x", "$event=>Ref.value.x=$event");
         test!(
@@ -1188,6 +1391,23 @@ mod tests {
             "_ctx.Unknown.x",
             "$event=>_ctx.Unknown.x=$event"
         );
+
+        // Members can be reassigned
+        assert!(helper.errors.is_empty());
+    }
+
+    fn assert_v_model_on_const(helper: &mut BindingsHelper) {
+        let errors: Vec<_> = helper.errors.drain(..).collect();
+        assert_eq!(2, errors.len());
+        for error in errors {
+            assert!(matches!(
+                error,
+                TransformError::TemplateError(TemplateError {
+                    kind: TemplateErrorKind::VModelOnConst,
+                    ..
+                })
+            ));
+        }
     }
 
     #[test]
@@ -1233,6 +1453,113 @@ mod tests {
         test!("\"string\"", "\"string\"");
     }
 
+    #[test]
+    fn it_transforms_destructuring_v_model() {
+        let mut helper = BindingsHelper::default();
+
+        macro_rules! test {
+            ($value: literal, $expected_handler: literal) => {
+                let mut v_model = VModelDirective {
+                    argument: None,
+                    value: js($value),
+                    update_handler: None,
+                    modifiers: vec![],
+                    span: DUMMY_SP,
+                };
+                helper.transform_v_model(&mut v_model, 0, &mut PatchHints::default());
+                assert_eq!(
+                    to_str(&v_model.update_handler.expect("Handler cannot be None")),
+                    $expected_handler
+                );
+            };
+        }
+
+        test!("[a, , ...b]", "$event=>[_ctx.a,,..._ctx.b]=$event");
+        test!("[a = 1, b.c]", "$event=>[_ctx.a=1,_ctx.b.c]=$event");
+        test!(
+            "{ a, b: c, [d]: e }",
+            "$event=>({a:_ctx.a,b:_ctx.c,[_ctx.d]:_ctx.e}=$event)"
+        );
+        assert!(helper.errors.is_empty());
+    }
+
+    #[test]
+    fn it_reports_malformed_v_model() {
+        let mut helper = BindingsHelper::default();
+
+        for value in ["foo()", "{ get a() { return 1 } }", "[a + 1]"] {
+            let mut v_model = VModelDirective {
+                argument: None,
+                value: js(value),
+                update_handler: None,
+                modifiers: vec![],
+                span: DUMMY_SP,
+            };
+            helper.transform_v_model(&mut v_model, 0, &mut PatchHints::default());
+            assert!(v_model.update_handler.is_none());
+        }
+
+        assert_eq!(3, helper.errors.len());
+    }
+
+    #[test]
+    fn it_reports_v_model_on_readonly_bindings() {
+        let mut helper = BindingsHelper::default();
+        helper
+            .setup_bindings
+            .push(SetupBinding(FervidAtom::from("prop"), BindingTypes::Props));
+        helper.setup_bindings.push(SetupBinding(
+            FervidAtom::from("constant"),
+            BindingTypes::LiteralConst,
+        ));
+        helper.setup_bindings.push(SetupBinding(
+            FervidAtom::from("imported"),
+            BindingTypes::Imported,
+        ));
+        helper.template_scopes.push(TemplateScope {
+            parent: 0,
+            variables: SmallVec::from_vec(vec![FervidAtom::from("item")]),
+        });
+
+        let mut check = |value: &str, expected: Option<TemplateErrorKind>| {
+            let mut v_model = VModelDirective {
+                argument: None,
+                value: js(value),
+                update_handler: None,
+                modifiers: vec![],
+                span: DUMMY_SP,
+            };
+            helper.transform_v_model(&mut v_model, 0, &mut PatchHints::default());
+
+            let kind = helper.errors.pop().map(|e| match e {
+                TransformError::TemplateError(e) => e.kind,
+                _ => panic!("Expected a template error"),
+            });
+            assert_eq!(expected, kind, "{value}");
+        };
+
+        check("prop", Some(TemplateErrorKind::VModelOnProps));
+        check("constant", Some(TemplateErrorKind::VModelOnConst));
+        check("imported", None);
+        check("item", Some(TemplateErrorKind::VModelOnScopeVariable));
+        check("item.name", None);
+        check("unknown", None);
+    }
+
+    #[test]
+    fn it_reports_props_aliased_in_non_inline_mode() {
+        let mut helper = BindingsHelper::default();
+        helper.setup_bindings.push(SetupBinding(
+            FervidAtom::from("aliased"),
+            BindingTypes::PropsAliased,
+        ));
+
+        let mut expr = js("aliased");
+        helper.transform_expr(&mut expr, 0);
+        assert_eq!(to_str(&expr), "aliased");
+        assert_eq!(1, helper.errors.len());
+    }
+
     #[test]
     fn it_works_with_template_scope_hierarchy() {
         let v_root = FervidAtom::from("root");
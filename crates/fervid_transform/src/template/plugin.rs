@@ -0,0 +1,469 @@
+//! User-defined transforms of the template, similar to `nodeTransforms` and `directiveTransforms`
+//! of the official compiler.
+
+use std::{fmt::Debug, sync::Arc};
+
+use fervid_core::{ElementNode, Node, VCustomDirective, VueDirectives};
+
+use crate::BindingsHelper;
+
+/// A plugin which transforms the template elements before they are processed by the compiler.
+///
+/// The plugin has access to [`BindingsHelper`], so it can check the bindings and the compilation mode,
+/// add imports using [`BindingsHelper::add_template_import`]
+/// and emit diagnostics by pushing a [`crate::error::PluginError`] to [`BindingsHelper::errors`].
+///
+/// The hooks are called while the template is transformed, parents before children,
+/// and before the elements are validated. The `template_scope` is the scope the element is in,
+/// i.e. the one with the `v-for` and `v-slot` variables of its ancestors
+/// (see [`BindingsHelper::is_template_local`]).
+///
+/// ## Example
+/// ```
+/// use fervid_core::{ElementNode, VCustomDirective};
+/// use fervid_transform::{template::TemplatePlugin, BindingsHelper};
+///
+/// /// Removes `v-test-id` in production
+/// struct StripTestId;
+///
+/// impl TemplatePlugin for StripTestId {
+///     fn name(&self) -> &str {
+///         "strip-test-id"
+///     }
+///
+///     fn transform_directive(
+///         &self,
+///         directive: &mut VCustomDirective,
+///         _element: &mut ElementNode,
+///         _template_scope: u32,
+///         bindings_helper: &mut BindingsHelper,
+///     ) -> bool {
+///         !(bindings_helper.is_prod && directive.name == "test-id")
+///     }
+/// }
+/// ```
+pub trait TemplatePlugin: Send + Sync {
+    /// Name of the plugin, e.g. for the diagnostics
+    fn name(&self) -> &str;
+
+    /// Called for every element before it is transformed, i.e. before its kind is recognized
+    /// and its expressions are resolved.
+    /// The element can be freely modified in place, e.g. `<Icon name="x">` into `<img :src="icon_x">`,
+    /// or replaced by another node or removed using the returned [`ElementTransform`].
+    fn transform_element(
+        &self,
+        _element: &mut ElementNode,
+        _template_scope: u32,
+        _bindings_helper: &mut BindingsHelper,
+    ) -> ElementTransform {
+        ElementTransform::Keep
+    }
+
+    /// Called for every custom directive (e.g. `v-t` in `<p v-t="'hello'">`) after [`TemplatePlugin::transform_element`].
+    /// The `element` is the one the directive belongs to, but without its custom directives.
+    /// Returns whether the directive should be kept on the element.
+    fn transform_directive(
+        &self,
+        _directive: &mut VCustomDirective,
+        _element: &mut ElementNode,
+        _template_scope: u32,
+        _bindings_helper: &mut BindingsHelper,
+    ) -> bool {
+        true
+    }
+}
+
+impl Debug for dyn TemplatePlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TemplatePlugin").field(&self.name()).finish()
+    }
+}
+
+/// What happens to an element after [`TemplatePlugin::transform_element`]
+#[derive(Debug, Default)]
+pub enum ElementTransform {
+    /// Keep the element (possibly modified in place)
+    #[default]
+    Keep,
+    /// Replace the element with another node, e.g. a text.
+    /// The following plugins only get the new node if it is an element
+    Replace(Node),
+    /// Remove the element together with its children
+    Remove,
+}
+
+/// Runs the [`BindingsHelper::template_plugins`] on the elements of `nodes`, but not on their children.
+/// The plugins are applied to every element in the order they were registered.
+/// `template_scope` is the scope the `nodes` are in.
+pub(crate) fn run_template_plugins(
+    nodes: &mut Vec<Node>,
+    template_scope: u32,
+    bindings_helper: &mut BindingsHelper,
+) {
+    if bindings_helper.template_plugins.is_empty() {
+        return;
+    }
+
+    // Plugins are detached for the time being to give them `&mut BindingsHelper`
+    let plugins = std::mem::take(&mut bindings_helper.template_plugins);
+
+    let old_nodes = std::mem::replace(nodes, Vec::with_capacity(nodes.len()));
+    nodes.extend(
+        old_nodes
+            .into_iter()
+            .filter_map(|node| transform_node(node, &plugins, template_scope, bindings_helper)),
+    );
+
+    bindings_helper.template_plugins = plugins;
+}
+
+/// Returns `None` when the node was removed
+fn transform_node(
+    mut node: Node,
+    plugins: &[Arc<dyn TemplatePlugin>],
+    template_scope: u32,
+    bindings_helper: &mut BindingsHelper,
+) -> Option<Node> {
+    for plugin in plugins.iter() {
+        let Node::Element(ref mut element_node) = node else {
+            break;
+        };
+
+        match plugin.transform_element(element_node, template_scope, bindings_helper) {
+            ElementTransform::Keep => {}
+            ElementTransform::Replace(new_node) => {
+                node = new_node;
+                continue;
+            }
+            ElementTransform::Remove => return None,
+        }
+
+        transform_custom_directives(
+            plugin.as_ref(),
+            element_node,
+            template_scope,
+            bindings_helper,
+        );
+    }
+
+    Some(node)
+}
+
+/// Runs [`TemplatePlugin::transform_directive`] on the custom directives of an element
+fn transform_custom_directives(
+    plugin: &dyn TemplatePlugin,
+    element_node: &mut ElementNode,
+    template_scope: u32,
+    bindings_helper: &mut BindingsHelper,
+) {
+    let Some(mut custom_directives) = element_node
+        .starting_tag
+        .directives
+        .as_mut()
+        .map(|directives| std::mem::take(&mut directives.custom))
+    else {
+        return;
+    };
+    custom_directives.retain_mut(|directive| {
+        plugin.transform_directive(directive, element_node, template_scope, bindings_helper)
+    });
+
+    // Put the kept directives back, before the ones added by the plugin (if any)
+    match element_node.starting_tag.directives {
+        Some(ref mut directives) => {
+            custom_directives.append(&mut directives.custom);
+            directives.custom = custom_directives;
+        }
+        None if !custom_directives.is_empty() => {
+            element_node.starting_tag.directives = Some(Box::new(VueDirectives {
+                custom: custom_directives,
+                ..Default::default()
+            }));
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use fervid_core::{
+        error::SeverityLevel, fervid_atom, AttributeOrBinding, ElementKind, FervidAtom, Node,
+        SfcTemplateBlock, StartingTag, StrOrExpr, VBindDirective,
+    };
+    use swc_core::{
+        common::DUMMY_SP,
+        ecma::ast::{Expr, Lit},
+    };
+
+    use super::*;
+    use crate::{
+        error::{PluginError, TransformError},
+        test_utils::{js, sfc, to_str},
+        TemplateImport, TransformSfcOptions,
+    };
+
+    /// `<p v-t="'hello'">` -> `<p>hello</p>`
+    struct I18n;
+
+    impl TemplatePlugin for I18n {
+        fn name(&self) -> &str {
+            "i18n"
+        }
+
+        fn transform_directive(
+            &self,
+            directive: &mut VCustomDirective,
+            element: &mut ElementNode,
+            _template_scope: u32,
+            bindings_helper: &mut BindingsHelper,
+        ) -> bool {
+            if directive.name != "t" {
+                return true;
+            }
+
+            match directive.value.as_deref() {
+                Some(Expr::Lit(Lit::Str(key))) => {
+                    element.children = vec![Node::Text(key.value.to_owned(), DUMMY_SP)];
+                }
+                _ => bindings_helper
+                    .errors
+                    .push(TransformError::PluginError(PluginError {
+                        span: element.span,
+                        plugin: self.name().to_string(),
+                        code: "I18N_DYNAMIC_KEY",
+                        message: "v-t only supports string literals".to_string(),
+                        severity: SeverityLevel::RecoverableError,
+                    })),
+            }
+
+            false
+        }
+    }
+
+    /// Removes `v-test-id` in PROD
+    struct StripTestId;
+
+    impl TemplatePlugin for StripTestId {
+        fn name(&self) -> &str {
+            "strip-test-id"
+        }
+
+        fn transform_directive(
+            &self,
+            directive: &mut VCustomDirective,
+            _element: &mut ElementNode,
+            _template_scope: u32,
+            bindings_helper: &mut BindingsHelper,
+        ) -> bool {
+            !(bindings_helper.is_prod && directive.name == "test-id")
+        }
+    }
+
+    /// `<Icon name="x">` -> `<img :src="icon_x">` and `import icon_x from '@/icons/x.svg'`
+    struct StaticIcons;
+
+    impl TemplatePlugin for StaticIcons {
+        fn name(&self) -> &str {
+            "static-icons"
+        }
+
+        fn transform_element(
+            &self,
+            element: &mut ElementNode,
+            _template_scope: u32,
+            bindings_helper: &mut BindingsHelper,
+        ) -> ElementTransform {
+            if element.starting_tag.tag_name != "Icon" {
+                return ElementTransform::Keep;
+            }
+
+            let Some(name) = element
+                .starting_tag
+                .attributes
+                .iter()
+                .find_map(|attr| match attr {
+                    AttributeOrBinding::RegularAttribute { name, value, .. } if name == "name" => {
+                        Some(value.to_owned())
+                    }
+                    _ => None,
+                })
+            else {
+                return ElementTransform::Keep;
+            };
+
+            let local = FervidAtom::from(format!("icon_{}", name));
+            bindings_helper.add_template_import(TemplateImport {
+                local: local.to_owned(),
+                imported: None,
+                source: FervidAtom::from(format!("@/icons/{}.svg", name)),
+            });
+
+            element.starting_tag = StartingTag {
+                tag_name: fervid_atom!("img"),
+                attributes: vec![AttributeOrBinding::VBind(VBindDirective {
+                    argument: Some(StrOrExpr::Str(fervid_atom!("src"))),
+                    value: js(&local),
+                    is_camel: false,
+                    is_prop: false,
+                    is_attr: false,
+                    span: DUMMY_SP,
+                })],
+                directives: None,
+            };
+
+            ElementTransform::Keep
+        }
+    }
+
+    /// `<Lorem>` -> `Lorem ipsum` and `<DevOnly>` is removed in PROD
+    struct Placeholders;
+
+    impl TemplatePlugin for Placeholders {
+        fn name(&self) -> &str {
+            "placeholders"
+        }
+
+        fn transform_element(
+            &self,
+            element: &mut ElementNode,
+            _template_scope: u32,
+            bindings_helper: &mut BindingsHelper,
+        ) -> ElementTransform {
+            match element.starting_tag.tag_name.as_str() {
+                "Lorem" => {
+                    ElementTransform::Replace(Node::Text(fervid_atom!("Lorem ipsum"), element.span))
+                }
+                "DevOnly" if bindings_helper.is_prod => ElementTransform::Remove,
+                _ => ElementTransform::Keep,
+            }
+        }
+    }
+
+    fn transform(
+        source: &str,
+        is_prod: bool,
+    ) -> (SfcTemplateBlock, BindingsHelper, Vec<TransformError>) {
+        let (result, errors) = sfc::transform(
+            source,
+            TransformSfcOptions {
+                is_prod,
+                template_plugins: vec![
+                    Arc::new(I18n),
+                    Arc::new(StripTestId),
+                    Arc::new(StaticIcons),
+                    Arc::new(Placeholders),
+                ],
+                ..Default::default()
+            },
+        );
+        let sfc_template = result.template_block.expect("Expected a template");
+        (sfc_template, result.bindings_helper, errors)
+    }
+
+    #[test]
+    fn it_transforms_directives() {
+        let source = r#"<template><p v-t="'hello'" v-test-id="'greeting'"></p></template>"#;
+
+        // DEV: only `v-t` is removed
+        let (sfc_template, bindings_helper, _) = transform(source, false);
+        let Node::Element(ref p) = sfc_template.roots[0] else {
+            panic!("root is not an element")
+        };
+        assert!(matches!(p.children.as_slice(), [Node::Text(text, _)] if text == "hello"));
+        let custom = &p.starting_tag.directives.as_ref().unwrap().custom;
+        assert_eq!(1, custom.len());
+        assert_eq!("test-id", custom[0].name.as_str());
+        assert!(bindings_helper
+            .custom_directives
+            .contains_key(&fervid_atom!("test-id")));
+        assert!(!bindings_helper
+            .custom_directives
+            .contains_key(&fervid_atom!("t")));
+
+        // PROD: both are removed
+        let (sfc_template, bindings_helper, errors) = transform(source, true);
+        let Node::Element(ref p) = sfc_template.roots[0] else {
+            panic!("root is not an element")
+        };
+        assert!(p
+            .starting_tag
+            .directives
+            .as_ref()
+            .unwrap()
+            .custom
+            .is_empty());
+        assert!(bindings_helper.custom_directives.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn it_replaces_elements_and_adds_imports() {
+        let (sfc_template, bindings_helper, _) = transform(
+            r#"<template><div><Icon name="close"></Icon><Icon name="close"></Icon></div></template>"#,
+            false,
+        );
+
+        let Node::Element(ref div) = sfc_template.roots[0] else {
+            panic!("root is not an element")
+        };
+        let Node::Element(ref img) = div.children[0] else {
+            panic!("child is not an element")
+        };
+        assert_eq!("img", img.starting_tag.tag_name.as_str());
+        assert!(matches!(img.kind, ElementKind::Element));
+        let [AttributeOrBinding::VBind(src)] = img.starting_tag.attributes.as_slice() else {
+            panic!("expected a single binding")
+        };
+        assert_eq!("icon_close", to_str(&src.value));
+
+        // The import is added only once and the component is not resolved
+        assert_eq!(
+            bindings_helper.template_imports,
+            vec![TemplateImport {
+                local: "icon_close".into(),
+                imported: None,
+                source: "@/icons/close.svg".into(),
+            }]
+        );
+        assert!(bindings_helper.components.is_empty());
+    }
+
+    #[test]
+    fn it_replaces_and_removes_nodes() {
+        let source =
+            "<template><div><Lorem></Lorem><DevOnly><Lorem></Lorem></DevOnly></div></template>";
+        let is_lorem = |node: &Node| matches!(node, Node::Text(text, _) if text == "Lorem ipsum");
+
+        // DEV: the children of the kept elements are transformed as well
+        let (sfc_template, _, _) = transform(source, false);
+        let Node::Element(ref div) = sfc_template.roots[0] else {
+            panic!("root is not an element")
+        };
+        let [lorem, Node::Element(dev_only)] = div.children.as_slice() else {
+            panic!("expected a text and an element")
+        };
+        assert!(is_lorem(lorem));
+        assert!(matches!(dev_only.children.as_slice(), [lorem] if is_lorem(lorem)));
+
+        // PROD: `<DevOnly>` is removed with its children
+        let (sfc_template, bindings_helper, _) = transform(source, true);
+        let Node::Element(ref div) = sfc_template.roots[0] else {
+            panic!("root is not an element")
+        };
+        assert!(matches!(div.children.as_slice(), [lorem] if is_lorem(lorem)));
+        assert!(bindings_helper.components.is_empty());
+    }
+
+    #[test]
+    fn it_reports_plugin_diagnostics() {
+        let (_, _, errors) = transform(r#"<template><p v-t="key"></p></template>"#, false);
+
+        let [TransformError::PluginError(error)] = errors.as_slice() else {
+            panic!("expected a plugin error")
+        };
+        assert_eq!("i18n", error.plugin);
+        assert_eq!("I18N_DYNAMIC_KEY", error.code);
+    }
+}
@@ -438,6 +438,8 @@ mod tests {
             bindings_helper,
             current_scope: 0,
             v_for_scope: false,
+            whitespace: Default::default(),
+            is_pre: false,
         }
     }
 }
@@ -327,6 +327,8 @@ mod tests {
             bindings_helper: &mut bindings_helper,
             current_scope: 0,
             v_for_scope: false,
+            whitespace: Default::default(),
+            is_pre: false,
         };
 
         macro_rules! test {
@@ -471,6 +473,8 @@ mod tests {
             bindings_helper: &mut bindings_helper,
             current_scope: 0,
             v_for_scope: false,
+            whitespace: Default::default(),
+            is_pre: false,
         };
 
         macro_rules! test {
@@ -560,6 +564,8 @@ mod tests {
             bindings_helper: &mut bindings_helper,
             current_scope: 0,
             v_for_scope: false,
+            whitespace: Default::default(),
+            is_pre: false,
         };
 
         macro_rules! test {
@@ -608,6 +614,8 @@ mod tests {
             bindings_helper: &mut bindings_helper,
             current_scope: 0,
             v_for_scope: false,
+            whitespace: Default::default(),
+            is_pre: false,
         };
 
         macro_rules! test {
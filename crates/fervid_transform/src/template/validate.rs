@@ -0,0 +1,408 @@
+use fervid_core::{
+    check_attribute_name, fervid_atom, AttributeOrBinding, BuiltinType, ElementKind, ElementNode,
+    FervidAtom, Interpolation, Node, SfcTemplateBlock, StartingTag, StrOrExpr,
+};
+use smallvec::SmallVec;
+use swc_core::common::Span;
+
+use crate::{
+    error::{TemplateError, TemplateErrorKind, TransformError},
+    TagPredicate,
+};
+
+use super::ast_transform::recognize_element_kind;
+
+/// Checks the template for semantic mistakes before it gets transformed.
+///
+/// The reported errors mirror the compile-time errors of the official compiler:
+/// - `v-else`/`v-else-if` without an adjacent `v-if` or `v-else-if`;
+/// - `key` on the children of `<template v-for>`;
+/// - `v-slot` on an element, or on a `<template>` which is not a direct child of a component;
+/// - duplicate slot names, mixed `v-slot` usage and extraneous default slot children.
+///
+/// The template itself is left as is.
+pub fn validate_template(
+    template: &SfcTemplateBlock,
+    is_custom_element_tag: Option<&TagPredicate>,
+    errors: &mut Vec<TransformError>,
+) {
+    fn validate_tree(
+        children: &[Node],
+        parent: Option<&StartingTag>,
+        is_custom_element_tag: Option<&TagPredicate>,
+        errors: &mut Vec<TransformError>,
+    ) {
+        validate_children(children, parent, is_custom_element_tag, errors);
+
+        for child in children {
+            if let Node::Element(element) = child {
+                validate_tree(
+                    &element.children,
+                    Some(&element.starting_tag),
+                    is_custom_element_tag,
+                    errors,
+                );
+            }
+        }
+    }
+
+    validate_tree(&template.roots, None, is_custom_element_tag, errors);
+}
+
+/// Same as [`validate_template`], but only checks the `children` of `parent` (or the roots when it is `None`)
+/// and not the children of `children`.
+/// This way the template is validated level by level, right after the template plugins are applied to a level
+pub(crate) fn validate_children(
+    children: &[Node],
+    parent: Option<&StartingTag>,
+    is_custom_element_tag: Option<&TagPredicate>,
+    errors: &mut Vec<TransformError>,
+) {
+    let mut validator = TemplateValidator {
+        is_custom_element_tag,
+        errors,
+    };
+
+    let Some(parent) = parent else {
+        validator.validate_children(children, false);
+        return;
+    };
+
+    let is_parent_component = is_slot_owner(recognize_element_kind(parent, is_custom_element_tag));
+    if let Some(ref directives) = parent.directives {
+        // `<template v-for><div :key></div></template>`
+        if parent.tag_name.eq("template") && directives.v_for.is_some() {
+            validator.validate_template_v_for_children(children);
+        }
+    }
+
+    if is_parent_component {
+        let has_own_slot = parent
+            .directives
+            .as_ref()
+            .map_or(false, |directives| directives.v_slot.is_some());
+        validator.validate_slots(children, has_own_slot);
+    }
+
+    validator.validate_children(children, is_parent_component);
+}
+
+struct TemplateValidator<'e> {
+    is_custom_element_tag: Option<&'e TagPredicate>,
+    errors: &'e mut Vec<TransformError>,
+}
+
+impl TemplateValidator<'_> {
+    fn validate_children(&mut self, children: &[Node], is_parent_component: bool) {
+        // Whether the previous meaningful sibling was `v-if` or `v-else-if`
+        let mut follows_if = false;
+
+        for child in children {
+            let element = match child {
+                Node::Element(element) => element,
+                // Whitespace and comments are allowed between the conditional nodes
+                Node::Text(text, _) if text.trim().is_empty() => continue,
+                Node::Comment(_, _) => continue,
+                _ => {
+                    follows_if = false;
+                    continue;
+                }
+            };
+
+            if let Some(ref directives) = element.starting_tag.directives {
+                let is_else = directives.v_else.is_some() || directives.v_else_if.is_some();
+                if is_else && !follows_if {
+                    self.report(element.span, TemplateErrorKind::VElseNoAdjacentIf);
+                }
+
+                follows_if = directives.v_if.is_some() || directives.v_else_if.is_some();
+            } else {
+                follows_if = false;
+            }
+
+            self.validate_element(element, is_parent_component);
+        }
+    }
+
+    /// Checks the element itself, its children are checked separately
+    fn validate_element(&mut self, element: &ElementNode, is_parent_component: bool) {
+        let Some(ref directives) = element.starting_tag.directives else {
+            return;
+        };
+
+        // `<div v-slot>` or `<div><template v-slot></template></div>`
+        if directives.v_slot.is_some() {
+            let is_allowed = if element.starting_tag.tag_name.eq("template") {
+                is_parent_component
+            } else {
+                is_slot_owner(recognize_element_kind(
+                    &element.starting_tag,
+                    self.is_custom_element_tag,
+                ))
+            };
+
+            if !is_allowed {
+                self.report(element.span, TemplateErrorKind::VSlotMisplaced);
+            }
+        }
+    }
+
+    /// Checks the children of `<template v-for>`
+    fn validate_template_v_for_children(&mut self, children: &[Node]) {
+        let child_key = children.iter().find_map(|child| match child {
+            Node::Element(child) => child
+                .starting_tag
+                .attributes
+                .iter()
+                .find(|attr| check_attribute_name(attr, "key")),
+            _ => None,
+        });
+
+        if let Some(key) = child_key {
+            self.report(
+                attribute_span(key),
+                TemplateErrorKind::VForTemplateKeyPlacement,
+            );
+        }
+    }
+
+    /// Checks the `<template v-slot>` children of a component
+    fn validate_slots(&mut self, children: &[Node], has_own_slot: bool) {
+        let mut seen_slot_names = SmallVec::<[FervidAtom; 4]>::new();
+        let mut has_template_slots = false;
+        let mut first_implicit_child: Option<Span> = None;
+
+        for child in children.iter() {
+            let (span, directives) = match child {
+                Node::Element(ElementNode {
+                    starting_tag:
+                        StartingTag {
+                            tag_name,
+                            directives: Some(directives),
+                            ..
+                        },
+                    span,
+                    ..
+                }) if tag_name.eq("template") && directives.v_slot.is_some() => (*span, directives),
+
+                // Implicit default slot content
+                Node::Text(text, _) if text.trim().is_empty() => continue,
+                Node::Comment(_, _) => continue,
+                Node::Text(_, span)
+                | Node::Element(ElementNode { span, .. })
+                | Node::Interpolation(Interpolation { span, .. }) => {
+                    first_implicit_child.get_or_insert(*span);
+                    continue;
+                }
+                Node::ConditionalSeq(_) => continue,
+            };
+            let Some(ref v_slot) = directives.v_slot else {
+                continue;
+            };
+
+            has_template_slots = true;
+
+            if has_own_slot {
+                self.report(span, TemplateErrorKind::VSlotMixedSlotUsage);
+            }
+
+            // Conditional and looped slots may legitimately share the name
+            let is_dynamic = directives.v_if.is_some()
+                || directives.v_else_if.is_some()
+                || directives.v_else.is_some()
+                || directives.v_for.is_some();
+            if is_dynamic {
+                continue;
+            }
+
+            let slot_name = match v_slot.slot_name {
+                None => fervid_atom!("default"),
+                Some(StrOrExpr::Str(ref name)) => name.to_owned(),
+                Some(StrOrExpr::Expr(_)) => continue,
+            };
+
+            if seen_slot_names.contains(&slot_name) {
+                self.report(span, TemplateErrorKind::VSlotDuplicateSlotNames);
+            } else {
+                seen_slot_names.push(slot_name);
+            }
+        }
+
+        if let Some(span) = first_implicit_child {
+            if has_template_slots && seen_slot_names.iter().any(|name| name.eq("default")) {
+                self.report(span, TemplateErrorKind::VSlotExtraneousDefaultSlotChildren);
+            }
+        }
+    }
+
+    fn report(&mut self, span: Span, kind: TemplateErrorKind) {
+        self.errors
+            .push(TransformError::TemplateError(TemplateError { span, kind }));
+    }
+}
+
+/// Whether an element of this kind can receive slots.
+/// Everything except plain elements and `<slot>` is a component in this sense.
+fn is_slot_owner(element_kind: ElementKind) -> bool {
+    !matches!(
+        element_kind,
+        ElementKind::Element | ElementKind::Builtin(BuiltinType::Slot)
+    )
+}
+
+fn attribute_span(attr: &AttributeOrBinding) -> Span {
+    match attr {
+        AttributeOrBinding::RegularAttribute { span, .. } => *span,
+        AttributeOrBinding::VBind(v_bind) => v_bind.span,
+        AttributeOrBinding::VOn(v_on) => v_on.span,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::sfc::transform;
+
+    use super::*;
+
+    /// Kinds of the template errors of an SFC, each with the source it points to
+    fn validate(source: &str) -> Vec<(TemplateErrorKind, &str)> {
+        let (_, errors) = transform(source, Default::default());
+
+        errors
+            .into_iter()
+            .map(|e| match e {
+                TransformError::TemplateError(e) => (
+                    e.kind,
+                    &source[e.span.lo.0 as usize - 1..e.span.hi.0 as usize - 1],
+                ),
+                _ => panic!("Expected a template error"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_reports_v_else_without_v_if() {
+        assert!(validate(
+            r#"<template><h1 v-if="foo"></h1> <!-- comment --> <h2 v-else-if="bar"></h2><h3 v-else></h3></template>"#
+        )
+        .is_empty());
+
+        assert_eq!(
+            vec![(
+                TemplateErrorKind::VElseNoAdjacentIf,
+                r#"<h2 v-else-if="bar"></h2>"#
+            )],
+            validate(r#"<template><h2 v-else-if="bar"></h2><h3 v-else></h3></template>"#)
+        );
+
+        assert_eq!(
+            vec![(TemplateErrorKind::VElseNoAdjacentIf, "<h3 v-else></h3>")],
+            validate(r#"<template><h1 v-if="foo"></h1>text<h3 v-else></h3></template>"#)
+        );
+
+        // Nested
+        assert_eq!(
+            vec![(TemplateErrorKind::VElseNoAdjacentIf, "<h3 v-else></h3>")],
+            validate("<template><div><h3 v-else></h3></div></template>")
+        );
+    }
+
+    #[test]
+    fn it_reports_key_on_template_v_for_children() {
+        assert_eq!(
+            vec![(
+                TemplateErrorKind::VForTemplateKeyPlacement,
+                r#":key="item.id""#
+            )],
+            validate(
+                r#"<template><template v-for="item in list"><div :key="item.id"></div></template></template>"#
+            )
+        );
+
+        assert!(validate(
+            r#"<template><template v-for="item in list" :key="item.id"><div></div></template></template>"#
+        )
+        .is_empty());
+
+        assert!(validate(
+            r#"<template><div v-for="item in list"><span :key="item.id"></span></div></template>"#
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn it_reports_misplaced_v_slot() {
+        assert_eq!(
+            vec![(TemplateErrorKind::VSlotMisplaced, "<div v-slot></div>")],
+            validate("<template><div v-slot></div></template>")
+        );
+
+        assert_eq!(
+            vec![(
+                TemplateErrorKind::VSlotMisplaced,
+                "<template v-slot>slot</template>"
+            )],
+            validate("<template><div><template v-slot>slot</template></div></template>")
+        );
+
+        assert!(validate(
+            "<template><comp v-slot></comp><comp><template v-slot>slot</template></comp></template>"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn it_reports_duplicate_and_extraneous_slots() {
+        assert_eq!(
+            vec![
+                (
+                    TemplateErrorKind::VSlotDuplicateSlotNames,
+                    "<template v-slot:default>slot</template>"
+                ),
+                (
+                    TemplateErrorKind::VSlotDuplicateSlotNames,
+                    "<template v-slot:foo>slot</template>"
+                ),
+            ],
+            validate(
+                r#"<template>
+  <comp>
+    <template v-slot:foo>slot</template>
+    <template v-slot>slot</template>
+    <template v-slot:default>slot</template>
+    <template v-slot:foo>slot</template>
+  </comp>
+</template>"#
+            )
+        );
+
+        assert_eq!(
+            vec![(
+                TemplateErrorKind::VSlotExtraneousDefaultSlotChildren,
+                "not hi"
+            )],
+            validate(
+                "<template><comp><template v-slot:default>slot</template><!-- comment -->not hi</comp></template>"
+            )
+        );
+
+        // Implicit default slot next to a named one is fine
+        assert!(validate(
+            "<template><comp><template v-slot:foo>slot</template>hi</comp></template>"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn it_reports_mixed_slot_usage() {
+        assert_eq!(
+            vec![(
+                TemplateErrorKind::VSlotMixedSlotUsage,
+                "<template v-slot:named>slot</template>"
+            )],
+            validate(
+                r#"<template><comp v-slot="{ foo }"><template v-slot:named>slot</template></comp></template>"#
+            )
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub mod parser;
+pub mod sfc;
 
 use swc_core::common::{sync::Lrc, SourceMap};
 use swc_core::ecma::ast::Expr;
@@ -0,0 +1,27 @@
+//! Builds the IR from `.vue` source, the same way the compiler does
+
+use fervid_core::SfcDescriptor;
+use fervid_parser::SfcParser;
+
+use crate::{error::TransformError, transform_sfc, TransformSfcOptions, TransformSfcResult};
+
+/// Parses an SFC, panics on parsing errors
+pub fn parse_sfc(source: &str) -> SfcDescriptor {
+    let mut errors = Vec::new();
+    let sfc = SfcParser::new(source, &mut errors)
+        .parse_sfc()
+        .expect("parse_sfc expects the input to be parseable");
+    assert!(errors.is_empty(), "{:?}", errors);
+    sfc
+}
+
+/// Parses and transforms an SFC, returns the result with the errors of the transformation
+pub fn transform(
+    source: &str,
+    options: TransformSfcOptions,
+) -> (TransformSfcResult, Vec<TransformError>) {
+    let mut errors = Vec::new();
+    let result = transform_sfc(parse_sfc(source), options, &mut errors);
+    (result, errors)
+}
+
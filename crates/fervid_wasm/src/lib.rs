@@ -4,7 +4,14 @@ extern crate wee_alloc;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-use fervid::{compile, CompileOptions, CompileResult};
+use fervid::{
+    compile,
+    error::{generate_code_frame, ErrorCode, Severity},
+    errors::CompileError,
+    parse, CompileEmittedAsset, CompileEmittedStyle, CompileOptions, CompileResult, ParseOptions,
+    ParsedSfcBlock, TagPredicate, WhitespaceMode,
+};
+use fervid_transform::PropsDestructureConfig;
 use swc_core::common::{sync::Lrc, SourceMap, Spanned};
 use wasm_bindgen::prelude::*;
 
@@ -16,63 +23,405 @@ pub struct WasmCompileError {
     pub start_column: usize,
     pub end_column: usize,
     pub message: String,
+    /// Stable error code, e.g. `X_INVALID_END_TAG`
+    pub code: String,
+    /// Either `error` or `warning`
+    pub severity: String,
+    /// Source code excerpt with the error location underlined
+    pub frame: Option<String>,
+}
+
+/// Options of `compile_sync`, same as `FervidCompileOptions` and `FervidJsCompilerOptions` of `@fervid/napi`
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Default)]
+pub struct WasmCompileOptions {
+    /// Filename is used for automatic component name inference and self-referential imports
+    pub filename: String,
+    /// Scope ID for prefixing injected CSS variables
+    pub id: String,
+    /// Apply production optimizations. Default: false
+    pub is_prod: Option<bool>,
+    /// Is the currently compiled file a custom element
+    pub is_custom_element: Option<bool>,
+    /// Enable SSR. Default: false
+    pub ssr: Option<bool>,
+    /// Generate a const instead of default export
+    pub gen_default_as: Option<String>,
+    /// Enable, disable or error on props destructure: `true`, `false` or `'error'`
+    pub props_destructure: JsValue,
+    /// Whether setup bindings need to be returned in `setup_bindings`
+    pub output_setup_bindings: Option<bool>,
+    /// Generate source maps
+    pub source_map: Option<bool>,
+    /// JSON-serialized source map of the input, e.g. when the SFC was preprocessed.
+    /// The resulting source map will point to the original file.
+    pub in_source_map: Option<String>,
+    /// Interpolation delimiters, e.g. `['${', '}']`. Default: `['{{', '}}']`
+    pub delimiters: Option<Vec<String>>,
+    /// How whitespace in the template is handled, `'condense'` or `'preserve'`. Default: `'condense'`
+    pub whitespace: Option<String>,
+    /// Keep the HTML comments of the template. Default: `true` in development, `false` in production
+    pub comments: Option<bool>,
+    /// Prefixes of the tags which are native custom elements, e.g. `['ion-']`
+    pub custom_element_prefixes: Option<Vec<String>>,
+    /// Regular expressions in Rust syntax matching the tags which are native custom elements
+    pub custom_element_patterns: Option<Vec<String>>,
+}
+
+#[wasm_bindgen]
+impl WasmCompileOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new(filename: String, id: String) -> WasmCompileOptions {
+        WasmCompileOptions {
+            filename,
+            id,
+            ..Default::default()
+        }
+    }
 }
 
 #[wasm_bindgen(getter_with_clone)]
 pub struct WasmCompileResult {
     pub code: String,
+    pub file_hash: String,
     pub errors: Vec<WasmCompileError>,
+    pub styles: Vec<WasmStyle>,
+    pub custom_blocks: Vec<WasmCustomBlock>,
+    pub source_map: Option<String>,
+    /// Only filled when `output_setup_bindings` is enabled
+    pub setup_bindings: Vec<WasmSetupBinding>,
+    /// Whether the code is TypeScript, i.e. the SFC has `<script lang="ts">`
+    pub is_ts: bool,
+    /// Files the compilation depended on, e.g. the ones the types of `defineProps` were imported from
+    pub dependencies: Vec<String>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct WasmStyle {
+    pub code: String,
+    pub is_compiled: bool,
+    pub lang: String,
+    pub is_scoped: bool,
+    /// Source map pointing into the SFC, only present for compiled styles
+    pub source_map: Option<String>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct WasmCustomBlock {
+    pub content: String,
+    pub lo: u32,
+    pub hi: u32,
+    pub tag_name: String,
 }
 
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct WasmSetupBinding {
+    pub name: String,
+    pub binding_type: BindingTypes,
+}
+
+/// Copy of `fervid_core::BindingTypes`, same as the one of `@fervid/napi`
 #[wasm_bindgen]
-pub fn compile_sync(source: &str, is_prod: Option<bool>) -> Result<WasmCompileResult, String> {
-    // compile_sync_naive(source, is_prod.unwrap_or(false))
+#[derive(Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum BindingTypes {
+    DATA,
+    PROPS,
+    PROPS_ALIASED,
+    SETUP_LET,
+    SETUP_CONST,
+    SETUP_REACTIVE_CONST,
+    SETUP_MAYBE_REF,
+    SETUP_REF,
+    OPTIONS,
+    LITERAL_CONST,
+    COMPONENT,
+    IMPORTED,
+    TEMPLATE_LOCAL,
+    JS_GLOBAL,
+    UNRESOLVED,
+}
+
+/// Blocks of an SFC, see `parse_sync`
+#[wasm_bindgen(getter_with_clone)]
+pub struct WasmParseResult {
+    pub template: Option<WasmSfcBlock>,
+    pub script: Option<WasmSfcBlock>,
+    pub script_setup: Option<WasmSfcBlock>,
+    pub styles: Vec<WasmSfcBlock>,
+    pub custom_blocks: Vec<WasmSfcBlock>,
+    pub errors: Vec<WasmCompileError>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct WasmSfcBlock {
+    /// Tag name of the block, e.g. `template`, `script`, `style` or `i18n`
+    pub block_type: String,
+    /// Raw content between the starting and the ending tags
+    pub content: String,
+    /// Attributes as written, attributes without a value (e.g. `scoped`) have an empty one
+    pub attrs: Vec<WasmSfcBlockAttribute>,
+    pub lang: Option<String>,
+    pub src: Option<String>,
+    pub setup: bool,
+    pub scoped: bool,
+    pub module: Option<String>,
+    /// 0-based byte offsets, lines and columns of `content`
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub start_line_number: usize,
+    pub end_line_number: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct WasmSfcBlockAttribute {
+    pub name: String,
+    pub value: String,
+}
+
+#[wasm_bindgen]
+pub fn compile_sync(
+    source: &str,
+    options: &WasmCompileOptions,
+) -> Result<WasmCompileResult, String> {
+    let delimiters = match options.delimiters.as_deref() {
+        Some([start, end]) => Some((start.as_str().into(), end.as_str().into())),
+        Some(_) => return Err("`delimiters` must contain exactly two strings".to_owned()),
+        None => None,
+    };
+
+    let whitespace = match options.whitespace.as_deref() {
+        Some("condense") => Some(WhitespaceMode::Condense),
+        Some("preserve") => Some(WhitespaceMode::Preserve),
+        Some(_) => return Err("`whitespace` must be either 'condense' or 'preserve'".to_owned()),
+        None => None,
+    };
+
+    let props_destructure = match options.props_destructure.as_bool() {
+        Some(true) => Some(PropsDestructureConfig::True),
+        Some(false) => Some(PropsDestructureConfig::False),
+        None if options.props_destructure.as_string().as_deref() == Some("error") => {
+            Some(PropsDestructureConfig::Error)
+        }
+        None => None,
+    };
+
+    // Tags which are custom elements rather than components
+    let is_custom_element_tag =
+        if options.custom_element_prefixes.is_some() || options.custom_element_patterns.is_some() {
+            Some(
+                TagPredicate::from_prefixes_and_patterns(
+                    options
+                        .custom_element_prefixes
+                        .to_owned()
+                        .unwrap_or_default(),
+                    options
+                        .custom_element_patterns
+                        .as_deref()
+                        .unwrap_or_default(),
+                )
+                .map_err(|e| e.to_string())?,
+            )
+        } else {
+            None
+        };
+
     let compile_result = compile(
         source,
         CompileOptions {
-            filename: "anonymous.vue".into(),
-            id: "".into(),
-            is_prod,
-            is_custom_element: Some(false),
-            props_destructure: None,
-            ssr: Some(false),
-            gen_default_as: None,
-            source_map: None,
+            filename: options.filename.as_str().into(),
+            id: options.id.as_str().into(),
+            is_prod: options.is_prod,
+            is_custom_element: options.is_custom_element,
+            props_destructure,
+            ssr: options.ssr,
+            gen_default_as: options.gen_default_as.as_deref().map(Into::into),
+            source_map: options.source_map,
+            in_source_map: options.in_source_map.as_deref().map(Into::into),
+            delimiters,
+            whitespace,
+            is_custom_element_tag,
+            comments: options.comments,
+            ..Default::default()
         },
     );
 
     match compile_result {
-        Ok(compiled) => Ok(convert_compile_result(compiled, source)),
+        Ok(compiled) => Ok(convert_compile_result(
+            compiled,
+            source,
+            options.output_setup_bindings.unwrap_or(false),
+        )),
 
         Err(e) => Err(e.to_string()),
     }
 }
 
-fn convert_compile_result(compiled: CompileResult, source: &str) -> WasmCompileResult {
-    let code = compiled.code;
+/// Splits an SFC into its blocks without compiling it, similar to `parse` of `@vue/compiler-sfc`
+#[wasm_bindgen]
+pub fn parse_sync(source: &str, ignore_empty: Option<bool>) -> Result<WasmParseResult, String> {
+    let parsed = parse(
+        source,
+        ParseOptions {
+            ignore_empty,
+            ..Default::default()
+        },
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(WasmParseResult {
+        template: parsed.template.map(convert_sfc_block),
+        script: parsed.script.map(convert_sfc_block),
+        script_setup: parsed.script_setup.map(convert_sfc_block),
+        styles: parsed.styles.into_iter().map(convert_sfc_block).collect(),
+        custom_blocks: parsed
+            .custom_blocks
+            .into_iter()
+            .map(convert_sfc_block)
+            .collect(),
+        errors: convert_errors(parsed.errors, source),
+    })
+}
+
+fn convert_compile_result(
+    compiled: CompileResult,
+    source: &str,
+    output_setup_bindings: bool,
+) -> WasmCompileResult {
+    let setup_bindings = if output_setup_bindings {
+        compiled
+            .setup_bindings
+            .into_iter()
+            .map(|binding| WasmSetupBinding {
+                name: binding.0.to_string(),
+                binding_type: binding.1.into(),
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    WasmCompileResult {
+        code: compiled.code,
+        file_hash: compiled.file_hash,
+        errors: convert_errors(compiled.errors, source),
+        styles: compiled.styles.into_iter().map(From::from).collect(),
+        custom_blocks: compiled.other_assets.into_iter().map(From::from).collect(),
+        source_map: compiled.source_map,
+        setup_bindings,
+        is_ts: compiled.is_ts,
+        dependencies: compiled.dependencies,
+    }
+}
+
+impl From<CompileEmittedStyle> for WasmStyle {
+    fn from(value: CompileEmittedStyle) -> Self {
+        WasmStyle {
+            code: value.code,
+            is_compiled: value.is_compiled,
+            lang: value.lang,
+            is_scoped: value.is_scoped,
+            source_map: value.source_map,
+        }
+    }
+}
+
+impl From<CompileEmittedAsset> for WasmCustomBlock {
+    fn from(value: CompileEmittedAsset) -> Self {
+        WasmCustomBlock {
+            content: value.content,
+            lo: value.lo,
+            hi: value.hi,
+            tag_name: value.tag_name,
+        }
+    }
+}
+
+impl From<fervid::BindingTypes> for BindingTypes {
+    fn from(value: fervid::BindingTypes) -> Self {
+        match value {
+            fervid::BindingTypes::Data => BindingTypes::DATA,
+            fervid::BindingTypes::Props => BindingTypes::PROPS,
+            fervid::BindingTypes::PropsAliased => BindingTypes::PROPS_ALIASED,
+            fervid::BindingTypes::SetupLet => BindingTypes::SETUP_LET,
+            fervid::BindingTypes::SetupConst => BindingTypes::SETUP_CONST,
+            fervid::BindingTypes::SetupReactiveConst => BindingTypes::SETUP_REACTIVE_CONST,
+            fervid::BindingTypes::SetupMaybeRef => BindingTypes::SETUP_MAYBE_REF,
+            fervid::BindingTypes::SetupRef => BindingTypes::SETUP_REF,
+            fervid::BindingTypes::Options => BindingTypes::OPTIONS,
+            fervid::BindingTypes::LiteralConst => BindingTypes::LITERAL_CONST,
+            fervid::BindingTypes::Component => BindingTypes::COMPONENT,
+            fervid::BindingTypes::Imported => BindingTypes::IMPORTED,
+            fervid::BindingTypes::TemplateLocal => BindingTypes::TEMPLATE_LOCAL,
+            fervid::BindingTypes::JsGlobal => BindingTypes::JS_GLOBAL,
+            fervid::BindingTypes::Unresolved => BindingTypes::UNRESOLVED,
+        }
+    }
+}
+
+fn convert_sfc_block(block: ParsedSfcBlock) -> WasmSfcBlock {
+    WasmSfcBlock {
+        block_type: block.block_type,
+        content: block.content,
+        attrs: block
+            .attrs
+            .into_iter()
+            .map(|(name, value)| WasmSfcBlockAttribute { name, value })
+            .collect(),
+        lang: block.lang,
+        src: block.src,
+        setup: block.setup,
+        scoped: block.scoped,
+        module: block.module,
+        start_offset: block.loc.start.offset,
+        end_offset: block.loc.end.offset,
+        start_line_number: block.loc.start.line,
+        end_line_number: block.loc.end.line,
+        start_column: block.loc.start.column,
+        end_column: block.loc.end.column,
+    }
+}
 
+fn convert_errors(compile_errors: Vec<CompileError>, source: &str) -> Vec<WasmCompileError> {
     let mut errors = vec![];
-    if !compiled.errors.is_empty() {
+    if !compile_errors.is_empty() {
         let cm: Lrc<SourceMap> = Default::default();
         cm.new_source_file(
             Lrc::new(swc_core::common::FileName::Anon),
             source.to_owned(),
         );
-        errors.reserve(compiled.errors.len());
+        errors.reserve(compile_errors.len());
 
-        for error in compiled.errors {
+        for error in compile_errors {
             let span = error.span();
             let start = cm.lookup_char_pos(span.lo);
             let end = cm.lookup_char_pos(span.hi);
+            let frame = (!span.is_dummy()).then(|| {
+                generate_code_frame(
+                    source,
+                    (span.lo.0 as usize).saturating_sub(1),
+                    (span.hi.0 as usize).saturating_sub(1),
+                )
+            });
             errors.push(WasmCompileError {
                 start_line_number: start.line,
                 end_line_number: end.line,
                 start_column: start.col.0,
                 end_column: end.col.0,
                 message: error.to_string(),
+                code: error.error_code().to_owned(),
+                severity: error.get_severity().as_str().to_owned(),
+                frame,
             })
         }
     }
 
-    WasmCompileResult { code, errors }
+    errors
 }
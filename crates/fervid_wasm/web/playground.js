@@ -1,4 +1,4 @@
-import init, { compile_sync } from '../pkg/fervid_wasm.js'
+import init, { compile_sync, WasmCompileOptions } from '../pkg/fervid_wasm.js'
 import * as monaco from 'https://cdn.jsdelivr.net/npm/monaco-editor@0.41.0/esm/vs/editor/editor.main.js/+esm'
 
 const INITIAL =
@@ -76,8 +76,11 @@ function mountEditor () {
 }
 
 function compileAndTime () {
+    const options = new WasmCompileOptions('anonymous.vue', '')
+    options.is_prod = is_prod
+
     const start = performance.now()
-    const result = compile_sync(value, is_prod)
+    const result = compile_sync(value, options)
     const end = performance.now()
 
     outputTimeElement.textContent = `${((end - start) * 1000).toFixed(0)}µs ${isTimeInitial ? '(cold)' : ''}`
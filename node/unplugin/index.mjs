@@ -14,6 +14,13 @@ const unplugin = createUnplugin(({ mode = 'production', hmr = false }, meta) =>
   /** @type {VirtualModulesPlugin | undefined} */
   let vfs = undefined
 
+  /**
+   * Blocks of the compiled SFCs by their ids, e.g. `/src/App.vue?vue&type=style&index=0&lang.css`.
+   * Not used for webpack, which gets the blocks from Virtual File System.
+   * @type {Map<string, string>}
+   */
+  const blocks = new Map()
+
   /**
    * Adds a file to Virtual File System.
    * This is used for additional assets like `<style>`s, custom blocks, etc.
@@ -26,6 +33,35 @@ const unplugin = createUnplugin(({ mode = 'production', hmr = false }, meta) =>
     }
   }
 
+  /**
+   * Compiles an SFC importing its blocks with the same ids as the other integrations of fervid.
+   * @param {string} code
+   * @param {string} id
+   */
+  function transformWithPrelude(code, id) {
+    const compileResult = compiler.compileSync(code, {
+      id: id,
+      filename: id,
+      genDefaultAs: '_sfc_main',
+    })
+
+    for (const style of compileResult.styles) {
+      blocks.set(id + style.query, style.code)
+    }
+
+    // Custom blocks are functions applied to the component, e.g. by `@intlify/unplugin-vue-i18n`
+    let customBlocks = ''
+    compileResult.customBlocks.forEach((block, idx) => {
+      blocks.set(id + block.query, block.content)
+      customBlocks += `\nif (typeof _sfc_block${idx} === "function") _sfc_block${idx}(_sfc_main)`
+    })
+
+    const base = compileResult.prelude + compileResult.code + customBlocks
+    const hmr = shouldAddHmr ? viteHmr(id) : ''
+
+    return base + hmr + '\nexport default _sfc_main'
+  }
+
   return {
     name: 'unplugin-fervid',
 
@@ -33,7 +69,26 @@ const unplugin = createUnplugin(({ mode = 'production', hmr = false }, meta) =>
       return id.endsWith('.vue')
     },
 
+    resolveId(id) {
+      if (blocks.has(id)) {
+        return id
+      }
+    },
+
+    loadInclude(id) {
+      return blocks.has(id)
+    },
+
+    load(id) {
+      return blocks.get(id)
+    },
+
     transform(code, id) {
+      // Webpack imports the blocks as files from Virtual File System, so they can't have a query
+      if (bundler !== 'webpack') {
+        return transformWithPrelude(code, id)
+      }
+
       const compileResult = compiler.compileSync(code, {
         id: id,
         filename: id,
@@ -56,11 +111,7 @@ const unplugin = createUnplugin(({ mode = 'production', hmr = false }, meta) =>
         return base
       }
 
-      const hmr = bundler === 'webpack'
-        ? webpackHmr(id)
-        : viteHmr(id)
-
-      return base + hmr
+      return base + webpackHmr(id)
     },
 
     webpack(compiler) {